@@ -0,0 +1,421 @@
+// 无头地图生成工具
+//
+// 不启动游戏窗口，直接用地形规则和瓦片集运行 WFC，
+// 为每个种子输出 ASCII 瓦片类型网格和由瓦片图集拼合的 PNG 预览。
+//
+// 用法：cargo run --bin mapgen -- [--seed N] [--count N] [--width N] [--height N] [--out DIR]
+//       cargo run --bin mapgen -- stats [--seed N] [--count N] [--width N] [--height N] [--out DIR]
+//       cargo run --bin mapgen -- validate
+
+use bevy::prelude::IVec2;
+use bevy_game::config::map::{GRID_X, GRID_Y, TILE_SIZE};
+use bevy_game::map::assets::SpawnableAsset;
+use bevy_game::map::biome::BiomeMap;
+use bevy_game::map::chunk::chunk_seed;
+use bevy_game::map::generate::{
+    ChunkData, RULES_FILE, TILESET_FILE, compile_biome_rules, compile_rules, generate_grid,
+    map_grid,
+};
+use bevy_game::map::layout::MapLayout;
+use bevy_game::map::rules::{TerrainRules, build_world};
+use bevy_game::map::stats::{BatchStats, MapStats};
+use bevy_game::map::structure::{StructureLayout, resolve_structures};
+use bevy_game::map::tilemap::TilesetDefinition;
+use bevy_game::map::validate::validate_rules;
+use bevy_procedural_tilemaps::prelude::Cartesian3D;
+use bevy_procedural_tilemaps::proc_gen::generator::rules::Rules;
+use image::{RgbaImage, imageops};
+use serde::de::DeserializeOwned;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Arc;
+
+/// 资源目录（与游戏的 AssetPlugin 设置一致）
+const ASSETS_DIR: &str = "src/assets";
+
+/// 用法说明
+const USAGE: &str = "Usage: mapgen [--seed N] [--count N] [--width N] [--height N] [--out DIR]
+       mapgen stats [--seed N] [--count N] [--width N] [--height N] [--out DIR]
+       mapgen validate
+
+  --seed N     first world seed (default: random)
+  --count N    number of consecutive seeds to generate (default: 1)
+  --width N    grid width in tiles (default: chunk width)
+  --height N   grid height in tiles (default: chunk height)
+  --out DIR    output directory (default: mapgen_out)
+
+With the default size, the output matches the spawn chunk the game
+generates for the same seed.
+
+The stats subcommand generates the same maps without images and writes
+stats_maps.csv, stats_models.csv and stats.json with model and tile type
+frequencies, walkable ratio, water coverage and plants per map.
+
+The validate subcommand checks the terrain rules against the tileset
+and prints a report instead of generating maps.";
+
+/// 命令行选项
+struct Options {
+    /// 第一个世界种子
+    seed: u64,
+    /// 生成的地图数量
+    count: u64,
+    /// 网格宽度
+    width: u32,
+    /// 网格高度
+    height: u32,
+    /// 输出目录
+    out: PathBuf,
+}
+
+/// 解析命令行参数
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        seed: rand::random(),
+        count: 1,
+        width: GRID_X,
+        height: GRID_Y,
+        out: PathBuf::from("mapgen_out"),
+    };
+
+    while let Some(arg) = args.next() {
+        // 支持 `--flag value` 和 `--flag=value` 两种写法
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+            None => (arg, None),
+        };
+        if flag == "--help" || flag == "-h" {
+            return Err(String::new());
+        }
+        let value = inline_value
+            .or_else(|| args.next())
+            .ok_or_else(|| format!("missing value for {flag}"))?;
+
+        match flag.as_str() {
+            "--seed" => options.seed = parse_number(&flag, &value)?,
+            "--count" => options.count = parse_number(&flag, &value)?,
+            "--width" => options.width = parse_number(&flag, &value)?,
+            "--height" => options.height = parse_number(&flag, &value)?,
+            "--out" => options.out = PathBuf::from(value),
+            _ => return Err(format!("unknown argument {flag}")),
+        }
+    }
+
+    if options.width == 0 || options.height == 0 {
+        return Err("width and height must be greater than 0".to_string());
+    }
+    Ok(options)
+}
+
+/// 解析数字参数
+fn parse_number<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value '{value}' for {flag}"))
+}
+
+/// 读取并解析资源目录中的 RON 文件
+fn read_ron<T: DeserializeOwned>(path: &str) -> Result<T, String> {
+    let full_path = Path::new(ASSETS_DIR).join(path);
+    let contents = fs::read_to_string(&full_path)
+        .map_err(|err| format!("failed to read {}: {err}", full_path.display()))?;
+    ron::from_str(&contents)
+        .map_err(|err| format!("failed to parse {}: {err}", full_path.display()))
+}
+
+/// 加载瓦片集中的所有瓦片图图像
+fn load_atlases(tileset: &TilesetDefinition) -> Result<Vec<RgbaImage>, String> {
+    tileset
+        .images
+        .iter()
+        .map(|tileset_image| {
+            let path = Path::new(ASSETS_DIR).join(&tileset_image.path);
+            image::open(&path)
+                .map(|image| image.to_rgba8())
+                .map_err(|err| format!("failed to load {}: {err}", path.display()))
+        })
+        .collect()
+}
+
+/// 将地图布局渲染为 PNG 图像
+///
+/// 按绘制顺序（z 值从小到大）把每个精灵从瓦片图集中裁剪出来，
+/// 以原始像素大小叠加到画布上，精灵中心对齐其世界坐标
+fn render_layout(
+    layout: &MapLayout,
+    tileset: &TilesetDefinition,
+    atlases: &[RgbaImage],
+) -> Result<RgbaImage, String> {
+    // 世界单位到像素的缩放（一个网格 = 一个瓦片的像素大小）
+    let scale = tileset.tile_width as f32 / TILE_SIZE;
+    let canvas_width = layout.width * tileset.tile_width;
+    let canvas_height = layout.height * tileset.tile_height;
+    let mut canvas = RgbaImage::new(canvas_width, canvas_height);
+
+    let mut sprites: Vec<_> = layout.sprites.iter().collect();
+    sprites.sort_by(|a, b| a.translation.z.total_cmp(&b.translation.z));
+
+    for sprite in sprites {
+        let sprite_ref = tileset
+            .sprite_index(&sprite.sprite_name)
+            .ok_or_else(|| format!("unknown atlas sprite '{}'", sprite.sprite_name))?;
+        let tileset_image = &tileset.images[sprite_ref.image];
+        let rect = tileset.sprite_rect(tileset_image, sprite_ref.index);
+        let size = rect.size();
+        let region = imageops::crop_imm(
+            &atlases[sprite_ref.image],
+            rect.min.x,
+            rect.min.y,
+            size.x,
+            size.y,
+        )
+        .to_image();
+
+        // 世界坐标 y 轴向上，图像 y 轴向下
+        let center_x = sprite.translation.x * scale;
+        let center_y = canvas_height as f32 - sprite.translation.y * scale;
+        let left = (center_x - size.x as f32 / 2.0).round() as i64;
+        let top = (center_y - size.y as f32 / 2.0).round() as i64;
+        imageops::overlay(&mut canvas, &region, left, top);
+    }
+
+    Ok(canvas)
+}
+
+/// 地图生成器
+///
+/// 加载并编译一次地形规则，之后为每个种子生成地图
+struct MapGenerator {
+    /// 地形规则
+    terrain_rules: TerrainRules,
+    /// 基础 WFC 规则（未定义生物群系时使用）
+    rules: Arc<Rules<Cartesian3D>>,
+    /// 每个模型的名称
+    model_names: Vec<String>,
+    /// 每个模型对应的可生成资源列表
+    assets: Vec<Vec<SpawnableAsset>>,
+    /// 预先放置的结构
+    structures: StructureLayout,
+}
+
+impl MapGenerator {
+    /// 读取并编译地形规则
+    fn load() -> Result<Self, String> {
+        let terrain_rules: TerrainRules = read_ron(RULES_FILE)?;
+        let world = build_world(&terrain_rules)
+            .map_err(|err| format!("invalid terrain rules '{RULES_FILE}': {err}"))?;
+        let rules = compile_rules(world.models, world.socket_collection)
+            .map_err(|err| format!("failed to compile terrain rules: {err}"))?;
+        let structures = resolve_structures(&terrain_rules)
+            .map_err(|err| format!("invalid structures in '{RULES_FILE}': {err}"))?;
+
+        Ok(Self {
+            terrain_rules,
+            rules: Arc::new(rules),
+            model_names: world.model_names,
+            assets: world.assets,
+            structures,
+        })
+    }
+
+    /// 为一个世界种子生成地图
+    ///
+    /// 与游戏中出生区块使用相同的种子派生、生物群系权重和结构放置
+    fn generate(
+        &self,
+        seed: u64,
+        width: u32,
+        height: u32,
+    ) -> Result<(ChunkData, MapLayout), String> {
+        let grid_seed = chunk_seed(seed, IVec2::ZERO);
+        let grid = map_grid(width, height);
+
+        // 与游戏相同，按地图范围内的生物群系比例调整权重
+        let biomes = BiomeMap::new(seed, &self.terrain_rules);
+        let cells =
+            (0..height as i32).flat_map(|y| (0..width as i32).map(move |x| IVec2::new(x, y)));
+        let rules = match biomes.blend(cells) {
+            Some(blend) => Arc::new(
+                compile_biome_rules(&self.terrain_rules, &blend)
+                    .map_err(|err| format!("failed to compile biome rules: {err}"))?,
+            ),
+            None => self.rules.clone(),
+        };
+
+        // 结构按世界网格坐标放置，地图左下角与出生区块左下角对齐
+        let presets = self.structures.presets(IVec2::ZERO, width, height);
+        let data = generate_grid(rules, grid, grid_seed, presets)
+            .map_err(|err| format!("generation failed: {err}"))?;
+        let layout = MapLayout::from_data(&data, width, height, &self.assets);
+        Ok((data, layout))
+    }
+}
+
+/// 创建输出目录
+fn create_out_dir(options: &Options) -> Result<(), String> {
+    fs::create_dir_all(&options.out)
+        .map_err(|err| format!("failed to create {}: {err}", options.out.display()))
+}
+
+/// 写入输出文件
+fn write_file(path: &Path, contents: &str) -> Result<(), String> {
+    fs::write(path, contents).map_err(|err| format!("failed to write {}: {err}", path.display()))
+}
+
+/// 生成所有请求的地图并写入输出目录
+fn run(options: &Options) -> Result<(), String> {
+    let generator = MapGenerator::load()?;
+    let tileset: TilesetDefinition = read_ron(TILESET_FILE)?;
+    let atlases = load_atlases(&tileset)?;
+    create_out_dir(options)?;
+
+    let mut failures = 0;
+    for i in 0..options.count {
+        let seed = options.seed.wrapping_add(i);
+        let (_, layout) = match generator.generate(seed, options.width, options.height) {
+            Ok(result) => result,
+            Err(err) => {
+                eprintln!("seed {seed}: {err}");
+                failures += 1;
+                continue;
+            }
+        };
+
+        let text_path = options.out.join(format!("map_{seed}.txt"));
+        write_file(&text_path, &layout.to_ascii())?;
+
+        let image_path = options.out.join(format!("map_{seed}.png"));
+        render_layout(&layout, &tileset, &atlases)?
+            .save(&image_path)
+            .map_err(|err| format!("failed to write {}: {err}", image_path.display()))?;
+
+        println!(
+            "seed {seed}: wrote {} and {} ({} isolated regions, {} tiles carved, {} unreachable)",
+            text_path.display(),
+            image_path.display(),
+            layout.regions.isolated.len(),
+            layout.regions.carved_tiles,
+            layout.regions.unreachable.len()
+        );
+    }
+
+    if failures > 0 {
+        return Err(format!("{failures} of {} maps failed", options.count));
+    }
+    Ok(())
+}
+
+/// 批量生成地图并输出统计报告
+///
+/// 写入每张地图一行的 `stats_maps.csv`、每个模型一行的 `stats_models.csv`
+/// 和包含汇总与明细的 `stats.json`
+fn stats(options: &Options) -> Result<(), String> {
+    let generator = MapGenerator::load()?;
+    create_out_dir(options)?;
+
+    let mut maps = Vec::new();
+    let mut failed_seeds = Vec::new();
+    for i in 0..options.count {
+        let seed = options.seed.wrapping_add(i);
+        match generator.generate(seed, options.width, options.height) {
+            Ok((data, layout)) => maps.push(MapStats::collect(
+                seed,
+                &data,
+                &layout,
+                &generator.model_names,
+                &generator.assets,
+            )),
+            Err(err) => {
+                eprintln!("seed {seed}: {err}");
+                failed_seeds.push(seed);
+            }
+        }
+    }
+
+    let batch = BatchStats::from_maps(maps, failed_seeds, &generator.model_names);
+    let maps_path = options.out.join("stats_maps.csv");
+    write_file(&maps_path, &batch.maps_csv())?;
+    let models_path = options.out.join("stats_models.csv");
+    write_file(&models_path, &batch.models_csv())?;
+    let json_path = options.out.join("stats.json");
+    let json = serde_json::to_string_pretty(&batch)
+        .map_err(|err| format!("failed to serialize statistics: {err}"))?;
+    write_file(&json_path, &json)?;
+
+    println!(
+        "{} maps generated, {} failed",
+        batch.generated,
+        batch.failed_seeds.len()
+    );
+    println!(
+        "walkable ratio: {:.3} (min {:.3}, max {:.3})",
+        batch.walkable_ratio.mean, batch.walkable_ratio.min, batch.walkable_ratio.max
+    );
+    println!(
+        "water coverage: {:.3} (min {:.3}, max {:.3})",
+        batch.water_coverage.mean, batch.water_coverage.min, batch.water_coverage.max
+    );
+    println!(
+        "plants per map: {:.1} (min {}, max {})",
+        batch.total_plants_per_map.mean,
+        batch.total_plants_per_map.min,
+        batch.total_plants_per_map.max
+    );
+    println!(
+        "wrote {}, {} and {}",
+        maps_path.display(),
+        models_path.display(),
+        json_path.display()
+    );
+    Ok(())
+}
+
+/// 检查地形规则与瓦片集是否一致并打印报告
+fn validate() -> Result<(), String> {
+    let terrain_rules: TerrainRules = read_ron(RULES_FILE)?;
+    let tileset: TilesetDefinition = read_ron(TILESET_FILE)?;
+
+    let report = validate_rules(&terrain_rules, &tileset);
+    println!("{RULES_FILE} against {TILESET_FILE}");
+    println!("{report}");
+    if !report.is_ok() {
+        return Err(format!("{} has errors", RULES_FILE));
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1).peekable();
+    let command = match args.peek().map(String::as_str) {
+        Some("validate") | Some("stats") => args.next(),
+        _ => None,
+    };
+
+    let result = if command.as_deref() == Some("validate") {
+        validate()
+    } else {
+        let options = match parse_args(args) {
+            Ok(options) => options,
+            Err(err) => {
+                if !err.is_empty() {
+                    eprintln!("mapgen: {err}\n");
+                }
+                eprintln!("{USAGE}");
+                return ExitCode::FAILURE;
+            }
+        };
+        match command.as_deref() {
+            Some("stats") => stats(&options),
+            _ => run(&options),
+        }
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("mapgen: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
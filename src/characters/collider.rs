@@ -1,12 +1,19 @@
 use crate::characters::physics::Velocity;
 use crate::collision::CollisionMap;
 use crate::config::player::COLLIDER_RADIUS;
+use crate::spatial::{SpatialIndex, SpatialTracked};
 use bevy::prelude::*;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Component, Debug, Clone)]
+#[require(SpatialTracked)]
 pub struct Collider {
     pub radius: f32,
     pub offset: Vec2,
+    /// 质量，角色互相重叠时按质量的反比分配推开的距离
+    pub mass: f32,
+    /// 是否可以被其他角色推动（分开重叠的角色时），不可推动的角色只会推开别人
+    pub pushable: bool,
 }
 
 impl Default for Collider {
@@ -14,6 +21,8 @@ impl Default for Collider {
         Self {
             radius: COLLIDER_RADIUS,
             offset: Vec2::ZERO,
+            mass: 1.0,
+            pushable: true,
         }
     }
 }
@@ -21,18 +30,90 @@ impl Collider {
     pub fn world_position(&self, transform: &Transform) -> Vec2 {
         transform.translation.truncate() + self.offset
     }
+
+    /// 设置质量
+    pub fn with_mass(mut self, mass: f32) -> Self {
+        self.mass = mass;
+        self
+    }
+
+    /// 设置为不可推动
+    pub fn immovable(mut self) -> Self {
+        self.pushable = false;
+        self
+    }
+
+    /// 质量的倒数，不可推动或质量无效时为 0
+    fn inverse_mass(&self) -> f32 {
+        if self.pushable && self.mass > 0.0 {
+            1.0 / self.mass
+        } else {
+            0.0
+        }
+    }
+}
+
+/// 检查从 `from` 移动到 `to` 是否会更深地进入另一个圆
+///
+/// 已经重叠的角色（例如生成在同一位置）仍然可以向远离对方的方向移动
+fn pushes_into(from: Vec2, to: Vec2, radius: f32, other: Vec2, other_radius: f32) -> bool {
+    let min_distance = radius + other_radius;
+    let distance = to.distance(other);
+    distance < min_distance && distance < from.distance(other)
 }
 
+/// 计算在空间索引中查找相邻碰撞体时需要额外扩大的范围
+///
+/// 空间索引记录的是上一次变换传播后的实体原点，碰撞体的圆心还要加上偏移，
+/// 本帧已经移动过的实体也可能偏离索引中的位置，因此取所有碰撞体中
+/// 最大的半径加上最大的（偏移 + 位移）
+///
+/// # 参数
+/// - `index`: 空间索引
+/// - `bodies`: 所有碰撞体的实体、当前原点和碰撞体
+fn neighbour_margin<'a>(
+    index: &SpatialIndex,
+    bodies: impl Iterator<Item = (Entity, Vec2, &'a Collider)>,
+) -> f32 {
+    let (radius, drift) = bodies.fold(
+        (0.0f32, 0.0f32),
+        |(radius, drift), (entity, origin, collider)| {
+            let moved = index
+                .position(entity)
+                .map_or(0.0, |indexed| indexed.distance(origin));
+            (
+                radius.max(collider.radius),
+                drift.max(collider.offset.length() + moved),
+            )
+        },
+    );
+    radius + drift
+}
+
+/// 验证移动
+///
+/// 沿速度方向扫描碰撞地图，同时把附近的其他角色当作障碍物，
+/// 角色撞到墙壁或其他角色时贴着对方滑动，速度被修正为实际可达的位移。
+/// 附近的角色从空间索引中查询，不需要遍历所有角色
 pub fn validate_movement(
     map: Option<Res<CollisionMap>>,
+    index: Res<SpatialIndex>,
     time: Res<Time>,
-    mut query: Query<(&Transform, &mut Velocity, &Collider)>,
+    mut query: Query<(Entity, &Transform, &mut Velocity, &Collider)>,
+    obstacles: Query<(Entity, &Transform, &Collider)>,
 ) {
     let Some(map) = map else {
         return;
     };
 
-    for (transform, mut velocity, collider) in query.iter_mut() {
+    let margin = neighbour_margin(
+        &index,
+        obstacles.iter().map(|(entity, transform, collider)| {
+            (entity, transform.translation.truncate(), collider)
+        }),
+    );
+
+    for (entity, transform, mut velocity, collider) in query.iter_mut() {
         if !velocity.is_moving() {
             continue;
         }
@@ -42,7 +123,26 @@ pub fn validate_movement(
         let delta = velocity.0 * time.delta_secs();
         let desired_pos = current_pos + delta;
 
-        let valid_pos = map.sweep_circle(current_pos, desired_pos, collider.radius);
+        // 移动范围内可能碰到的其他角色
+        let search_radius = collider.radius + delta.length() + margin;
+        let nearby: Vec<(Vec2, f32)> = index
+            .within_radius(current_pos, search_radius)
+            .filter(|(other, _)| *other != entity)
+            .filter_map(|(other, _)| obstacles.get(other).ok())
+            .map(|(_, other_transform, other_collider)| {
+                (
+                    other_collider.world_position(other_transform),
+                    other_collider.radius,
+                )
+            })
+            .collect();
+
+        let valid_pos =
+            map.sweep_circle_with(current_pos, desired_pos, collider.radius, |from, to| {
+                nearby.iter().any(|(other_pos, other_radius)| {
+                    pushes_into(from, to, collider.radius, *other_pos, *other_radius)
+                })
+            });
 
         let actual_delta = valid_pos - current_pos;
 
@@ -54,3 +154,82 @@ pub fn validate_movement(
         }
     }
 }
+
+/// 分开互相重叠的角色
+///
+/// 在移动之后运行，对每一对重叠的碰撞体沿圆心连线推开，
+/// 推开的距离按质量的反比分配（不可推动的一方不移动）。
+/// 相邻的碰撞体从空间索引中查询；推动通过碰撞地图扫描，角色不会被推进障碍物中，
+/// 碰撞地图尚未构建时不推动
+pub fn resolve_collider_overlaps(
+    map: Option<Res<CollisionMap>>,
+    index: Res<SpatialIndex>,
+    mut query: Query<(Entity, &mut Transform, &Collider)>,
+) {
+    let Some(map) = map else {
+        return;
+    };
+
+    let margin = neighbour_margin(
+        &index,
+        query.iter().map(|(entity, transform, collider)| {
+            (entity, transform.translation.truncate(), collider)
+        }),
+    );
+
+    let mut pushes: HashMap<Entity, Vec2> = HashMap::new();
+    let mut pairs: HashSet<(Entity, Entity)> = HashSet::new();
+    for (entity_a, transform_a, collider_a) in query.iter() {
+        let pos_a = collider_a.world_position(transform_a);
+        for (entity_b, _) in index.within_radius(pos_a, collider_a.radius + margin) {
+            // 每一对只处理一次（刚生成的实体可能只在一方的查询结果中）
+            if entity_b == entity_a
+                || !pairs.insert((entity_a.min(entity_b), entity_a.max(entity_b)))
+            {
+                continue;
+            }
+            let Ok((_, transform_b, collider_b)) = query.get(entity_b) else {
+                continue;
+            };
+            let pos_b = collider_b.world_position(transform_b);
+
+            let min_distance = collider_a.radius + collider_b.radius;
+            let offset = pos_b - pos_a;
+            let distance = offset.length();
+            if distance >= min_distance {
+                continue;
+            }
+
+            let inverse_a = collider_a.inverse_mass();
+            let inverse_b = collider_b.inverse_mass();
+            let total = inverse_a + inverse_b;
+            if total <= 0.0 {
+                continue;
+            }
+
+            // 圆心重合时任选一个方向推开
+            let normal = if distance > 0.001 {
+                offset / distance
+            } else {
+                Vec2::X
+            };
+            let penetration = min_distance - distance;
+            *pushes.entry(entity_a).or_default() -= normal * penetration * inverse_a / total;
+            *pushes.entry(entity_b).or_default() += normal * penetration * inverse_b / total;
+        }
+    }
+
+    for (entity, push) in pushes {
+        if push == Vec2::ZERO {
+            continue;
+        }
+        let Ok((_, mut transform, collider)) = query.get_mut(entity) else {
+            continue;
+        };
+        let current_pos = collider.world_position(&transform);
+        let target = map.sweep_circle(current_pos, current_pos + push, collider.radius);
+        let moved = target - current_pos;
+        transform.translation.x += moved.x;
+        transform.translation.y += moved.y;
+    }
+}
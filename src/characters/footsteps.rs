@@ -0,0 +1,67 @@
+use crate::characters::collider::Collider;
+use crate::characters::physics::Velocity;
+use crate::characters::state::CharacterState;
+use crate::collision::{CollisionMap, FootstepSurface};
+use crate::config::player::FOOTSTEP_STRIDE;
+use bevy::prelude::*;
+
+/// 脚步消息
+///
+/// 角色在地面上每移动一段距离发送一次，粒子和音效系统根据地面类型播放对应的效果
+#[derive(Message, Debug, Copy, Clone)]
+pub struct Footstep {
+    /// 发出脚步的角色
+    pub entity: Entity,
+    /// 脚步的世界坐标（碰撞体中心）
+    pub position: Vec2,
+    /// 脚下的地面类型
+    pub surface: FootstepSurface,
+}
+
+/// 脚步组件
+///
+/// 记录角色自上一次脚步以来移动的距离
+#[derive(Component, Debug, Default)]
+pub struct Footsteps {
+    distance: f32,
+}
+
+/// 发送脚步消息
+///
+/// 在移动之后运行，按实际移动速度累计距离，每累计一个步长发送一次 [`Footstep`]
+pub fn emit_footsteps(
+    time: Res<Time>,
+    map: Option<Res<CollisionMap>>,
+    mut query: Query<(
+        Entity,
+        &Transform,
+        &Collider,
+        &Velocity,
+        &CharacterState,
+        &mut Footsteps,
+    )>,
+    mut footsteps: MessageWriter<Footstep>,
+) {
+    let Some(map) = map else {
+        return;
+    };
+
+    for (entity, transform, collider, velocity, state, mut steps) in query.iter_mut() {
+        if !velocity.is_moving() || !state.is_grounded() {
+            continue;
+        }
+
+        steps.distance += velocity.length() * time.delta_secs();
+        if steps.distance < FOOTSTEP_STRIDE {
+            continue;
+        }
+        steps.distance -= FOOTSTEP_STRIDE;
+
+        let position = collider.world_position(transform);
+        footsteps.write(Footstep {
+            entity,
+            position,
+            surface: map.movement_at(position).surface,
+        });
+    }
+}
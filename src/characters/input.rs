@@ -1,8 +1,10 @@
 use crate::characters::animation::{AnimationController, AnimationTimer};
+use crate::characters::collider::Collider;
 use crate::characters::config::CharacterEntry;
 use crate::characters::facing::Facing;
 use crate::characters::physics::Velocity;
 use crate::characters::state::CharacterState;
+use crate::collision::CollisionMap;
 use bevy::prelude::*;
 
 /// 玩家标记组件
@@ -65,25 +67,38 @@ fn determine_new_state(
     }
 }
 
+#[allow(clippy::type_complexity)]
 pub fn handle_player_input(
     input: Res<ButtonInput<KeyCode>>,
+    map: Option<Res<CollisionMap>>,
     mut query: Query<
         (
             &mut CharacterState,
             &mut Velocity,
             &mut Facing,
             &CharacterEntry,
+            &Transform,
+            &Collider,
         ),
         With<Player>,
     >,
 ) {
-    let Ok((mut state, mut velocity, mut facing, character)) = query.single_mut() else {
+    let Ok((mut state, mut velocity, mut facing, character, transform, collider)) =
+        query.single_mut()
+    else {
         return;
     };
 
+    // 脚下地形的移动属性
+    let terrain = map
+        .map(|map| map.movement_at(collider.world_position(transform)))
+        .unwrap_or_default();
+
     // 读取用户输入
     let direction = read_movement_input(&input);
-    let is_running = input.pressed(KeyCode::ShiftLeft) || input.pressed(KeyCode::ShiftRight);
+    // 不能跑步的地形上（例如浅水）只能行走
+    let is_running = (input.pressed(KeyCode::ShiftLeft) || input.pressed(KeyCode::ShiftRight))
+        && terrain.can_run;
     let wants_jump = input.just_pressed(KeyCode::Space);
 
     // 更新用户朝向
@@ -101,7 +116,8 @@ pub fn handle_player_input(
     }
 
     // 根据用户状态计算速度向量
-    *velocity = super::physics::calculate_velocity(*state, direction, character);
+    *velocity =
+        super::physics::calculate_velocity(*state, direction, character, terrain.speed_multiplier);
 }
 
 pub fn update_jump_state(
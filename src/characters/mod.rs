@@ -7,9 +7,9 @@ pub mod animation;
 pub mod collider;
 pub mod config;
 pub mod facing;
+pub mod footsteps;
 pub mod input;
 pub mod physics;
-mod rendering;
 pub mod spawn;
 pub mod state;
 
@@ -24,6 +24,7 @@ impl Plugin for CharactersPlugin {
         app.add_plugins(RonAssetPlugin::<CharactersList>::new(&["characters.ron"]))
             // 初始化当前角色索引资源
             .init_resource::<spawn::CurrentCharacterIndex>()
+            .add_message::<footsteps::Footstep>()
             // 在启动时生成玩家角色
             .add_systems(Startup, spawn::spawn_player)
             // 每帧更新系统
@@ -37,7 +38,9 @@ impl Plugin for CharactersPlugin {
                     animation::on_state_change_update_animation,
                     collider::validate_movement,
                     physics::apply_velocity,
-                    rendering::update_player_depth,
+                    // 移动后分开互相重叠的角色
+                    collider::resolve_collider_overlaps,
+                    footsteps::emit_footsteps,
                     animation::tick_animations,
                 )
                     .chain()
@@ -1,5 +1,6 @@
 use crate::characters::config::CharacterEntry;
 use crate::characters::state::CharacterState;
+use crate::collision::CollisionMap;
 use bevy::prelude::*;
 
 #[derive(Component, Debug, Copy, Clone, Default, Deref, DerefMut)]
@@ -13,25 +14,45 @@ impl Velocity {
     }
 }
 
+/// 根据角色状态和移动方向计算速度
+///
+/// # 参数
+/// - `state`: 角色状态
+/// - `direction`: 移动方向
+/// - `character`: 角色配置
+/// - `terrain_speed`: 脚下地形的速度倍率（见 [`terrain_speed_at`]）
+///
+/// # 返回
+/// 角色的速度
 pub fn calculate_velocity(
     state: CharacterState,
     direction: Vec2,
     character: &CharacterEntry,
+    terrain_speed: f32,
 ) -> Velocity {
+    let speed = character.base_move_speed * terrain_speed;
     match state {
         CharacterState::Idle => Velocity::ZERO,
         CharacterState::Jumping => Velocity::ZERO,
-        CharacterState::Walking => {
-            Velocity(direction.normalize_or_zero() * character.base_move_speed)
+        CharacterState::Walking => Velocity(direction.normalize_or_zero() * speed),
+        CharacterState::Running => {
+            Velocity(direction.normalize_or_zero() * speed * character.run_speed_multiplier)
         }
-        CharacterState::Running => Velocity(
-            direction.normalize_or_zero()
-                * character.base_move_speed
-                * character.run_speed_multiplier,
-        ),
     }
 }
 
+/// 获取指定位置地形的速度倍率
+///
+/// 所有设置速度的系统（玩家输入、导航代理、流场代理）在计算速度时乘以这个倍率，
+/// 地形对所有角色的影响一致，导航代理在限制不越过路径点之前应用它
+///
+/// # 参数
+/// - `map`: 碰撞地图（尚未构建时倍率为 1.0）
+/// - `position`: 世界坐标
+pub fn terrain_speed_at(map: Option<&CollisionMap>, position: Vec2) -> f32 {
+    map.map_or(1.0, |map| map.movement_at(position).speed_multiplier)
+}
+
 pub fn apply_velocity(time: Res<Time>, mut query: Query<(&Velocity, &mut Transform)>) {
     for (velocity, mut transform) in query.iter_mut() {
         if velocity.is_moving() {
@@ -4,11 +4,16 @@ use crate::characters::animation::{
 use crate::characters::collider::Collider;
 use crate::characters::config::{CharacterEntry, CharactersList};
 use crate::characters::facing::Facing;
+use crate::characters::footsteps::Footsteps;
 use crate::characters::input::Player;
 use crate::characters::physics::Velocity;
 use crate::characters::state::CharacterState;
+use crate::collision::{CollisionMap, find_spawn_point};
 use crate::combat::PlayerCombat;
 use crate::config::player::{PLAYER_SCALE, PLAYER_Z_POSITION};
+use crate::map::generate::PROPS_LAYER;
+use crate::navigation::FlowFieldGoal;
+use crate::rendering::YSort;
 use bevy::prelude::*;
 
 /// 当前角色索引资源
@@ -56,6 +61,13 @@ pub fn create_character_atlas_layout(
     ))
 }
 
+/// 创建角色的深度排序组件
+///
+/// 角色与道具层一起排序，锚点位于精灵底部（脚下）
+fn character_y_sort(character_entry: &CharacterEntry) -> YSort {
+    YSort::new(PROPS_LAYER).with_anchor(-(character_entry.tile_size as f32) / 2.0)
+}
+
 /// 生成玩家角色
 ///
 /// 在游戏启动时创建玩家实体并加载角色配置
@@ -78,6 +90,8 @@ pub fn spawn_player(
     // 生成玩家实体（初始只有基础组件，稍后初始化）
     commands.spawn((
         Player,
+        // 敌人等流场代理朝玩家移动
+        FlowFieldGoal,
         Transform::from_translation(Vec3::new(0.0, 0.0, PLAYER_Z_POSITION))
             .with_scale(Vec3::splat(PLAYER_SCALE)),
         Sprite::default(),
@@ -127,17 +141,52 @@ pub fn initialize_player_character(
             Velocity::default(),
             Facing::default(),
             Collider::default(),
+            Footsteps::default(),
             PlayerCombat::default(),
             AnimationTimer(Timer::from_seconds(
                 DEFAULT_ANIMATION_FRAME_TIME,
                 TimerMode::Repeating,
             )),
+            character_y_sort(character_entry),
             character_entry.clone(),
             sprite,
         ));
     }
 }
 
+/// 将玩家放置到可行走的位置
+///
+/// 碰撞地图构建完成后，把玩家移动到离当前位置最近、能容纳其碰撞体的瓦片上，
+/// 避免玩家出生在水、岩石或树木中
+pub fn place_player_on_walkable_tile(
+    map: Option<Res<CollisionMap>>,
+    mut query: Query<(&mut Transform, Option<&Collider>), With<Player>>,
+) {
+    let Some(map) = map else {
+        warn!("Collision map not ready, player spawn point not validated");
+        return;
+    };
+
+    for (mut transform, collider) in query.iter_mut() {
+        let collider = collider.cloned().unwrap_or_default();
+        let current = collider.world_position(&transform);
+
+        let Some(spawn_point) = find_spawn_point(&map, current, collider.radius) else {
+            warn!(
+                "No walkable spawn point found, keeping player at {:?}",
+                current
+            );
+            continue;
+        };
+
+        // 碰撞体有偏移时，让碰撞体中心落在出生点上
+        let target = spawn_point - collider.offset;
+        transform.translation.x = target.x;
+        transform.translation.y = target.y;
+        info!("Player placed at walkable spawn point {:?}", spawn_point);
+    }
+}
+
 /// 切换角色
 ///
 /// 按下数字键 1-9 切换到对应的角色
@@ -146,7 +195,7 @@ pub fn switch_character(
     mut character_index: ResMut<CurrentCharacterIndex>,
     characters_list: Res<Assets<CharactersList>>,
     characters_list_resource: Option<Res<CharactersListResource>>,
-    mut query: Query<(&mut CharacterEntry, &mut Sprite), With<Player>>,
+    mut query: Query<(&mut CharacterEntry, &mut Sprite, &mut YSort), With<Player>>,
     mut atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
     asset_server: Res<AssetServer>,
 ) {
@@ -184,7 +233,7 @@ pub fn switch_character(
     character_index.index = new_index;
 
     // 获取玩家实体的组件
-    let Ok((mut current_entry, mut sprite)) = query.single_mut() else {
+    let Ok((mut current_entry, mut sprite, mut y_sort)) = query.single_mut() else {
         return;
     };
 
@@ -192,6 +241,8 @@ pub fn switch_character(
     let character_entry = &characters_list.characters[new_index];
     // 更新角色配置
     *current_entry = character_entry.clone();
+    // 不同角色的精灵大小可能不同，重新计算脚下锚点
+    *y_sort = character_y_sort(character_entry);
 
     // 加载新角色的纹理
     let texture = asset_server.load(&character_entry.texture_path);
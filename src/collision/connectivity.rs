@@ -0,0 +1,282 @@
+use crate::collision::map::CollisionMap;
+use crate::collision::regions::WalkableRegions;
+use crate::collision::tile_type::TileType;
+use crate::config::map::MAX_CARVE_COST;
+use bevy::prelude::*;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// 孤立区域
+///
+/// 与最大可行走区域不连通的一块区域
+#[derive(Debug, Clone)]
+pub struct IsolatedRegion {
+    /// 区域包含的瓦片数量
+    pub size: usize,
+    /// 区域中的一个瓦片（网格坐标）
+    pub sample: IVec2,
+}
+
+/// 被开辟的瓦片
+#[derive(Debug, Copy, Clone)]
+pub struct CarvedTile {
+    /// 网格坐标
+    pub grid: IVec2,
+    /// 原来的瓦片类型
+    pub from: TileType,
+    /// 开辟后的瓦片类型
+    pub to: TileType,
+}
+
+/// 区域统计
+///
+/// 记录一次连通性检查的结果
+#[derive(Debug, Clone, Default)]
+pub struct RegionStats {
+    /// 可行走瓦片总数
+    pub walkable_tiles: usize,
+    /// 开辟路径后的连通区域数量
+    pub region_count: usize,
+    /// 最大区域包含的瓦片数量
+    pub largest_region: usize,
+    /// 开辟路径前发现的孤立区域
+    pub isolated: Vec<IsolatedRegion>,
+    /// 开辟路径后仍然孤立的区域
+    pub unreachable: Vec<IsolatedRegion>,
+    /// 被开辟的瓦片数量
+    pub carved_tiles: usize,
+}
+
+/// 开辟瓦片的代价
+///
+/// 道具（树木、岩石）改为草地，深水改为可通行的浅滩，
+/// 开辟水路的代价更高，因此优先移除道具。空白瓦片不能开辟
+fn carve_cost(tile: TileType) -> Option<u32> {
+    match tile {
+        TileType::Tree | TileType::Rock => Some(1),
+        TileType::Water => Some(2),
+        _ if tile.is_ground() => Some(0),
+        _ => None,
+    }
+}
+
+/// 瓦片被开辟后的类型
+fn carved_type(tile: TileType) -> TileType {
+    match tile {
+        TileType::Tree | TileType::Rock => TileType::Grass,
+        TileType::Water => TileType::Shore,
+        other => other,
+    }
+}
+
+/// 收集每个区域的大小和一个样本瓦片
+fn collect_regions(map: &CollisionMap, regions: &WalkableRegions) -> Vec<IsolatedRegion> {
+    let mut result: Vec<Option<IsolatedRegion>> = vec![None; regions.count()];
+    for y in 0..map.height() {
+        for x in 0..map.width() {
+            if let Some(region) = regions.region_at(x, y) {
+                result[region].get_or_insert(IsolatedRegion {
+                    size: regions.sizes()[region],
+                    sample: IVec2::new(x, y),
+                });
+            }
+        }
+    }
+    result.into_iter().flatten().collect()
+}
+
+/// 标记每个区域是否接触地图边界
+fn border_regions(map: &CollisionMap, regions: &WalkableRegions) -> Vec<bool> {
+    let mut touches = vec![false; regions.count()];
+    let (width, height) = (map.width(), map.height());
+    let border = (0..width)
+        .flat_map(|x| [IVec2::new(x, 0), IVec2::new(x, height - 1)])
+        .chain((0..height).flat_map(|y| [IVec2::new(0, y), IVec2::new(width - 1, y)]));
+    for cell in border {
+        if let Some(region) = regions.region_at(cell.x, cell.y) {
+            touches[region] = true;
+        }
+    }
+    touches
+}
+
+/// 寻找从指定区域到任意目标区域的最低代价路径
+///
+/// 以区域中的所有瓦片为起点运行 Dijkstra，到达目标区域（`targets` 中为 true）中的任意瓦片即停止
+///
+/// # 返回
+/// 路径上需要开辟的瓦片，如果代价超过上限或无法到达则返回 None
+fn find_carve_path(
+    map: &CollisionMap,
+    regions: &WalkableRegions,
+    from: usize,
+    targets: &[bool],
+) -> Option<Vec<IVec2>> {
+    let width = map.width();
+    let size = (width * map.height()) as usize;
+    let index = |cell: IVec2| (cell.y * width + cell.x) as usize;
+    let mut cost = vec![u32::MAX; size];
+    let mut previous: Vec<Option<IVec2>> = vec![None; size];
+    let mut heap = BinaryHeap::new();
+
+    for y in 0..map.height() {
+        for x in 0..width {
+            if regions.region_at(x, y) == Some(from) {
+                let cell = IVec2::new(x, y);
+                cost[index(cell)] = 0;
+                heap.push(Reverse((0, x, y)));
+            }
+        }
+    }
+
+    while let Some(Reverse((current_cost, x, y))) = heap.pop() {
+        let cell = IVec2::new(x, y);
+        if current_cost > cost[index(cell)] {
+            continue;
+        }
+        if current_cost > MAX_CARVE_COST {
+            return None;
+        }
+
+        // 到达目标区域，回溯路径
+        if regions
+            .region_at(x, y)
+            .is_some_and(|region| targets[region])
+        {
+            let mut path = Vec::new();
+            let mut step = previous[index(cell)];
+            while let Some(step_cell) = step {
+                if regions.region_at(step_cell.x, step_cell.y) == Some(from) {
+                    break;
+                }
+                path.push(step_cell);
+                step = previous[index(step_cell)];
+            }
+            return Some(path);
+        }
+
+        for offset in [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y] {
+            let next = cell + offset;
+            let Some(tile) = map.get_tile(next.x, next.y) else {
+                continue;
+            };
+            let Some(step_cost) = carve_cost(tile) else {
+                continue;
+            };
+            let next_cost = current_cost + step_cost;
+            if next_cost < cost[index(next)] {
+                cost[index(next)] = next_cost;
+                previous[index(next)] = Some(cell);
+                heap.push(Reverse((next_cost, next.x, next.y)));
+            }
+        }
+    }
+
+    None
+}
+
+/// 检查碰撞地图的连通性并开辟路径
+///
+/// 对可行走瓦片做洪水填充，找出与最大区域不连通的孤立区域，
+/// 然后为每个孤立区域开辟一条代价最低的路径（道具改为草地，深水改为浅滩）。
+/// 代价超过 `MAX_CARVE_COST` 的区域保持孤立并记录在统计结果中。
+///
+/// # 参数
+/// - `map`: 碰撞地图的可变引用
+///
+/// # 返回
+/// 区域统计结果和所有被开辟的瓦片
+pub fn ensure_connectivity(map: &mut CollisionMap) -> (RegionStats, Vec<CarvedTile>) {
+    connect_regions(map, false)
+}
+
+/// 检查单个区块的连通性并开辟路径
+///
+/// 与 [`ensure_connectivity`] 相同，但地图边界不是屏障：接触边界的区域可能经由相邻区块连通，
+/// 不算作孤立区域，被区块完全包围的孤立区域连到最大区域或任意接触边界的区域即可。
+/// 结果只取决于区块自己的瓦片，与相邻区块是否已加载无关
+///
+/// # 参数
+/// - `map`: 只包含一个区块的碰撞地图
+///
+/// # 返回
+/// 区域统计结果和所有被开辟的瓦片
+pub fn ensure_chunk_connectivity(map: &mut CollisionMap) -> (RegionStats, Vec<CarvedTile>) {
+    connect_regions(map, true)
+}
+
+/// 连通孤立区域
+///
+/// `open_border` 为 true 时接触地图边界的区域和最大区域一样视为已连通
+fn connect_regions(map: &mut CollisionMap, open_border: bool) -> (RegionStats, Vec<CarvedTile>) {
+    // 已连通的区域：最大区域，以及开放边界时接触边界的区域
+    let connected = |map: &CollisionMap, regions: &WalkableRegions| {
+        let largest = regions.largest()?;
+        let mut targets = if open_border {
+            border_regions(map, regions)
+        } else {
+            vec![false; regions.count()]
+        };
+        targets[largest] = true;
+        Some(targets)
+    };
+
+    let regions = WalkableRegions::compute(map);
+    let Some(targets) = connected(map, &regions) else {
+        return (RegionStats::default(), Vec::new());
+    };
+    let isolated: Vec<IsolatedRegion> = collect_regions(map, &regions)
+        .into_iter()
+        .enumerate()
+        .filter(|(region, _)| !targets[*region])
+        .map(|(_, info)| info)
+        .collect();
+
+    let mut carved = Vec::new();
+    let mut unreachable: Vec<IsolatedRegion> = Vec::new();
+
+    // 每次开辟后重新计算区域（开辟可能同时连通多个区域）
+    loop {
+        let regions = WalkableRegions::compute(map);
+        let Some(targets) = connected(map, &regions) else {
+            break;
+        };
+        let next = collect_regions(map, &regions)
+            .into_iter()
+            .enumerate()
+            .find(|(region, info)| {
+                !targets[*region] && !unreachable.iter().any(|u| u.sample == info.sample)
+            });
+        let Some((region, info)) = next else {
+            break;
+        };
+
+        match find_carve_path(map, &regions, region, &targets) {
+            Some(path) => {
+                for grid in path {
+                    let Some(from) = map.get_tile(grid.x, grid.y) else {
+                        continue;
+                    };
+                    if from.is_walkable() {
+                        continue;
+                    }
+                    let to = carved_type(from);
+                    map.set_tile(grid.x, grid.y, to);
+                    carved.push(CarvedTile { grid, from, to });
+                }
+            }
+            None => unreachable.push(info),
+        }
+    }
+
+    let regions = WalkableRegions::compute(map);
+    let stats = RegionStats {
+        walkable_tiles: regions.sizes().iter().sum(),
+        region_count: regions.count(),
+        largest_region: regions.largest().map_or(0, |r| regions.sizes()[r]),
+        isolated,
+        unreachable,
+        carved_tiles: carved.len(),
+    };
+    (stats, carved)
+}
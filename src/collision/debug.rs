@@ -1,11 +1,18 @@
 use crate::characters::collider::Collider;
 use crate::characters::input::Player;
 use crate::collision::map::CollisionMap;
+use crate::map::chunk::world_to_chunk;
+use crate::map::{BiomeId, BiomeMap, WorldSeed};
+use crate::navigation::{NavAgent, NavPath};
 use bevy::prelude::*;
 
 #[derive(Resource, Default)]
 pub struct DebugCollisionEnabled(pub bool);
 
+/// 调试信息文本标记组件
+#[derive(Component)]
+pub struct DebugOverlayText;
+
 pub fn toggle_debug_collision(
     keyboard: Res<ButtonInput<KeyCode>>,
     mut debug_enabled: ResMut<DebugCollisionEnabled>,
@@ -101,3 +108,100 @@ pub fn debug_player_position(
         }
     }
 }
+
+/// 绘制导航代理的路径
+///
+/// 从代理当前位置连接剩余的路径点，并标出每个路径点和目标位置
+pub fn debug_draw_paths(
+    debug_enabled: Res<DebugCollisionEnabled>,
+    agents: Query<(&Transform, Option<&Collider>, &NavAgent, &NavPath)>,
+    mut gizmos: Gizmos,
+) {
+    if !debug_enabled.0 {
+        return;
+    }
+
+    let path_color = Color::srgb(1.0, 0.5, 0.0);
+    for (transform, collider, agent, path) in agents.iter() {
+        let position = match collider {
+            Some(collider) => collider.world_position(transform),
+            None => transform.translation.truncate(),
+        };
+
+        gizmos.linestrip_2d(
+            std::iter::once(position).chain(path.remaining().iter().copied()),
+            path_color,
+        );
+        for waypoint in path.remaining() {
+            gizmos.circle_2d(*waypoint, 4.0, path_color);
+        }
+        if let Some(target) = agent.target {
+            gizmos.cross_2d(target, 10.0, Color::srgb(1.0, 0.0, 1.0));
+        }
+    }
+}
+
+/// 更新调试信息文本
+///
+/// 调试模式开启时在屏幕左上角显示世界种子、玩家所在网格和所在区块的生物群系，关闭时移除文本
+pub fn update_debug_overlay(
+    mut commands: Commands,
+    debug_enabled: Res<DebugCollisionEnabled>,
+    seed: Res<WorldSeed>,
+    map: Option<Res<CollisionMap>>,
+    biomes: Option<Res<BiomeMap>>,
+    player_query: Query<(&Transform, &Collider), With<Player>>,
+    mut overlay_query: Query<(Entity, &mut Text), With<DebugOverlayText>>,
+) {
+    if !debug_enabled.0 {
+        for (entity, _) in overlay_query.iter() {
+            commands.entity(entity).despawn();
+        }
+        return;
+    }
+
+    let grid = match (map, player_query.single()) {
+        (Some(map), Ok((transform, collider))) => {
+            let grid = map.world_to_grid(collider.world_position(transform));
+            format!("({}, {})", grid.x, grid.y)
+        }
+        _ => "-".to_string(),
+    };
+    // 显示区块的生物群系（与地形一致），括号中为所在瓦片的噪声结果
+    let biome = match (biomes, player_query.single()) {
+        (Some(biomes), Ok((transform, collider))) => {
+            let position = collider.world_position(transform);
+            let name = |id: Option<BiomeId>| id.map_or("-", |id| biomes.name(id));
+            format!(
+                "{} (tile: {})",
+                name(biomes.chunk_biome(world_to_chunk(position))),
+                name(biomes.biome_at_world(position))
+            )
+        }
+        _ => "-".to_string(),
+    };
+    let content = format!("Seed: {}\nGrid: {}\nBiome: {}", seed.value, grid, biome);
+
+    if let Ok((_, mut text)) = overlay_query.single_mut() {
+        if **text != content {
+            **text = content;
+        }
+        return;
+    }
+
+    commands.spawn((
+        DebugOverlayText,
+        Text::new(content),
+        TextFont {
+            font_size: 18.0,
+            ..default()
+        },
+        TextColor(Color::srgb(1.0, 1.0, 0.0)),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(8.0),
+            left: Val::Px(8.0),
+            ..default()
+        },
+    ));
+}
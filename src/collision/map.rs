@@ -1,4 +1,4 @@
-use crate::collision::tile_type::TileType;
+use crate::collision::tile_type::{TerrainMovement, TileType};
 use bevy::prelude::*;
 
 /// 碰撞地图资源
@@ -94,7 +94,6 @@ impl CollisionMap {
     ///
     /// # 返回
     /// 对应的世界坐标（瓦片中心点）
-    #[allow(unused)]
     pub fn grid_to_world(&self, grid_x: i32, grid_y: i32) -> Vec2 {
         Vec2::new(
             // 计算瓦片中心点的 X 坐标
@@ -145,6 +144,18 @@ impl CollisionMap {
         self.get_tile(x, y).map_or(false, |tile| tile.is_walkable())
     }
 
+    /// 检查指定位置是否属于可到达的地面（见 [`TileType::is_ground`]）
+    ///
+    /// # 参数
+    /// - `x`: 网格 X 坐标
+    /// - `y`: 网格 Y 坐标
+    ///
+    /// # 返回
+    /// 如果是可行走的非空白瓦片返回 true，否则返回 false
+    pub fn is_ground(&self, x: i32, y: i32) -> bool {
+        self.get_tile(x, y).is_some_and(|tile| tile.is_ground())
+    }
+
     /// 检查世界坐标位置是否可行走
     ///
     /// # 参数
@@ -157,6 +168,20 @@ impl CollisionMap {
         self.is_walkable(grid_pos.x, grid_pos.y)
     }
 
+    /// 获取世界坐标位置的地形对移动的影响
+    ///
+    /// # 参数
+    /// - `world_pos`: 世界坐标位置
+    ///
+    /// # 返回
+    /// 所在瓦片的移动属性，超出范围时返回默认值
+    pub fn movement_at(&self, world_pos: Vec2) -> TerrainMovement {
+        let grid_pos = self.world_to_grid(world_pos);
+        self.get_tile(grid_pos.x, grid_pos.y)
+            .map(|tile| tile.movement())
+            .unwrap_or_default()
+    }
+
     /// 检查圆形是否与指定瓦片相交
     ///
     /// # 参数
@@ -282,6 +307,32 @@ impl CollisionMap {
     /// - 角色移动路径上有墙壁：会在墙前停下或贴墙滑动
     /// - 角色沿墙移动：可以沿着墙壁的方向继续前进
     pub fn sweep_circle(&self, start: Vec2, end: Vec2, radius: f32) -> Vec2 {
+        self.sweep_circle_with(start, end, radius, |_, _| false)
+    }
+
+    /// 扫描圆形移动路径，同时避开额外的障碍物
+    ///
+    /// 与 [`sweep_circle`](Self::sweep_circle) 相同，但候选位置还需要通过 `blocked` 检查，
+    /// 用于让角色之间互相阻挡时也能贴着对方滑动
+    ///
+    /// # 参数
+    /// - `start`: 起点坐标（圆形中心）
+    /// - `end`: 目标终点坐标
+    /// - `radius`: 圆形半径（角色碰撞体积）
+    /// - `blocked`: 对从当前位置移动到的候选位置返回 true 时视为碰撞，参数为 (当前位置, 候选位置)
+    ///
+    /// # 返回
+    /// 实际可达的位置
+    pub fn sweep_circle_with(
+        &self,
+        start: Vec2,
+        end: Vec2,
+        radius: f32,
+        blocked: impl Fn(Vec2, Vec2) -> bool,
+    ) -> Vec2 {
+        let is_clear =
+            |from: Vec2, to: Vec2| self.is_circle_clear(to, radius) && !blocked(from, to);
+
         // 计算从起点到终点的位移向量
         let delta = end - start;
 
@@ -310,14 +361,14 @@ impl CollisionMap {
             let candidate = pos + step_vec;
 
             // 检查候选位置是否没有碰撞
-            if self.is_circle_clear(candidate, radius) {
+            if is_clear(pos, candidate) {
                 // 如果清晰，直接移动到候选位置
                 pos = candidate;
             } else {
                 // 如果直接移动会碰撞，尝试"贴墙滑动"策略
                 // 首先尝试只沿 X 轴移动（保持 Y 坐标不变）
                 let try_x = Vec2::new(candidate.x, pos.y);
-                if self.is_circle_clear(try_x, radius) {
+                if is_clear(pos, try_x) {
                     // 如果 X 轴方向可以移动，更新位置并继续下一步
                     pos = try_x;
                     continue;
@@ -325,7 +376,7 @@ impl CollisionMap {
 
                 // 如果 X 轴方向也不能移动，尝试只沿 Y 轴移动（保持 X 坐标不变）
                 let try_y = Vec2::new(pos.x, candidate.y);
-                if self.is_circle_clear(try_y, radius) {
+                if is_clear(pos, try_y) {
                     // 如果 Y 轴方向可以移动，更新位置并继续下一步
                     pos = try_y;
                     continue;
@@ -349,12 +400,10 @@ impl CollisionMap {
         self.height
     }
 
-    #[cfg(debug_assertions)]
     pub fn tile_size(&self) -> f32 {
         self.tile_size
     }
 
-    #[cfg(debug_assertions)]
     pub fn origin(&self) -> Vec2 {
         Vec2::new(self.origin_x, self.origin_y)
     }
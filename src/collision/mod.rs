@@ -1,15 +1,33 @@
+mod connectivity;
 #[cfg(debug_assertions)]
 mod debug;
 mod map;
+mod placement;
+mod raycast;
+mod regions;
 mod systems;
+mod tile_changes;
 mod tile_type;
 
 use crate::state::GameState;
 use bevy::prelude::*;
 
+pub use connectivity::{
+    CarvedTile, IsolatedRegion, RegionStats, ensure_chunk_connectivity, ensure_connectivity,
+};
 pub use map::CollisionMap;
-pub use systems::CollisionMapBuilt;
-pub use tile_type::{TileMarker, TileType};
+pub use placement::find_spawn_point;
+pub use raycast::Hit;
+pub use regions::WalkableRegions;
+pub use systems::{
+    CollisionMapBuilt, apply_tile_overrides, convert_water_edges_to_shore,
+    convert_water_edges_to_shore_in, reclassify_shore_in,
+};
+pub use tile_changes::{
+    CollisionMapUpdated, TileChanged, TileOverrides, apply_tile_change, map_grid_to_world,
+    world_grid_to_map,
+};
+pub use tile_type::{FootstepSurface, TerrainMovement, TileMarker, TileType};
 
 #[cfg(debug_assertions)]
 pub use debug::DebugCollisionEnabled;
@@ -18,12 +36,30 @@ pub struct CollisionPlugin;
 
 impl Plugin for CollisionPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<CollisionMapBuilt>().add_systems(
-            Update,
-            systems::build_collision_map
-                .run_if(resource_equals(CollisionMapBuilt(false)))
-                .run_if(in_state(GameState::Playing)),
-        );
+        app.init_resource::<CollisionMapBuilt>()
+            .init_resource::<TileOverrides>()
+            .add_message::<TileChanged>()
+            .add_message::<CollisionMapUpdated>()
+            .add_systems(
+                Update,
+                // 加载状态下也需要构建，确保进入游戏时碰撞地图已经就绪
+                systems::build_collision_map
+                    .run_if(resource_equals(CollisionMapBuilt(false)))
+                    .run_if(in_state(GameState::Loading).or(in_state(GameState::Playing))),
+            )
+            .add_systems(
+                Update,
+                tile_changes::apply_tile_changes
+                    .after(systems::build_collision_map)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                Update,
+                tile_changes::sync_tile_entities
+                    .after(systems::build_collision_map)
+                    .after(tile_changes::apply_tile_changes)
+                    .run_if(in_state(GameState::Loading).or(in_state(GameState::Playing))),
+            );
 
         #[cfg(debug_assertions)]
         app.init_resource::<DebugCollisionEnabled>().add_systems(
@@ -32,6 +68,8 @@ impl Plugin for CollisionPlugin {
                 debug::toggle_debug_collision,
                 debug::debug_draw_collision,
                 debug::debug_player_position,
+                debug::debug_draw_paths,
+                debug::update_debug_overlay,
             )
                 .run_if(in_state(GameState::Playing)),
         );
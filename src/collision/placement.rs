@@ -0,0 +1,50 @@
+use crate::collision::map::CollisionMap;
+use crate::collision::regions::WalkableRegions;
+use bevy::prelude::*;
+
+/// 查找可以放置圆形碰撞体的位置
+///
+/// 在所有瓦片中心中寻找离 `near` 最近、且对指定半径满足 `is_circle_clear` 的位置。
+/// 优先选择最大可行走连通区域中的瓦片，避免角色出生在封闭的小块陆地上；
+/// 最大区域中没有合适位置时退回到任意区域。
+/// 可用于玩家出生、重生和 NPC 放置。
+///
+/// # 参数
+/// - `map`: 碰撞地图
+/// - `near`: 期望位置的世界坐标
+/// - `radius`: 碰撞体半径
+///
+/// # 返回
+/// 找到的位置（瓦片中心的世界坐标），如果地图上没有任何空位则返回 None
+pub fn find_spawn_point(map: &CollisionMap, near: Vec2, radius: f32) -> Option<Vec2> {
+    let regions = WalkableRegions::compute(map);
+
+    if let Some(largest) = regions.largest() {
+        let in_largest = nearest_clear_tile(map, near, radius, |x, y| {
+            regions.region_at(x, y) == Some(largest)
+        });
+        if in_largest.is_some() {
+            return in_largest;
+        }
+    }
+
+    nearest_clear_tile(map, near, radius, |_, _| true)
+}
+
+/// 查找满足条件且离 `near` 最近的空闲瓦片中心
+fn nearest_clear_tile(
+    map: &CollisionMap,
+    near: Vec2,
+    radius: f32,
+    filter: impl Fn(i32, i32) -> bool,
+) -> Option<Vec2> {
+    (0..map.height())
+        .flat_map(|y| (0..map.width()).map(move |x| (x, y)))
+        .filter(|&(x, y)| filter(x, y))
+        .map(|(x, y)| map.grid_to_world(x, y))
+        .filter(|&center| map.is_circle_clear(center, radius))
+        .min_by(|a, b| {
+            a.distance_squared(near)
+                .total_cmp(&b.distance_squared(near))
+        })
+}
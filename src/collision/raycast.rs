@@ -0,0 +1,170 @@
+use crate::collision::map::CollisionMap;
+use crate::collision::tile_type::TileType;
+use bevy::prelude::*;
+
+/// 射线命中结果
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Hit {
+    /// 命中瓦片的碰撞地图网格坐标
+    pub grid: IVec2,
+    /// 命中瓦片的类型
+    pub tile_type: TileType,
+    /// 射线进入瓦片的世界坐标
+    pub point: Vec2,
+    /// 命中面的法线（起点已在遮挡瓦片内时为零向量）
+    pub normal: Vec2,
+}
+
+/// 计算从地图外的起点出发的射线进入地图范围时的距离和进入面的法线
+///
+/// # 参数
+/// - `bounds`: 地图范围（世界坐标）
+/// - `origin`: 射线起点
+/// - `dir`: 射线方向（单位向量）
+///
+/// # 返回
+/// 进入距离和法线，射线不经过地图范围时返回 None
+fn entry_into(bounds: Rect, origin: Vec2, dir: Vec2) -> Option<(f32, Vec2)> {
+    let mut t_enter = 0.0f32;
+    let mut t_exit = f32::INFINITY;
+    let mut normal = Vec2::ZERO;
+    for axis in 0..2 {
+        if dir[axis] == 0.0 {
+            if origin[axis] < bounds.min[axis] || origin[axis] > bounds.max[axis] {
+                return None;
+            }
+            continue;
+        }
+        let t1 = (bounds.min[axis] - origin[axis]) / dir[axis];
+        let t2 = (bounds.max[axis] - origin[axis]) / dir[axis];
+        let (near, far) = if t1 < t2 { (t1, t2) } else { (t2, t1) };
+        if near > t_enter {
+            t_enter = near;
+            normal = Vec2::ZERO;
+            normal[axis] = -dir[axis].signum();
+        }
+        t_exit = t_exit.min(far);
+    }
+    (t_enter <= t_exit).then_some((t_enter, normal))
+}
+
+impl CollisionMap {
+    /// 沿射线查找第一个遮挡视线的瓦片
+    ///
+    /// 使用 DDA 算法逐个遍历射线经过的瓦片，只检查 [`TileType::blocks_sight`]，
+    /// 因此水面不会挡住射线，树木和岩石会。起点在地图范围之外时从射线进入地图的位置开始遍历，
+    /// 射线离开地图范围时停止。
+    ///
+    /// # 参数
+    /// - `origin`: 射线起点的世界坐标
+    /// - `dir`: 射线方向（不需要归一化）
+    /// - `max_dist`: 最大距离（世界单位）
+    ///
+    /// # 返回
+    /// 最大距离内的第一个命中，没有命中或方向为零时返回 None
+    pub fn raycast(&self, origin: Vec2, dir: Vec2, max_dist: f32) -> Option<Hit> {
+        let tile_size = self.tile_size();
+        let dir = dir.normalize_or_zero();
+        let bounds = Rect::from_corners(
+            self.origin(),
+            self.origin() + Vec2::new(self.width() as f32, self.height() as f32) * tile_size,
+        );
+
+        // 起点在地图之外时，先沿射线走到地图边界
+        let (t_start, entry_normal) = if bounds.contains(origin) {
+            (0.0, Vec2::ZERO)
+        } else {
+            if dir == Vec2::ZERO {
+                return None;
+            }
+            let (t, normal) = entry_into(bounds, origin, dir)?;
+            if t > max_dist {
+                return None;
+            }
+            (t, normal)
+        };
+        let start = origin + dir * t_start;
+
+        // 以瓦片为单位的起点坐标，恰好落在地图上边缘或右边缘时归入最后一个瓦片
+        let local = (start - self.origin()) / tile_size;
+        let mut grid = local
+            .floor()
+            .as_ivec2()
+            .clamp(IVec2::ZERO, IVec2::new(self.width() - 1, self.height() - 1));
+
+        // 起点（或进入地图的位置）已经在遮挡瓦片内
+        let tile_type = self.get_tile(grid.x, grid.y)?;
+        if tile_type.blocks_sight() {
+            return Some(Hit {
+                grid,
+                tile_type,
+                point: start,
+                normal: entry_normal,
+            });
+        }
+
+        if dir == Vec2::ZERO {
+            return None;
+        }
+
+        let step = IVec2::new(dir.x.signum() as i32, dir.y.signum() as i32);
+        // 沿每个轴穿过一个完整瓦片需要的射线长度
+        let t_delta = Vec2::new((tile_size / dir.x).abs(), (tile_size / dir.y).abs());
+        // 到达每个轴上下一条瓦片边界时的射线长度（从起点算起）
+        let boundary = |axis: usize| {
+            let offset = (local[axis] - grid[axis] as f32).clamp(0.0, 1.0);
+            if dir[axis] > 0.0 {
+                t_start + (1.0 - offset) * t_delta[axis]
+            } else if dir[axis] < 0.0 {
+                t_start + offset * t_delta[axis]
+            } else {
+                f32::INFINITY
+            }
+        };
+        let mut t_max = Vec2::new(boundary(0), boundary(1));
+
+        loop {
+            let (t, normal) = if t_max.x < t_max.y {
+                grid.x += step.x;
+                let t = t_max.x;
+                t_max.x += t_delta.x;
+                (t, Vec2::new(-step.x as f32, 0.0))
+            } else {
+                grid.y += step.y;
+                let t = t_max.y;
+                t_max.y += t_delta.y;
+                (t, Vec2::new(0.0, -step.y as f32))
+            };
+
+            if t > max_dist {
+                return None;
+            }
+            let tile_type = self.get_tile(grid.x, grid.y)?;
+            if tile_type.blocks_sight() {
+                return Some(Hit {
+                    grid,
+                    tile_type,
+                    point: origin + dir * t,
+                    normal,
+                });
+            }
+        }
+    }
+
+    /// 检查两点之间的视线是否畅通
+    ///
+    /// # 参数
+    /// - `a`: 起点的世界坐标
+    /// - `b`: 终点的世界坐标
+    ///
+    /// # 返回
+    /// 两点都在地图范围内且之间没有遮挡视线的瓦片时返回 true。
+    /// 任一点在地图范围之外时地形未知，视线不算畅通
+    pub fn line_of_sight(&self, a: Vec2, b: Vec2) -> bool {
+        let inside = |point: Vec2| {
+            let grid = self.world_to_grid(point);
+            self.get_tile(grid.x, grid.y).is_some()
+        };
+        inside(a) && inside(b) && self.raycast(a, b - a, a.distance(b)).is_none()
+    }
+}
@@ -0,0 +1,104 @@
+use crate::collision::map::CollisionMap;
+use bevy::prelude::*;
+use std::collections::VecDeque;
+
+/// 可行走区域标记
+///
+/// 对碰撞地图做 4 邻域洪水填充，为每个可行走瓦片标记所属的连通区域。
+/// 空白瓦片不属于任何区域（见 [`TileType::is_ground`](crate::collision::TileType::is_ground)）
+pub struct WalkableRegions {
+    /// 每个瓦片所属的区域编号（不可行走的瓦片为 None）
+    labels: Vec<Option<usize>>,
+    /// 每个区域包含的瓦片数量（按区域编号索引）
+    sizes: Vec<usize>,
+    /// 网格宽度
+    width: i32,
+    /// 网格高度
+    height: i32,
+}
+
+impl WalkableRegions {
+    /// 计算碰撞地图中的所有可行走连通区域
+    ///
+    /// # 参数
+    /// - `map`: 碰撞地图
+    ///
+    /// # 返回
+    /// 区域标记结果
+    pub fn compute(map: &CollisionMap) -> Self {
+        let width = map.width();
+        let height = map.height();
+        let mut labels = vec![None; (width * height) as usize];
+        let mut sizes = Vec::new();
+        let mut queue = VecDeque::new();
+
+        for y in 0..height {
+            for x in 0..width {
+                // 跳过不可行走或已标记的瓦片
+                if !map.is_ground(x, y) || labels[(y * width + x) as usize].is_some() {
+                    continue;
+                }
+
+                // 从该瓦片开始洪水填充一个新区域
+                let region = sizes.len();
+                let mut size = 0;
+                labels[(y * width + x) as usize] = Some(region);
+                queue.push_back(IVec2::new(x, y));
+
+                while let Some(cell) = queue.pop_front() {
+                    size += 1;
+                    for offset in [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y] {
+                        let next = cell + offset;
+                        if !map.is_ground(next.x, next.y) {
+                            continue;
+                        }
+                        let index = (next.y * width + next.x) as usize;
+                        if labels[index].is_none() {
+                            labels[index] = Some(region);
+                            queue.push_back(next);
+                        }
+                    }
+                }
+
+                sizes.push(size);
+            }
+        }
+
+        Self {
+            labels,
+            sizes,
+            width,
+            height,
+        }
+    }
+
+    /// 获取指定瓦片所属的区域编号
+    ///
+    /// # 返回
+    /// 区域编号，如果瓦片不可行走或超出范围则返回 None
+    pub fn region_at(&self, x: i32, y: i32) -> Option<usize> {
+        if x < 0 || x >= self.width || y < 0 || y >= self.height {
+            return None;
+        }
+        self.labels[(y * self.width + x) as usize]
+    }
+
+    /// 区域数量
+    pub fn count(&self) -> usize {
+        self.sizes.len()
+    }
+
+    /// 每个区域包含的瓦片数量（按区域编号索引）
+    pub fn sizes(&self) -> &[usize] {
+        &self.sizes
+    }
+
+    /// 获取最大的区域编号
+    pub fn largest(&self) -> Option<usize> {
+        self.sizes
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, size)| **size)
+            .map(|(region, _)| region)
+    }
+}
@@ -1,122 +1,102 @@
 use crate::collision::map::CollisionMap;
+use crate::collision::tile_changes::{TileOverrides, world_grid_to_map};
 use crate::collision::tile_type::{TileMarker, TileType};
 use crate::config::map::{GRID_X, GRID_Y, TILE_SIZE};
+use crate::map::chunk::{CHUNK_STRIDE_X, CHUNK_STRIDE_Y, MapChunk, chunk_origin};
 use bevy::prelude::*;
-use std::collections::HashMap;
 use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
 
 /// 碰撞地图构建完成标志资源
 ///
-/// 用于追踪碰撞地图是否已经构建完成，避免重复构建
+/// 用于追踪碰撞地图是否已经构建完成，避免重复构建。
+/// 加载或卸载区块时会被重置为 false，触发重新构建。
 #[derive(Resource, Default, Eq, PartialEq)]
 pub struct CollisionMapBuilt(pub bool);
 
 /// 构建碰撞地图系统
 ///
-/// 从场景中的瓦片实体收集数据，构建用于碰撞检测的地图。
-/// 系统会扫描所有带有 TileMarker 的实体，根据它们的位置和类型创建碰撞地图。
+/// 从已加载区块中的瓦片实体收集数据，构建用于碰撞检测的地图。
+/// 碰撞地图覆盖所有已加载区块的包围矩形，区块加载或卸载后会重新构建。
 ///
 /// # 处理流程
-/// 1. 遍历所有瓦片实体，收集位置和类型信息
-/// 2. 对于同一网格位置的多个瓦片，只保留 Z 轴最高的（最上层）
-/// 3. 计算实际地图边界
+/// 1. 根据已加载区块计算地图范围和原点
+/// 2. 遍历所有瓦片实体，结合父区块位置计算其网格坐标
+/// 3. 对于同一网格位置的多个瓦片，只保留 Z 轴最高的（最上层）
 /// 4. 创建 CollisionMap 并填充瓦片类型
-/// 5. 将水边缘转换为海岸类型
+/// 5. 应用运行时改变过的瓦片和各区块加载时开辟的瓦片（[`TileOverrides`]），并重新检测海岸
 /// 6. 插入 CollisionMap 资源并标记构建完成
 ///
+/// 连通性在区块加载时按区块检查（[`TileOverrides::carve_chunk`]），
+/// 已加载范围的边界不会被当作屏障
+///
 /// # 参数
 /// - `commands`: Bevy 命令队列，用于插入资源
 /// - `built`: 碰撞地图构建状态资源
-/// - `tile_query`: 查询所有瓦片实体（TileMarker 和 Transform 组件）
+/// - `overrides`: 运行时改变过的瓦片和开辟的瓦片
+/// - `chunk_query`: 查询所有区块实体
+/// - `tile_query`: 查询所有瓦片实体（TileMarker、Transform 和父区块）
 pub fn build_collision_map(
     mut commands: Commands,
     mut built: ResMut<CollisionMapBuilt>,
-    tile_query: Query<(&TileMarker, &Transform)>,
+    overrides: Res<TileOverrides>,
+    chunk_query: Query<(&MapChunk, &Transform)>,
+    tile_query: Query<(&TileMarker, &Transform, &ChildOf)>,
 ) {
-    let mut tile_iter = tile_query.iter();
-    // 如果场景中没有瓦片实体，直接返回
-    let Some((first_marker, first_transform)) = tile_iter.next() else {
+    // 计算已加载区块的坐标范围
+    let mut chunk_iter = chunk_query.iter();
+    // 如果场景中没有区块，直接返回
+    let Some((first_chunk, _)) = chunk_iter.next() else {
         return;
     };
+    let (min_chunk, max_chunk) = chunk_iter.fold(
+        (first_chunk.coord, first_chunk.coord),
+        |(min, max), (chunk, _)| (min.min(chunk.coord), max.max(chunk.coord)),
+    );
+
+    // 计算碰撞地图尺寸（相邻区块共享边缘）
+    let width = (max_chunk.x - min_chunk.x) * CHUNK_STRIDE_X + GRID_X as i32;
+    let height = (max_chunk.y - min_chunk.y) * CHUNK_STRIDE_Y + GRID_Y as i32;
+    let origin = chunk_origin(min_chunk);
 
-    // 计算网格原点坐标（地图中心）
-    let grid_origin_x = -TILE_SIZE * GRID_X as f32 / 2.0;
-    let grid_origin_y = -TILE_SIZE * GRID_Y as f32 / 2.0;
+    // 创建碰撞地图实例
+    let mut map = CollisionMap::new(width, height, TILE_SIZE, origin.x, origin.y);
 
-    // 初始化地图边界变量
-    let (mut min_x, mut max_x) = (i32::MAX, i32::MIN);
-    let (mut min_y, mut max_y) = (i32::MAX, i32::MIN);
     // 使用 HashMap 追踪每个网格位置的最高层瓦片
-    // Key: (网格X, 网格Y), Value: (瓦片类型, Z轴高度)
-    let mut layer_tracker: HashMap<(i32, i32), (TileType, f32)> = HashMap::new();
-    let mut tile_count: usize = 0;
-
-    // 定义处理单个瓦片的闭包函数
-    let mut process_tile = |marker: &TileMarker, transform: &Transform| {
-        // 统计瓦片数量
-        tile_count += 1;
-
-        // 获取瓦片的世界坐标
-        let world_x = transform.translation.x;
-        let world_y = transform.translation.y;
-        let world_z = transform.translation.z;
-
-        // 将世界坐标转换为网格坐标
-        let grid_x = ((world_x - grid_origin_x) / TILE_SIZE).floor() as i32;
-        let grid_y = ((world_y - grid_origin_y) / TILE_SIZE).floor() as i32;
-
-        // 更新地图边界
-        min_x = min_x.min(grid_x);
-        max_x = max_x.max(grid_x);
-        min_y = min_y.min(grid_y);
-        max_y = max_y.max(grid_y);
+    // Key: 网格坐标, Value: (瓦片类型, Z轴高度)
+    let mut layer_tracker: HashMap<IVec2, (TileType, f32)> = HashMap::new();
+
+    for (marker, transform, child_of) in tile_query.iter() {
+        // 瓦片的位置相对于父区块
+        let Ok((_, chunk_transform)) = chunk_query.get(child_of.parent()) else {
+            continue;
+        };
+        let world_pos = chunk_transform.translation + transform.translation;
+        let grid = map.world_to_grid(world_pos.truncate());
 
         // 处理同一位置的多个瓦片（多层结构）
         // 只保留 Z 轴最高的瓦片（最上层）
-        match layer_tracker.entry((grid_x, grid_y)) {
+        match layer_tracker.entry(grid) {
             Entry::Occupied(mut entry) => {
                 // 如果当前瓦片比已存在的瓦片更高，替换它
-                if world_z > entry.get().1 {
-                    *entry.get_mut() = (marker.tile_type, world_z);
+                if world_pos.z > entry.get().1 {
+                    *entry.get_mut() = (marker.tile_type, world_pos.z);
                 }
             }
             Entry::Vacant(entry) => {
                 // 该位置还没有瓦片，直接插入
-                entry.insert((marker.tile_type, world_z));
+                entry.insert((marker.tile_type, world_pos.z));
             }
         }
-    };
-
-    // 处理第一个瓦片
-    process_tile(first_marker, first_transform);
-    // 处理剩余的所有瓦片
-    for (marker, transform) in tile_iter {
-        process_tile(marker, transform);
     }
 
-    // 计算实际地图尺寸
-    let actual_width = max_x - min_x + 1;
-    let actual_height = max_y - min_y + 1;
-
-    // 创建碰撞地图实例
-    let mut map = CollisionMap::new(
-        actual_width,
-        actual_height,
-        TILE_SIZE,
-        grid_origin_x,
-        grid_origin_y,
-    );
-
     // 将收集到的瓦片数据填充到碰撞地图中
-    // 注意：需要将绝对网格坐标转换为相对坐标（0-based）
-    for ((grid_x, grid_y), (tile_type, _z)) in layer_tracker.iter() {
-        let local_x = grid_x - min_x;
-        let local_y = grid_y - min_y;
-        map.set_tile(local_x, local_y, *tile_type);
+    for (grid, (tile_type, _z)) in layer_tracker.iter() {
+        map.set_tile(grid.x, grid.y, *tile_type);
     }
 
-    // 将水的边缘瓦片转换为海岸类型
-    convert_water_edges_to_shore(&mut map);
+    // 应用运行时改变过的瓦片和开辟的瓦片，并检测海岸
+    apply_tile_overrides(&mut map, &overrides);
 
     // 将碰撞地图插入为资源
     commands.insert_resource(map);
@@ -124,15 +104,37 @@ pub fn build_collision_map(
     built.0 = true;
 }
 
+/// 应用瓦片覆盖并重新检测整张地图的海岸
+///
+/// 覆盖的瓦片保持设置的类型，其余的水和海岸瓦片按 [`reclassify_shore_in`] 重新分类。
+/// 与运行时局部更新（[`apply_tile_change`]）使用相同的规则，因此完整重建和局部更新的结果一致
+///
+/// # 参数
+/// - `map`: 碰撞地图的可变引用
+/// - `overrides`: 运行时改变过的瓦片
+///
+/// [`apply_tile_change`]: crate::collision::apply_tile_change
+pub fn apply_tile_overrides(map: &mut CollisionMap, overrides: &TileOverrides) {
+    let mut fixed = HashSet::new();
+    for (grid, tile_type) in overrides.iter() {
+        let grid = world_grid_to_map(map, grid);
+        map.set_tile(grid.x, grid.y, tile_type);
+        fixed.insert(grid);
+    }
+
+    let max = IVec2::new(map.width() - 1, map.height() - 1);
+    reclassify_shore_in(map, IVec2::ZERO, max, |cell| fixed.contains(&cell));
+}
+
 /// 将水边缘转换为海岸类型
 ///
 /// 遍历地图中的所有水域瓦片，检查其 8 个方向的邻居。
-/// 如果水瓦片的任何邻居是可行走的（陆地），则将该水瓦片标记为海岸。
+/// 如果水瓦片的任何邻居是陆地（[`TileType::is_land`]），则将该水瓦片标记为海岸。
 ///
 /// # 处理逻辑
 /// 1. 遍历地图中每个位置
 /// 2. 如果当前位置是水域，检查其 8 个方向的邻居
-/// 3. 如果有任何邻居是可行走的（非水域），则该水瓦片是海岸
+/// 3. 如果有任何邻居是陆地（可行走且不是海岸），则该水瓦片是海岸
 /// 4. 将所有海岸瓦片的类型从 Water 改为 Shore
 ///
 /// # 作用
@@ -142,13 +144,27 @@ pub fn build_collision_map(
 ///
 /// # 参数
 /// - `map`: 碰撞地图的可变引用
-fn convert_water_edges_to_shore(map: &mut CollisionMap) {
+pub fn convert_water_edges_to_shore(map: &mut CollisionMap) {
+    let max = IVec2::new(map.width() - 1, map.height() - 1);
+    convert_water_edges_to_shore_in(map, IVec2::ZERO, max);
+}
+
+/// 将指定范围内的水边缘转换为海岸类型
+///
+/// 与 [`convert_water_edges_to_shore`] 相同，但只检查 `min..=max` 范围内的瓦片，
+/// 用于瓦片在运行时改变后的局部更新
+///
+/// # 参数
+/// - `map`: 碰撞地图的可变引用
+/// - `min`: 范围左下角的网格坐标（包含）
+/// - `max`: 范围右上角的网格坐标（包含）
+pub fn convert_water_edges_to_shore_in(map: &mut CollisionMap, min: IVec2, max: IVec2) {
     let mut shores = Vec::new();
 
-    // 遍历地图的每一行
-    for y in 0..map.height() {
-        // 遍历地图的每一列
-        for x in 0..map.width() {
+    // 遍历范围内的每一行
+    for y in min.y..=max.y {
+        // 遍历范围内的每一列
+        for x in min.x..=max.x {
             // 跳过非水域瓦片
             if map.get_tile(x, y) != Some(TileType::Water) {
                 continue;
@@ -168,8 +184,8 @@ fn convert_water_edges_to_shore(map: &mut CollisionMap) {
 
             // 检查所有邻居
             for (nx, ny) in neighbors {
-                // 如果邻居是可行走的（陆地），则当前水瓦片是海岸
-                if map.is_walkable(nx, ny) {
+                // 如果邻居是陆地，则当前水瓦片是海岸
+                if map.get_tile(nx, ny).is_some_and(|tile| tile.is_land()) {
                     shores.push((x, y));
                     break;
                 }
@@ -182,3 +198,52 @@ fn convert_water_edges_to_shore(map: &mut CollisionMap) {
         map.set_tile(x, y, TileType::Shore);
     }
 }
+
+/// 重新检测指定范围内的海岸
+///
+/// 瓦片在运行时改变后和完整重建时使用：范围内的水和海岸瓦片按周围是否有陆地（[`TileType::is_land`]）
+/// 重新分类，有陆地邻居的深水改为海岸，失去所有陆地邻居的海岸变回深水。
+/// 所有瓦片先根据改变前的地图判断，再一起更新，因此结果不依赖遍历顺序
+///
+/// # 参数
+/// - `map`: 碰撞地图的可变引用
+/// - `min`: 范围左下角的网格坐标（包含）
+/// - `max`: 范围右上角的网格坐标（包含）
+/// - `fixed`: 返回 true 的瓦片（例如被明确设置过类型的瓦片）保持不变
+pub fn reclassify_shore_in(
+    map: &mut CollisionMap,
+    min: IVec2,
+    max: IVec2,
+    fixed: impl Fn(IVec2) -> bool,
+) {
+    let is_land = |x: i32, y: i32| map.get_tile(x, y).is_some_and(|tile| tile.is_land());
+
+    let mut changes = Vec::new();
+    for y in min.y..=max.y {
+        for x in min.x..=max.x {
+            let current = map.get_tile(x, y);
+            if !matches!(current, Some(TileType::Water | TileType::Shore))
+                || fixed(IVec2::new(x, y))
+            {
+                continue;
+            }
+
+            let has_land = (-1..=1)
+                .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+                .filter(|offset| *offset != (0, 0))
+                .any(|(dx, dy)| is_land(x + dx, y + dy));
+            let new_type = if has_land {
+                TileType::Shore
+            } else {
+                TileType::Water
+            };
+            if current != Some(new_type) {
+                changes.push((x, y, new_type));
+            }
+        }
+    }
+
+    for (x, y, tile_type) in changes {
+        map.set_tile(x, y, tile_type);
+    }
+}
@@ -0,0 +1,277 @@
+use crate::collision::connectivity::{RegionStats, ensure_chunk_connectivity};
+use crate::collision::map::CollisionMap;
+use crate::collision::systems::reclassify_shore_in;
+use crate::collision::tile_type::{TileMarker, TileType};
+use crate::config::map::TILE_SIZE;
+use crate::map::chunk::{MapChunk, world_origin, world_to_cell};
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+/// 被开辟为浅滩的深水瓦片的颜色（与精灵颜色相乘，使水面变浅）
+const SHORE_TINT: Color = Color::srgb(0.8, 0.95, 1.0);
+
+/// 瓦片变化消息
+///
+/// 游戏玩法在运行时改变瓦片时发送（例如砍树、建墙、水面结冰），
+/// 碰撞地图会立即更新，之后重建碰撞地图时也会保留该变化
+#[derive(Message, Debug, Copy, Clone)]
+pub struct TileChanged {
+    /// 世界网格坐标（出生区块左下角为原点），区块加载或卸载后仍然有效
+    pub grid: IVec2,
+    /// 新的瓦片类型
+    pub new_type: TileType,
+}
+
+/// 碰撞地图更新消息
+///
+/// 处理完 [`TileChanged`] 后发送，包含所有类型实际发生变化的瓦片，
+/// 包括因此重新检测出的海岸
+#[derive(Message, Debug, Clone)]
+pub struct CollisionMapUpdated {
+    /// 发生变化的世界网格坐标
+    pub tiles: Vec<IVec2>,
+}
+
+/// 瓦片覆盖资源
+///
+/// 记录运行时改变过的瓦片和连通性检查开辟的瓦片，重建碰撞地图时覆盖瓦片实体得到的类型
+#[derive(Resource, Default, Debug)]
+pub struct TileOverrides {
+    /// 游戏玩法改变的瓦片（世界网格坐标到瓦片类型的映射），会保存到地图存档
+    tiles: HashMap<IVec2, TileType>,
+    /// 每个已加载区块开辟的瓦片（区块坐标到开辟瓦片的映射）
+    ///
+    /// 只由区块自己的瓦片决定，区块加载时重新计算、卸载时移除，不保存到地图存档
+    carved: HashMap<IVec2, HashMap<IVec2, TileType>>,
+}
+
+impl TileOverrides {
+    /// 获取瓦片的覆盖类型（游戏玩法的改变优先于开辟的瓦片）
+    pub fn get(&self, grid: IVec2) -> Option<TileType> {
+        self.tiles
+            .get(&grid)
+            .or_else(|| self.carved.values().find_map(|tiles| tiles.get(&grid)))
+            .copied()
+    }
+
+    /// 记录瓦片的覆盖类型
+    pub fn insert(&mut self, grid: IVec2, tile_type: TileType) {
+        self.tiles.insert(grid, tile_type);
+    }
+
+    /// 遍历所有覆盖的瓦片（包括开辟的瓦片，每个网格只出现一次）
+    pub fn iter(&self) -> impl Iterator<Item = (IVec2, TileType)> + '_ {
+        let mut carved: HashMap<IVec2, TileType> = HashMap::new();
+        for tiles in self.carved.values() {
+            carved.extend(tiles);
+        }
+        carved.retain(|grid, _| !self.tiles.contains_key(grid));
+        self.changes().chain(carved)
+    }
+
+    /// 遍历游戏玩法改变的瓦片（不包括开辟的瓦片），用于保存地图
+    pub fn changes(&self) -> impl Iterator<Item = (IVec2, TileType)> + '_ {
+        self.tiles
+            .iter()
+            .map(|(grid, tile_type)| (*grid, *tile_type))
+    }
+
+    /// 检查区块的连通性，记录为孤立区域开辟的瓦片
+    ///
+    /// 替换该区块之前开辟的瓦片。只使用区块自己的瓦片（[`ensure_chunk_connectivity`]），
+    /// 因此结果与区块的加载顺序和相邻区块是否已加载无关
+    ///
+    /// # 参数
+    /// - `chunk`: 区块坐标
+    /// - `tiles`: 只包含该区块的碰撞地图，开辟的瓦片会写入其中
+    ///
+    /// # 返回
+    /// 区块的区域统计结果
+    pub fn carve_chunk(&mut self, chunk: IVec2, tiles: &mut CollisionMap) -> RegionStats {
+        let (stats, carved) = ensure_chunk_connectivity(tiles);
+        let carved: HashMap<IVec2, TileType> = carved
+            .iter()
+            .map(|tile| (map_grid_to_world(tiles, tile.grid), tile.to))
+            .collect();
+        if carved.is_empty() {
+            self.carved.remove(&chunk);
+        } else {
+            self.carved.insert(chunk, carved);
+        }
+        stats
+    }
+
+    /// 移除区块开辟的瓦片（区块卸载时）
+    pub fn remove_chunk(&mut self, chunk: IVec2) {
+        self.carved.remove(&chunk);
+    }
+
+    /// 覆盖的瓦片数量
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// 没有覆盖任何瓦片时返回 true
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty() && self.carved.is_empty()
+    }
+
+    /// 清除所有覆盖（例如重新生成地图时）
+    pub fn clear(&mut self) {
+        self.tiles.clear();
+        self.carved.clear();
+    }
+}
+
+/// 将世界网格坐标转换为碰撞地图的网格坐标
+pub fn world_grid_to_map(map: &CollisionMap, grid: IVec2) -> IVec2 {
+    map.world_to_grid(world_origin() + (grid.as_vec2() + 0.5) * TILE_SIZE)
+}
+
+/// 将碰撞地图的网格坐标转换为世界网格坐标
+pub fn map_grid_to_world(map: &CollisionMap, grid: IVec2) -> IVec2 {
+    ((map.grid_to_world(grid.x, grid.y) - world_origin()) / TILE_SIZE)
+        .floor()
+        .as_ivec2()
+}
+
+/// 处理瓦片变化
+///
+/// 记录覆盖并更新碰撞地图中的瓦片，然后只在变化瓦片周围重新检测海岸
+/// （深水可能变为海岸，失去陆地邻居的海岸变回深水），最后发送 [`CollisionMapUpdated`] 通知其他系统
+///
+/// # 参数
+/// - `changes`: 瓦片变化消息
+/// - `overrides`: 瓦片覆盖资源
+/// - `map`: 碰撞地图（尚未构建时只记录覆盖，构建时应用）
+/// - `updates`: 碰撞地图更新消息
+pub fn apply_tile_changes(
+    mut changes: MessageReader<TileChanged>,
+    mut overrides: ResMut<TileOverrides>,
+    map: Option<ResMut<CollisionMap>>,
+    mut updates: MessageWriter<CollisionMapUpdated>,
+) {
+    if changes.is_empty() {
+        return;
+    }
+    let Some(mut map) = map else {
+        for change in changes.read() {
+            overrides.tiles.insert(change.grid, change.new_type);
+        }
+        return;
+    };
+
+    let mut updated = Vec::new();
+    for change in changes.read() {
+        updated.extend(apply_tile_change(&mut map, &mut overrides, change));
+    }
+
+    if !updated.is_empty() {
+        debug!("Collision map updated: {} tiles changed", updated.len());
+        updates.write(CollisionMapUpdated { tiles: updated });
+    }
+}
+
+/// 应用单个瓦片变化
+///
+/// 记录覆盖并更新碰撞地图中的瓦片，然后只在变化瓦片周围重新检测海岸。
+/// 覆盖过的瓦片保持设置的类型，结果与应用同样覆盖后完整构建（[`apply_tile_overrides`]）一致
+///
+/// # 返回
+/// 类型实际发生变化的世界网格坐标
+///
+/// [`apply_tile_overrides`]: crate::collision::apply_tile_overrides
+pub fn apply_tile_change(
+    map: &mut CollisionMap,
+    overrides: &mut TileOverrides,
+    change: &TileChanged,
+) -> Vec<IVec2> {
+    overrides.tiles.insert(change.grid, change.new_type);
+
+    let grid = world_grid_to_map(map, change.grid);
+    if map
+        .get_tile(grid.x, grid.y)
+        .is_none_or(|tile| tile == change.new_type)
+    {
+        return Vec::new();
+    }
+
+    // 记录周围瓦片的旧类型，海岸检测可能改变相邻的水和海岸瓦片
+    let min = grid - IVec2::ONE;
+    let max = grid + IVec2::ONE;
+    let before: Vec<(IVec2, Option<TileType>)> = (min.y..=max.y)
+        .flat_map(|y| (min.x..=max.x).map(move |x| IVec2::new(x, y)))
+        .map(|cell| (cell, map.get_tile(cell.x, cell.y)))
+        .collect();
+    // 覆盖过的瓦片（包括本次改变的瓦片）保持设置的类型
+    let fixed: Vec<IVec2> = before
+        .iter()
+        .map(|(cell, _)| *cell)
+        .filter(|cell| overrides.get(map_grid_to_world(map, *cell)).is_some())
+        .collect();
+
+    map.set_tile(grid.x, grid.y, change.new_type);
+    reclassify_shore_in(map, min, max, |cell| fixed.contains(&cell));
+
+    before
+        .into_iter()
+        .filter(|(cell, old)| map.get_tile(cell.x, cell.y) != *old)
+        .map(|(cell, _)| map_grid_to_world(map, cell))
+        .collect()
+}
+
+/// 同步瓦片实体与瓦片覆盖
+///
+/// 覆盖类型与瓦片实体不一致时更新实体：被移除的道具（树木、岩石）直接删除，
+/// 改为浅滩的深水换成浅滩标记和较浅的颜色。覆盖变化时检查所有瓦片实体，否则只检查新生成的，
+/// 因此重新加载的区块不会恢复已经被开辟或改变的瓦片
+///
+/// # 参数
+/// - `commands`: 命令缓冲
+/// - `overrides`: 瓦片覆盖资源
+/// - `chunks`: 区块的变换
+/// - `tiles`: 瓦片实体
+#[allow(clippy::type_complexity)]
+pub fn sync_tile_entities(
+    mut commands: Commands,
+    overrides: Res<TileOverrides>,
+    chunks: Query<&Transform, With<MapChunk>>,
+    mut tiles: Query<(
+        Entity,
+        Mut<TileMarker>,
+        &Transform,
+        &ChildOf,
+        Option<&mut Sprite>,
+    )>,
+) {
+    if overrides.is_empty() {
+        return;
+    }
+    let check_all = overrides.is_changed();
+
+    for (entity, mut marker, transform, child_of, sprite) in tiles.iter_mut() {
+        if !check_all && !marker.is_added() {
+            continue;
+        }
+        let Ok(chunk_transform) = chunks.get(child_of.parent()) else {
+            continue;
+        };
+        let cell = world_to_cell((chunk_transform.translation + transform.translation).truncate());
+        let Some(tile_type) = overrides.get(cell) else {
+            continue;
+        };
+
+        match (marker.tile_type, tile_type) {
+            (TileType::Tree | TileType::Rock, to) if to != marker.tile_type => {
+                commands.entity(entity).despawn();
+            }
+            (TileType::Water, TileType::Shore) => {
+                marker.tile_type = TileType::Shore;
+                if let Some(mut sprite) = sprite {
+                    sprite.color = SHORE_TINT;
+                }
+            }
+            _ => {}
+        }
+    }
+}
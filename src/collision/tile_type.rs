@@ -1,6 +1,37 @@
 use bevy::prelude::Component;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+/// 脚步所在的地面类型，决定脚步声和脚步粒子
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum FootstepSurface {
+    Dirt,
+    Grass,
+    Water,
+    Stone,
+}
+
+/// 地形对移动的影响
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TerrainMovement {
+    /// 移动速度倍率
+    pub speed_multiplier: f32,
+    /// 是否可以跑步
+    pub can_run: bool,
+    /// 脚步的地面类型
+    pub surface: FootstepSurface,
+}
+
+impl Default for TerrainMovement {
+    fn default() -> Self {
+        Self {
+            speed_multiplier: 1.0,
+            can_run: true,
+            surface: FootstepSurface::Dirt,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
 pub enum TileType {
     // Walkable terrain
     #[default]
@@ -16,6 +47,18 @@ pub enum TileType {
 }
 
 impl TileType {
+    /// 所有瓦片类型（按声明顺序）
+    pub const ALL: [TileType; 8] = [
+        TileType::Empty,
+        TileType::Dirt,
+        TileType::Grass,
+        TileType::YellowGrass,
+        TileType::Shore,
+        TileType::Water,
+        TileType::Tree,
+        TileType::Rock,
+    ];
+
     /// 检查瓦片是否可行走
     ///
     /// 返回 false 的瓦片类型：
@@ -30,6 +73,75 @@ impl TileType {
         !matches!(self, TileType::Water | TileType::Tree | TileType::Rock)
     }
 
+    /// 检查瓦片是否属于可到达的地面
+    ///
+    /// 与 [`TileType::is_walkable`] 相同，但空白瓦片（没有任何地形覆盖的网格）不算地面，
+    /// 连通性检查和区域划分不会把空白瓦片当作可行走区域或开辟路径穿过它们
+    pub fn is_ground(&self) -> bool {
+        self.is_walkable() && *self != TileType::Empty
+    }
+
+    /// 检查瓦片是否算作海岸检测中的陆地
+    ///
+    /// 可行走且不是海岸的瓦片。海岸本身不算陆地，否则海岸会让相邻的深水继续变为海岸，
+    /// 完整构建和局部更新都使用这个判断，因此两者的结果一致
+    pub fn is_land(&self) -> bool {
+        self.is_walkable() && *self != TileType::Shore
+    }
+
+    /// 检查瓦片是否遮挡视线
+    ///
+    /// 与是否可行走无关：
+    /// - Tree, Rock: 既不可行走也遮挡视线
+    /// - Water: 不可行走，但可以看过去（射线和投射物可以越过水面）
+    pub fn blocks_sight(&self) -> bool {
+        matches!(self, TileType::Tree | TileType::Rock)
+    }
+
+    /// 获取瓦片对移动的影响
+    ///
+    /// - Dirt: 泥土小路，移动稍快
+    /// - Shore: 浅水，移动变慢且不能跑步
+    /// - 不可行走的瓦片只在角色被推入时生效
+    pub fn movement(&self) -> TerrainMovement {
+        let (speed_multiplier, can_run, surface) = match self {
+            TileType::Empty => (1.0, true, FootstepSurface::Dirt),
+            TileType::Dirt => (1.15, true, FootstepSurface::Dirt),
+            TileType::Grass => (1.0, true, FootstepSurface::Grass),
+            TileType::YellowGrass => (0.95, true, FootstepSurface::Grass),
+            TileType::Shore => (0.6, false, FootstepSurface::Water),
+            TileType::Water => (0.4, false, FootstepSurface::Water),
+            TileType::Tree => (1.0, true, FootstepSurface::Grass),
+            TileType::Rock => (1.0, true, FootstepSurface::Stone),
+        };
+        TerrainMovement {
+            speed_multiplier,
+            can_run,
+            surface,
+        }
+    }
+
+    /// 获取瓦片在 ASCII 地图中的字符
+    pub fn symbol(&self) -> char {
+        match self {
+            TileType::Empty => ' ',
+            TileType::Dirt => '.',
+            TileType::Grass => '"',
+            TileType::YellowGrass => ':',
+            TileType::Shore => '~',
+            TileType::Water => '=',
+            TileType::Tree => 'T',
+            TileType::Rock => 'R',
+        }
+    }
+
+    /// 根据 ASCII 地图中的字符获取瓦片类型，无法识别时返回 None
+    pub fn from_symbol(symbol: char) -> Option<TileType> {
+        TileType::ALL
+            .into_iter()
+            .find(|tile_type| tile_type.symbol() == symbol)
+    }
+
     /// Get the collision adjustment for this tile type.
     /// Positive = push player away, negative = allow corner cutting.
     pub fn collision_adjustment(&self) -> f32 {
@@ -4,6 +4,9 @@ pub mod player {
     pub const PLAYER_Z_POSITION: f32 = 20.0;
 
     pub const PLAYER_SCALE: f32 = 1.2;
+
+    /// 角色每移动该距离发出一次脚步
+    pub const FOOTSTEP_STRIDE: f32 = 40.0;
 }
 
 pub mod map {
@@ -13,13 +16,54 @@ pub mod map {
     pub const GRID_Y: u32 = 18;
 
     pub const NODE_SIZE_Z: f32 = 1.0;
+
+    /// 玩家周围需要加载的区块半径（以区块为单位）
+    pub const CHUNK_LOAD_RADIUS: i32 = 1;
+    /// 超出该半径（以区块为单位）的区块会被卸载
+    pub const CHUNK_UNLOAD_RADIUS: i32 = 2;
+
+    /// 深度排序覆盖的 y 范围（以玩家所在区块的下边缘为中心），范围内每个 y 坐标都有不同的深度。
+    /// 覆盖卸载半径内的所有区块并留出一个区块的余量，超出范围的实体与范围边界使用相同的深度
+    pub const Y_SORT_RANGE: f32 =
+        (2 * (CHUNK_UNLOAD_RADIUS + 2)) as f32 * (GRID_Y - 1) as f32 * TILE_SIZE;
+
+    /// 生成失败时每个区块最多尝试的种子数量
+    pub const GENERATION_RETRY_BUDGET: u32 = 5;
+
+    /// 生物群系噪声的尺度（每个噪声格跨越的瓦片数量），越大生物群系越大
+    pub const BIOME_SCALE: f32 = 40.0;
+
+    /// 连通孤立区域时允许的最大开辟代价（道具每格 1，深水每格 2）
+    pub const MAX_CARVE_COST: u32 = 12;
 }
 
 pub mod pickup {
     pub const DEFAULT_RADIUS: f32 = 40.0;
 }
 
+pub mod navigation {
+    /// 导航代理的默认移动速度（世界单位/秒）
+    pub const DEFAULT_SPEED: f32 = 120.0;
+    /// 距离路径点小于该值时视为到达，继续前往下一个路径点
+    pub const WAYPOINT_TOLERANCE: f32 = 4.0;
+    /// 单次寻路最多展开的节点数，超过后视为没有路径
+    pub const MAX_SEARCH_NODES: usize = 50_000;
+}
+
 pub mod camera {
     pub const CAMERA_LERP_SPEED: f32 = 6.0;
     pub const CAMERA_Z: f32 = 1000.0;
 }
+
+pub mod minimap {
+    /// 小地图图像的边长（像素）
+    pub const IMAGE_SIZE: u32 = 192;
+    /// 小地图在屏幕上显示的边长
+    pub const DISPLAY_SIZE: f32 = 240.0;
+    /// 小地图与屏幕边缘的距离
+    pub const MARGIN: f32 = 12.0;
+    /// 可选的缩放级别（每个瓦片占用的像素数），越大显示的范围越小
+    pub const ZOOM_LEVELS: [u32; 4] = [2, 3, 4, 6];
+    /// 默认缩放级别（`ZOOM_LEVELS` 中的索引）
+    pub const DEFAULT_ZOOM: usize = 1;
+}
@@ -1,9 +1,11 @@
 use crate::config::pickup::DEFAULT_RADIUS;
+use crate::spatial::SpatialTracked;
 use bevy::prelude::*;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub enum ItemKind {
     Plant1,
     Plant2,
@@ -12,6 +14,13 @@ pub enum ItemKind {
 }
 
 impl ItemKind {
+    pub const ALL: [ItemKind; 4] = [
+        ItemKind::Plant1,
+        ItemKind::Plant2,
+        ItemKind::Plant3,
+        ItemKind::Plant4,
+    ];
+
     pub fn display_name(&self) -> &'static str {
         match self {
             ItemKind::Plant1 => "Herb",
@@ -29,6 +38,7 @@ impl fmt::Display for ItemKind {
 }
 
 #[derive(Component, Debug)]
+#[require(SpatialTracked)]
 pub struct Pickable {
     pub kind: ItemKind,
     pub radius: f32,
@@ -68,3 +78,40 @@ impl Inventory {
         parts.join(", ")
     }
 }
+
+/// 已拾取的拾取物资源
+///
+/// 记录拾取物被拾取时所在的世界网格坐标（出生区块左下角为原点）。
+/// 区块重新加载或边缘网格转交给相邻区块时会重新生成瓦片实体，
+/// 这些网格上的拾取物不再生成，离开后再回来不会重复拾取
+#[derive(Resource, Default, Debug, Clone)]
+pub struct CollectedPickups {
+    cells: HashSet<IVec2>,
+}
+
+impl CollectedPickups {
+    /// 记录拾取物已被拾取
+    pub fn insert(&mut self, cell: IVec2) {
+        self.cells.insert(cell);
+    }
+
+    /// 检查该世界网格坐标上的拾取物是否已被拾取
+    pub fn contains(&self, cell: IVec2) -> bool {
+        self.cells.contains(&cell)
+    }
+
+    /// 遍历所有已拾取的世界网格坐标
+    pub fn iter(&self) -> impl Iterator<Item = IVec2> + '_ {
+        self.cells.iter().copied()
+    }
+
+    /// 已拾取的数量
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// 没有拾取过任何拾取物时返回 true
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+}
@@ -3,14 +3,21 @@ mod systems;
 
 use crate::state::GameState;
 use bevy::prelude::*;
-pub use inventory::{Inventory, ItemKind, Pickable};
-use systems::handle_pickups;
+pub use inventory::{CollectedPickups, Inventory, ItemKind, Pickable};
+use systems::{MaxPickupRadius, handle_pickups, track_pickup_radius};
 
 pub struct InventoryPlugin;
 
 impl Plugin for InventoryPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<Inventory>()
-            .add_systems(Update, handle_pickups.run_if(in_state(GameState::Playing)));
+            .init_resource::<CollectedPickups>()
+            .init_resource::<MaxPickupRadius>()
+            .add_systems(
+                Update,
+                (track_pickup_radius, handle_pickups)
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            );
     }
 }
@@ -1,12 +1,56 @@
 use crate::characters::input::Player;
-use crate::inventory::inventory::{Inventory, Pickable};
+use crate::config::pickup::DEFAULT_RADIUS;
+use crate::inventory::inventory::{CollectedPickups, Inventory, Pickable};
+use crate::map::chunk::world_to_cell;
+use crate::spatial::SpatialIndex;
 use bevy::prelude::*;
 
+/// 最大拾取半径资源
+///
+/// 记录现存拾取物中最大的拾取半径，拾取物新增、修改或移除时重新计算，
+/// 拾取检测在空间索引中按该半径查询，任何拾取物都不会被漏掉
+#[derive(Resource, Debug, PartialEq)]
+pub struct MaxPickupRadius(pub f32);
+
+impl Default for MaxPickupRadius {
+    fn default() -> Self {
+        Self(DEFAULT_RADIUS)
+    }
+}
+
+/// 拾取物新增、修改或移除时根据现存的拾取物重新计算 [`MaxPickupRadius`]
+///
+/// 半径较大的拾取物被拾取或卸载后，查询半径会随之缩小
+pub fn track_pickup_radius(
+    mut max_radius: ResMut<MaxPickupRadius>,
+    changed: Query<(), Changed<Pickable>>,
+    mut removed: RemovedComponents<Pickable>,
+    pickables: Query<&Pickable>,
+) {
+    // 必须读完移除事件，否则下一帧会再次读到
+    let any_removed = removed.read().count() > 0;
+    if changed.is_empty() && !any_removed {
+        return;
+    }
+
+    let radius = pickables
+        .iter()
+        .map(|pickable| pickable.radius)
+        .fold(0.0, f32::max);
+    max_radius.set_if_neq(MaxPickupRadius(radius));
+}
+
+/// 拾取玩家附近的拾取物
+///
+/// 拾取物被销毁并加入背包，所在的世界网格坐标记录到 [`CollectedPickups`]
 pub fn handle_pickups(
     mut commands: Commands,
     mut inventory: ResMut<Inventory>,
+    mut collected_cells: ResMut<CollectedPickups>,
+    index: Res<SpatialIndex>,
+    max_radius: Res<MaxPickupRadius>,
     player_query: Query<&Transform, With<Player>>,
-    pickable: Query<(Entity, &GlobalTransform, &Pickable)>,
+    pickable: Query<(&GlobalTransform, &Pickable)>,
 ) {
     let Ok(player_transform) = player_query.single() else {
         return;
@@ -15,16 +59,21 @@ pub fn handle_pickups(
     let player_pos = player_transform.translation.truncate();
     let mut collected = Vec::new();
 
-    for (entity, global_transform, pickable) in pickable.iter() {
+    // 只检查空间索引中玩家附近的实体
+    for (entity, _) in index.within_radius(player_pos, max_radius.0) {
+        let Ok((global_transform, pickable)) = pickable.get(entity) else {
+            continue;
+        };
         let item_pos = global_transform.translation().truncate();
         let distance_sq = player_pos.distance_squared(item_pos);
         if distance_sq < pickable.radius * pickable.radius {
-            collected.push((entity, pickable.kind));
+            collected.push((entity, pickable.kind, world_to_cell(item_pos)));
         }
     }
 
-    for (entity, kind) in collected {
+    for (entity, kind, cell) in collected {
         commands.entity(entity).despawn();
+        collected_cells.insert(cell);
         let count = inventory.add(kind);
         info!(
             "Picked up {} (total: {}) - inventory: {}",
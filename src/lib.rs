@@ -0,0 +1,17 @@
+// 游戏库
+//
+// 包含所有游戏模块，供游戏主程序和 mapgen 等工具共享
+
+pub mod camera;
+pub mod characters;
+pub mod collision;
+pub mod combat;
+pub mod config;
+pub mod inventory;
+pub mod map;
+pub mod minimap;
+pub mod navigation;
+pub mod particles;
+pub mod rendering;
+pub mod spatial;
+pub mod state;
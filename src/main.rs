@@ -1,17 +1,9 @@
-mod camera;
-mod characters;
-mod collision;
-mod combat;
-mod config;
-mod inventory;
-mod map;
-mod particles;
-mod state;
-
-use crate::map::generate::setup_generator;
 use bevy::prelude::*;
 use bevy::window::WindowMode;
-use bevy_procedural_tilemaps::prelude::{Cartesian3D, ProcGenSimplePlugin};
+use bevy_game::{
+    camera, characters, collision, combat, inventory, map, minimap, navigation, particles,
+    rendering, spatial, state,
+};
 
 fn main() {
     App::new()
@@ -37,8 +29,8 @@ fn main() {
                 // 设置图像采样模式为最近邻（保持像素风格）
                 .set(ImagePlugin::default_nearest()),
         )
-        // 添加过程化地图生成插件
-        .add_plugins(ProcGenSimplePlugin::<Cartesian3D, Sprite>::default())
+        // 添加地图插件（世界种子、区块流式生成、保存和加载）
+        .add_plugins(map::MapPlugin)
         .add_plugins(state::StatePlugin)
         .add_plugins(camera::CameraPlugin)
         .add_plugins(inventory::InventoryPlugin)
@@ -47,7 +39,9 @@ fn main() {
         .add_plugins(characters::CharactersPlugin)
         .add_plugins(combat::CombatPlugin)
         .add_plugins(particles::ParticlesPlugin)
-        // 在启动时设置相机和地图生成器
-        .add_systems(Startup, setup_generator)
+        .add_plugins(rendering::RenderingPlugin)
+        .add_plugins(minimap::MinimapPlugin)
+        .add_plugins(spatial::SpatialPlugin)
+        .add_plugins(navigation::NavigationPlugin)
         .run();
 }
@@ -0,0 +1,202 @@
+use crate::map::chunk::chunk_seed;
+use crate::map::tilemap::{FrameSync, SpriteRef, TilesetDefinition};
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+/// 单个动画帧
+#[derive(Debug, Copy, Clone)]
+struct AnimationFrame {
+    /// 精灵在瓦片图图集中的索引
+    index: usize,
+    /// 是否水平翻转
+    flip_x: bool,
+    /// 是否垂直翻转
+    flip_y: bool,
+}
+
+/// 瓦片动画
+#[derive(Debug, Clone)]
+struct TileAnimation {
+    /// 动画帧
+    frames: Vec<AnimationFrame>,
+    /// 每帧持续时间（秒）
+    frame_duration: f32,
+    /// 上次更新时的帧计数
+    tick: u64,
+}
+
+/// 瓦片动画资源
+///
+/// 从瓦片集定义中收集所有带动画的精灵。动画按全局时间推进，
+/// 只有帧计数变化的动画才会更新其瓦片，不会每帧遍历所有瓦片
+#[derive(Resource, Debug, Clone, Default)]
+pub struct TileAnimations {
+    /// 所有动画
+    animations: Vec<TileAnimation>,
+    /// 精灵到动画索引和同步方式的映射
+    by_sprite: HashMap<SpriteRef, (usize, FrameSync)>,
+}
+
+/// 动画瓦片组件
+///
+/// 标记使用帧动画的瓦片实体
+#[derive(Component, Debug, Copy, Clone)]
+pub struct AnimatedTile {
+    /// 动画索引
+    animation: usize,
+    /// 帧偏移（同步播放时为 0）
+    phase: u64,
+}
+
+impl AnimatedTile {
+    /// 创建动画瓦片组件
+    ///
+    /// # 参数
+    /// - `animation`: 动画索引
+    /// - `sync`: 帧同步方式
+    /// - `cell`: 瓦片的世界网格坐标（按位置错开帧时使用）
+    pub fn new(animation: usize, sync: FrameSync, cell: IVec2) -> Self {
+        let phase = match sync {
+            FrameSync::Synchronized => 0,
+            FrameSync::ByPosition => chunk_seed(animation as u64, cell),
+        };
+        Self { animation, phase }
+    }
+}
+
+impl TileAnimations {
+    /// 从瓦片集定义中收集动画
+    ///
+    /// 帧精灵不存在、位于其他瓦片图或帧时长无效的动画会被忽略并记录警告
+    pub fn from_tileset(tileset: &TilesetDefinition) -> Self {
+        let mut animations = Self::default();
+
+        for (image, tileset_image) in tileset.images.iter().enumerate() {
+            for (index, sprite) in tileset_image.sprites.iter().enumerate() {
+                let Some(definition) = &sprite.animation else {
+                    continue;
+                };
+                if definition.frames.is_empty() || definition.frame_duration <= 0.0 {
+                    warn!(
+                        "Ignoring animation of '{}': needs frames and a positive frame duration",
+                        sprite.name
+                    );
+                    continue;
+                }
+
+                let frames: Option<Vec<AnimationFrame>> = definition
+                    .frames
+                    .iter()
+                    .map(|frame| {
+                        let frame_ref = tileset.sprite_index(&frame.sprite)?;
+                        // 同一个精灵只能切换同一张图集中的索引
+                        (frame_ref.image == image).then_some(AnimationFrame {
+                            index: frame_ref.index,
+                            flip_x: frame.flip_x,
+                            flip_y: frame.flip_y,
+                        })
+                    })
+                    .collect();
+                let Some(frames) = frames else {
+                    warn!(
+                        "Ignoring animation of '{}': frames must be sprites from the same image",
+                        sprite.name
+                    );
+                    continue;
+                };
+
+                animations.by_sprite.insert(
+                    SpriteRef { image, index },
+                    (animations.animations.len(), definition.sync),
+                );
+                animations.animations.push(TileAnimation {
+                    frames,
+                    frame_duration: definition.frame_duration,
+                    tick: 0,
+                });
+            }
+        }
+
+        animations
+    }
+
+    /// 获取精灵的动画
+    ///
+    /// # 返回
+    /// 动画索引和同步方式，如果精灵没有动画则返回 None
+    pub fn animation_for(&self, sprite_ref: SpriteRef) -> Option<(usize, FrameSync)> {
+        self.by_sprite.get(&sprite_ref).copied()
+    }
+
+    /// 动画数量
+    pub fn len(&self) -> usize {
+        self.animations.len()
+    }
+
+    /// 是否没有任何动画
+    pub fn is_empty(&self) -> bool {
+        self.animations.is_empty()
+    }
+
+    /// 获取动画瓦片当前应显示的帧
+    fn current_frame(&self, tile: &AnimatedTile) -> Option<AnimationFrame> {
+        let animation = self.animations.get(tile.animation)?;
+        let frame = animation.tick.wrapping_add(tile.phase) % animation.frames.len() as u64;
+        Some(animation.frames[frame as usize])
+    }
+}
+
+/// 把动画帧应用到精灵上
+fn apply_frame(sprite: &mut Sprite, frame: AnimationFrame) {
+    if let Some(atlas) = sprite.texture_atlas.as_mut() {
+        atlas.index = frame.index;
+    }
+    sprite.flip_x = frame.flip_x;
+    sprite.flip_y = frame.flip_y;
+}
+
+/// 推进瓦片动画
+///
+/// 新生成的动画瓦片立即切换到当前帧；之后只在某个动画的帧计数变化时，
+/// 更新使用该动画的瓦片
+#[allow(clippy::type_complexity)]
+pub fn animate_tiles(
+    time: Res<Time>,
+    animations: Option<ResMut<TileAnimations>>,
+    mut tiles: ParamSet<(
+        Query<(&AnimatedTile, &mut Sprite), Added<AnimatedTile>>,
+        Query<(&AnimatedTile, &mut Sprite)>,
+    )>,
+) {
+    let Some(mut animations) = animations else {
+        return;
+    };
+
+    // 计算每个动画的帧计数，记录哪些动画需要切换帧
+    let elapsed = time.elapsed_secs_f64();
+    let mut advanced = vec![false; animations.animations.len()];
+    for (animation, advanced) in animations.animations.iter_mut().zip(advanced.iter_mut()) {
+        let tick = (elapsed / animation.frame_duration as f64) as u64;
+        if tick != animation.tick {
+            animation.tick = tick;
+            *advanced = true;
+        }
+    }
+
+    if advanced.iter().any(|advanced| *advanced) {
+        for (tile, mut sprite) in tiles.p1().iter_mut() {
+            if !advanced.get(tile.animation).copied().unwrap_or(false) {
+                continue;
+            }
+            if let Some(frame) = animations.current_frame(tile) {
+                apply_frame(&mut sprite, frame);
+            }
+        }
+    }
+
+    for (tile, mut sprite) in tiles.p0().iter_mut() {
+        if let Some(frame) = animations.current_frame(tile) {
+            apply_frame(&mut sprite, frame);
+        }
+    }
+}
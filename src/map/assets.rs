@@ -1,8 +1,10 @@
 use crate::collision::{TileMarker, TileType};
 use crate::inventory::{ItemKind, Pickable};
-use crate::map::tilemap::TILEMAP;
+use crate::map::animation::TileAnimations;
+use crate::map::tilemap::{FrameSync, SpriteRef, TilesetDefinition};
 use bevy::prelude::*;
-use bevy_procedural_tilemaps::prelude::{GridDelta, ModelAsset, ModelsAssets};
+use bevy_procedural_tilemaps::prelude::GridDelta;
+use bevy_procedural_tilemaps::proc_gen::generator::model::ModelIndex;
 
 /// 可生成资源结构体
 ///
@@ -10,7 +12,7 @@ use bevy_procedural_tilemaps::prelude::{GridDelta, ModelAsset, ModelsAssets};
 #[derive(Clone)]
 pub struct SpawnableAsset {
     /// 精灵名称（在瓦片图中的标识）
-    sprite_name: &'static str,
+    sprite_name: String,
     /// 网格偏移量
     grid_offset: GridDelta,
     /// 世界坐标偏移量
@@ -29,9 +31,9 @@ impl SpawnableAsset {
     ///
     /// # 返回
     /// 新的可生成资源实例
-    pub fn new(sprite_name: &'static str) -> Self {
+    pub fn new(sprite_name: &str) -> Self {
         Self {
-            sprite_name,
+            sprite_name: sprite_name.to_string(),
             grid_offset: GridDelta::new(0, 0, 0),
             offset: Vec3::ZERO,
             tile_type: None,
@@ -51,6 +53,12 @@ impl SpawnableAsset {
         self
     }
 
+    /// 设置世界坐标偏移量
+    pub fn with_offset(mut self, offset: Vec3) -> Self {
+        self.offset = offset;
+        self
+    }
+
     /// 设置用于碰撞检测的瓦片类型
     pub fn with_tile_type(mut self, tile_type: TileType) -> Self {
         self.tile_type = Some(tile_type);
@@ -61,85 +69,177 @@ impl SpawnableAsset {
         self.pickable = Some(kind);
         self
     }
+
+    /// 获取精灵名称
+    pub fn sprite_name(&self) -> &str {
+        &self.sprite_name
+    }
+
+    /// 获取网格偏移量
+    pub fn grid_offset(&self) -> &GridDelta {
+        &self.grid_offset
+    }
+
+    /// 获取世界坐标偏移量
+    pub fn offset(&self) -> Vec3 {
+        self.offset
+    }
+
+    /// 获取用于碰撞检测的瓦片类型
+    pub fn tile_type(&self) -> Option<TileType> {
+        self.tile_type
+    }
+
+    /// 获取拾取物类型
+    pub fn pickable(&self) -> Option<ItemKind> {
+        self.pickable
+    }
 }
 
-/// 瓦片图句柄
+/// 单张瓦片图的句柄
 ///
 /// 存储瓦片图的图像和布局句柄
 #[derive(Clone)]
-pub struct TilemapHandles {
+pub struct TilemapImageHandles {
     /// 图像句柄
     pub image: Handle<Image>,
     /// 纹理图集布局句柄
     pub layout: Handle<TextureAtlasLayout>,
 }
 
+/// 瓦片图句柄资源
+///
+/// 按瓦片集中的顺序存储每张瓦片图的句柄，加载状态用它追踪图像是否加载完成
+#[derive(Resource, Clone)]
+pub struct TilemapHandles {
+    /// 每张瓦片图的句柄
+    pub images: Vec<TilemapImageHandles>,
+}
+
 impl TilemapHandles {
-    /// 根据图集索引创建精灵
+    /// 根据精灵引用创建精灵
     ///
     /// # 参数
-    /// - `atlas_index`: 图集中的索引
+    /// - `sprite_ref`: 瓦片集中的精灵引用
     ///
     /// # 返回
     /// 配置好的精灵
-    pub fn sprite(&self, atlas_index: usize) -> Sprite {
+    pub fn sprite(&self, sprite_ref: SpriteRef) -> Sprite {
+        let handles = &self.images[sprite_ref.image];
         Sprite::from_atlas_image(
-            self.image.clone(),
-            TextureAtlas::from(self.layout.clone()).with_index(atlas_index),
+            handles.image.clone(),
+            TextureAtlas::from(handles.layout.clone()).with_index(sprite_ref.index),
         )
     }
+
+    /// 检查所有瓦片图图像是否已加载完成
+    pub fn is_loaded(&self, asset_server: &AssetServer) -> bool {
+        self.images
+            .iter()
+            .all(|handles| asset_server.is_loaded_with_dependencies(&handles.image))
+    }
 }
 
 /// 准备瓦片图句柄
 ///
-/// 加载瓦片图图像并创建纹理图集布局
+/// 加载瓦片集中的每张瓦片图图像并创建对应的纹理图集布局
 ///
 /// # 参数
 /// - `asset_server`: 资源服务器
 /// - `atlas_layouts`: 纹理图集布局资源集合
-/// - `assets_directory`: 资源目录路径
-/// - `tilemap_file`: 瓦片图文件名
+/// - `tileset`: 瓦片集定义
 ///
 /// # 返回
 /// 瓦片图句柄
 pub fn prepare_tilemap_handles(
     asset_server: &Res<AssetServer>,
     atlas_layouts: &mut ResMut<Assets<TextureAtlasLayout>>,
-    assets_directory: &str,
-    tilemap_file: &str,
+    tileset: &TilesetDefinition,
 ) -> TilemapHandles {
-    // 加载瓦片图图像
-    let image = asset_server.load::<Image>(format!("{assets_directory}/{tilemap_file}"));
-    // 创建空的纹理图集布局
-    let mut layout = TextureAtlasLayout::new_empty(TILEMAP.atlas_size());
-    // 为每个精灵添加纹理区域
-    for index in 0..TILEMAP.sprites.len() {
-        layout.add_texture(TILEMAP.sprite_rect(index));
-    }
-    // 将布局添加到资源集合中
-    let layout = atlas_layouts.add(layout);
+    let images = tileset
+        .images
+        .iter()
+        .map(|tileset_image| {
+            // 加载瓦片图图像
+            let image = asset_server.load::<Image>(&tileset_image.path);
+            // 创建空的纹理图集布局
+            let mut layout = TextureAtlasLayout::new_empty(tileset_image.atlas_size());
+            // 为每个精灵添加纹理区域
+            for index in 0..tileset_image.sprites.len() {
+                layout.add_texture(tileset.sprite_rect(tileset_image, index));
+            }
+            // 将布局添加到资源集合中
+            let layout = atlas_layouts.add(layout);
+
+            TilemapImageHandles { image, layout }
+        })
+        .collect();
+
+    TilemapHandles { images }
+}
 
-    TilemapHandles { image, layout }
+/// 模型精灵
+///
+/// 单个模型在生成时需要创建的一个精灵实体
+#[derive(Clone)]
+pub struct ModelSprite {
+    /// 精灵（已绑定图集索引）
+    pub sprite: Sprite,
+    /// 相对于节点的网格偏移量
+    pub grid_offset: GridDelta,
+    /// 世界坐标偏移量
+    pub world_offset: Vec3,
+    /// 用于碰撞检测的瓦片类型
+    pub tile_type: Option<TileType>,
+    /// 生成实体后执行的附加命令（插入碰撞、拾取物等组件）
+    pub spawn_commands: fn(&mut EntityCommands),
+    /// 精灵的帧动画（动画索引和同步方式）
+    pub animation: Option<(usize, FrameSync)>,
+    /// 可选的拾取物类型
+    pub pickable: Option<ItemKind>,
+}
+
+/// 模型精灵表
+///
+/// 按模型索引存储每个模型需要生成的精灵列表
+#[derive(Clone, Default)]
+pub struct ModelSprites {
+    models: Vec<Vec<ModelSprite>>,
+}
+
+impl ModelSprites {
+    /// 获取指定模型的精灵列表
+    pub fn get(&self, model_index: ModelIndex) -> &[ModelSprite] {
+        self.models
+            .get(model_index)
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
 }
 
 /// 加载地图资源
 ///
-/// 将资源定义转换为可用的模型资源
+/// 将资源定义转换为可用的模型精灵表
 ///
 /// # 参数
+/// - `tileset`: 瓦片集定义
 /// - `tilemap_handles`: 瓦片图句柄
+/// - `animations`: 瓦片动画
 /// - `assets_definitions`: 资源定义列表
 ///
 /// # 返回
-/// 模型资源集合
+/// 模型精灵表
 pub fn load_assets(
+    tileset: &TilesetDefinition,
     tilemap_handles: &TilemapHandles,
+    animations: &TileAnimations,
     assets_definitions: Vec<Vec<SpawnableAsset>>,
-) -> ModelsAssets<Sprite> {
-    let mut models_assets = ModelsAssets::<Sprite>::new();
+) -> ModelSprites {
+    let mut models = Vec::with_capacity(assets_definitions.len());
 
     // 遍历每个模型的资源定义
-    for (model_index, assets) in assets_definitions.into_iter().enumerate() {
+    for assets in assets_definitions {
+        let mut sprites = Vec::with_capacity(assets.len());
         // 遍历模型中的每个资源
         for asset_def in assets {
             let SpawnableAsset {
@@ -150,27 +250,25 @@ pub fn load_assets(
                 pickable,
             } = asset_def;
 
-            // 根据名称查找图集索引
-            let Some(atlas_index) = TILEMAP.sprite_index(sprite_name) else {
+            // 根据名称查找精灵
+            let Some(sprite_ref) = tileset.sprite_index(&sprite_name) else {
                 panic!("Unknown atlas sprite '{}'", sprite_name);
             };
 
-            let spawner = create_spawner(tile_type, pickable);
-
-            // 将资源添加到模型资源集合中
-            models_assets.add(
-                model_index,
-                ModelAsset {
-                    assets_bundle: tilemap_handles.sprite(atlas_index),
-                    grid_offset,
-                    world_offset: offset,
-                    spawn_commands: spawner,
-                },
-            )
+            sprites.push(ModelSprite {
+                sprite: tilemap_handles.sprite(sprite_ref),
+                grid_offset,
+                world_offset: offset,
+                tile_type,
+                spawn_commands: create_spawner(tile_type, pickable),
+                animation: animations.animation_for(sprite_ref),
+                pickable,
+            });
         }
+        models.push(sprites);
     }
 
-    models_assets
+    ModelSprites { models }
 }
 
 fn create_spawner(
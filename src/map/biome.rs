@@ -0,0 +1,204 @@
+use crate::config::map::{BIOME_SCALE, TILE_SIZE};
+use crate::map::chunk::{chunk_cells, chunk_seed, world_origin};
+use crate::map::rules::TerrainRules;
+use bevy::prelude::*;
+
+/// 生物群系 ID（地形规则中生物群系列表的索引）
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct BiomeId(pub usize);
+
+/// 生物群系地图资源
+///
+/// 用低频噪声场为世界网格中的每个瓦片选择生物群系，
+/// 相同的世界种子总能得到相同的生物群系分布。
+///
+/// 地形按区块生成，每个区块只使用一套按 [`BiomeMap::chunk_blend`] 混合权重的规则，
+/// 因此区块内的地形反映的是区块的生物群系（[`BiomeMap::chunk_biome`]），
+/// 而 [`BiomeMap::biome_at`] 是噪声场在单个瓦片上的结果，在生物群系边界附近可能与地形不一致
+#[derive(Resource, Debug, Clone)]
+pub struct BiomeMap {
+    /// 噪声种子
+    seed: u64,
+    /// 生物群系名称（按 ID 索引）
+    names: Vec<String>,
+}
+
+/// 生物群系混合比例
+///
+/// 一块区域中每个生物群系所占的比例（以十分之一为单位），用于在边界处混合权重
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct BiomeBlend(Vec<u8>);
+
+impl BiomeMap {
+    /// 创建生物群系地图
+    ///
+    /// # 参数
+    /// - `seed`: 世界种子
+    /// - `rules`: 地形规则（提供生物群系列表）
+    pub fn new(seed: u64, rules: &TerrainRules) -> Self {
+        Self {
+            seed,
+            names: rules
+                .biomes
+                .iter()
+                .map(|biome| biome.name.clone())
+                .collect(),
+        }
+    }
+
+    /// 是否没有定义任何生物群系
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// 获取生物群系名称
+    pub fn name(&self, id: BiomeId) -> &str {
+        self.names.get(id.0).map_or("unknown", String::as_str)
+    }
+
+    /// 根据名称查找生物群系
+    pub fn id(&self, name: &str) -> Option<BiomeId> {
+        self.names.iter().position(|n| n == name).map(BiomeId)
+    }
+
+    /// 获取世界网格坐标处的生物群系噪声结果
+    ///
+    /// 每个生物群系有独立的噪声场，取噪声值最高的生物群系。
+    /// 地形按区块混合权重生成，需要与地形一致的生物群系时使用 [`BiomeMap::chunk_biome`]
+    ///
+    /// # 参数
+    /// - `grid`: 世界网格坐标（出生区块左下角为原点）
+    ///
+    /// # 返回
+    /// 生物群系 ID，如果没有定义生物群系则返回 None
+    pub fn biome_at(&self, grid: IVec2) -> Option<BiomeId> {
+        let pos = grid.as_vec2() / BIOME_SCALE;
+        (0..self.names.len())
+            .map(|index| {
+                let seed = self.seed ^ (index as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+                // 两个八度：低频决定大致分布，高频让边界更自然
+                let value = value_noise(seed, pos) + 0.35 * value_noise(seed ^ 0xA5A5, pos * 3.0);
+                (index, value)
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(index, _)| BiomeId(index))
+    }
+
+    /// 获取世界坐标处的生物群系噪声结果（见 [`BiomeMap::biome_at`]）
+    pub fn biome_at_world(&self, world_pos: Vec2) -> Option<BiomeId> {
+        let grid = ((world_pos - world_origin()) / TILE_SIZE)
+            .floor()
+            .as_ivec2();
+        self.biome_at(grid)
+    }
+
+    /// 获取区块生成地形时使用的生物群系混合比例
+    ///
+    /// # 参数
+    /// - `coord`: 区块坐标
+    ///
+    /// # 返回
+    /// 区块内所有瓦片的混合比例，如果没有定义生物群系则返回 None
+    pub fn chunk_blend(&self, coord: IVec2) -> Option<BiomeBlend> {
+        self.blend(chunk_cells(coord))
+    }
+
+    /// 获取区块的生物群系
+    ///
+    /// 区块混合比例中占比最高的生物群系，与区块内生成的地形一致
+    ///
+    /// # 参数
+    /// - `coord`: 区块坐标
+    ///
+    /// # 返回
+    /// 生物群系 ID，如果没有定义生物群系则返回 None
+    pub fn chunk_biome(&self, coord: IVec2) -> Option<BiomeId> {
+        self.chunk_blend(coord).map(|blend| blend.dominant())
+    }
+
+    /// 计算一组瓦片的生物群系混合比例
+    ///
+    /// # 参数
+    /// - `cells`: 世界网格坐标
+    ///
+    /// # 返回
+    /// 混合比例，如果没有定义生物群系则返回 None
+    pub fn blend(&self, cells: impl IntoIterator<Item = IVec2>) -> Option<BiomeBlend> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let mut counts = vec![0usize; self.names.len()];
+        let mut total = 0;
+        for cell in cells {
+            if let Some(id) = self.biome_at(cell) {
+                counts[id.0] += 1;
+                total += 1;
+            }
+        }
+        if total == 0 {
+            return None;
+        }
+
+        // 量化到十分之一，使相近的混合比例可以共享编译好的规则
+        let mut tenths: Vec<u8> = counts
+            .iter()
+            .map(|count| ((*count * 10 + total / 2) / total) as u8)
+            .collect();
+        if tenths.iter().all(|tenth| *tenth == 0) {
+            let dominant = (0..counts.len()).max_by_key(|i| counts[*i]).unwrap_or(0);
+            tenths[dominant] = 10;
+        }
+        Some(BiomeBlend(tenths))
+    }
+}
+
+impl BiomeBlend {
+    /// 占比最高的生物群系（占比相同时取 ID 较小的）
+    pub fn dominant(&self) -> BiomeId {
+        let index = (0..self.0.len())
+            .rev()
+            .max_by_key(|index| self.0[*index])
+            .unwrap_or(0);
+        BiomeId(index)
+    }
+
+    /// 获取混合后的模型权重倍率
+    ///
+    /// 按每个生物群系所占比例对其权重倍率加权平均
+    pub fn multiplier(&self, rules: &TerrainRules, model: &str) -> f32 {
+        let total: f32 = self.0.iter().map(|tenth| *tenth as f32).sum();
+        rules
+            .biomes
+            .iter()
+            .zip(&self.0)
+            .map(|(biome, tenth)| biome.multiplier(model) * *tenth as f32)
+            .sum::<f32>()
+            / total
+    }
+}
+
+/// 将整数坐标和种子哈希为 [0, 1) 的值
+fn hash_to_unit(seed: u64, x: i32, y: i32) -> f32 {
+    (chunk_seed(seed, IVec2::new(x, y)) >> 40) as f32 / (1u64 << 24) as f32
+}
+
+/// 二维值噪声
+///
+/// 对整数格点上的随机值做平滑插值，返回 [0, 1) 之间的值
+fn value_noise(seed: u64, pos: Vec2) -> f32 {
+    let cell = pos.floor();
+    let (x, y) = (cell.x as i32, cell.y as i32);
+    let t = pos - cell;
+    // smoothstep 插值，避免格点处出现折痕
+    let t = t * t * (Vec2::splat(3.0) - 2.0 * t);
+
+    let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+    let bottom = lerp(hash_to_unit(seed, x, y), hash_to_unit(seed, x + 1, y), t.x);
+    let top = lerp(
+        hash_to_unit(seed, x, y + 1),
+        hash_to_unit(seed, x + 1, y + 1),
+        t.x,
+    );
+    lerp(bottom, top, t.y)
+}
@@ -0,0 +1,635 @@
+use crate::characters::input::Player;
+use crate::collision::{CollisionMapBuilt, TileOverrides};
+use crate::config::map::{CHUNK_LOAD_RADIUS, CHUNK_UNLOAD_RADIUS, GRID_X, GRID_Y, TILE_SIZE};
+use crate::inventory::CollectedPickups;
+use crate::map::generate::{
+    ChunkData, GRID_Z, GenerationError, GenerationFailed, GenerationRetryBudget, WorldGenerator,
+    chunk_grid,
+};
+use crate::map::save::SavedChunks;
+use crate::map::seed::WorldSeed;
+use bevy::prelude::*;
+use bevy_procedural_tilemaps::proc_gen::NodeIndex;
+use bevy_procedural_tilemaps::proc_gen::generator::model::ModelInstance;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+
+/// 区块在 X 方向上的步长（网格数）
+///
+/// 相邻区块共享一列边缘节点，所以步长比区块宽度少 1
+pub const CHUNK_STRIDE_X: i32 = GRID_X as i32 - 1;
+/// 区块在 Y 方向上的步长（网格数）
+pub const CHUNK_STRIDE_Y: i32 = GRID_Y as i32 - 1;
+
+/// 地图区块组件
+///
+/// 标记一个区块实体，瓦片实体都是它的子实体
+#[derive(Component, Debug, Copy, Clone)]
+pub struct MapChunk {
+    /// 区块坐标
+    pub coord: IVec2,
+}
+
+/// 区块边缘
+///
+/// 记录区块四条边上每个节点（含所有 Z 层）选中的模型，用于约束相邻区块
+struct ChunkEdges {
+    /// 左边缘（x = 0），按 `y * GRID_Z + z` 排列
+    left: Vec<ModelInstance>,
+    /// 右边缘（x = GRID_X - 1），按 `y * GRID_Z + z` 排列
+    right: Vec<ModelInstance>,
+    /// 下边缘（y = 0），按 `x * GRID_Z + z` 排列
+    bottom: Vec<ModelInstance>,
+    /// 上边缘（y = GRID_Y - 1），按 `x * GRID_Z + z` 排列
+    top: Vec<ModelInstance>,
+}
+
+impl ChunkEdges {
+    /// 从区块生成结果中提取边缘
+    fn from_data(data: &ChunkData) -> Self {
+        let grid = chunk_grid();
+        let column = |x: u32| -> Vec<ModelInstance> {
+            (0..GRID_Y)
+                .flat_map(|y| (0..GRID_Z).map(move |z| (y, z)))
+                .map(|(y, z)| *data.get(grid.index_from_coords(x, y, z)))
+                .collect()
+        };
+        let row = |y: u32| -> Vec<ModelInstance> {
+            (0..GRID_X)
+                .flat_map(|x| (0..GRID_Z).map(move |z| (x, z)))
+                .map(|(x, z)| *data.get(grid.index_from_coords(x, y, z)))
+                .collect()
+        };
+
+        Self {
+            left: column(0),
+            right: column(GRID_X - 1),
+            bottom: row(0),
+            top: row(GRID_Y - 1),
+        }
+    }
+}
+
+/// 遍历区块边缘上的所有列（区块内坐标）
+fn border_columns() -> impl Iterator<Item = UVec2> {
+    (0..GRID_Y)
+        .flat_map(|y| (0..GRID_X).map(move |x| UVec2::new(x, y)))
+        .filter(|column| {
+            column.x == 0 || column.x == GRID_X - 1 || column.y == 0 || column.y == GRID_Y - 1
+        })
+}
+
+/// 区块边缘网格的归属
+///
+/// 相邻区块（包括对角相邻的区块）共享边缘上的网格，每个网格只由一个已加载区块生成实体。
+/// 负责的区块卸载时，它负责的网格转交给仍然加载的相邻区块重新生成，地图上不会留下空洞。
+#[derive(Debug, Default)]
+pub struct SeamOwners {
+    /// 世界网格坐标到负责生成该网格实体的区块坐标的映射
+    owners: HashMap<IVec2, IVec2>,
+}
+
+impl SeamOwners {
+    /// 获取负责生成世界网格实体的区块
+    pub fn owner(&self, cell: IVec2) -> Option<IVec2> {
+        self.owners.get(&cell).copied()
+    }
+
+    /// 区块加载时认领尚无归属的边缘网格
+    ///
+    /// # 参数
+    /// - `coord`: 加载的区块坐标
+    ///
+    /// # 返回
+    /// 已由其他区块负责的边缘列（区块内坐标），加载的区块不为这些列生成实体
+    pub fn claim(&mut self, coord: IVec2) -> HashSet<UVec2> {
+        let origin = chunk_origin_cell(coord);
+        let mut owned_elsewhere = HashSet::new();
+        for column in border_columns() {
+            match self.owners.entry(origin + column.as_ivec2()) {
+                Entry::Occupied(owner) if *owner.get() != coord => {
+                    owned_elsewhere.insert(column);
+                }
+                Entry::Occupied(_) => {}
+                Entry::Vacant(owner) => {
+                    owner.insert(coord);
+                }
+            }
+        }
+        owned_elsewhere
+    }
+
+    /// 区块卸载时释放它负责的边缘网格
+    ///
+    /// 每个网格转交给仍然加载且覆盖该网格的相邻区块，没有这样的区块时网格不再有归属
+    ///
+    /// # 参数
+    /// - `coord`: 卸载的区块坐标
+    /// - `is_loaded`: 检查相邻区块是否仍然加载
+    ///
+    /// # 返回
+    /// 接手的区块坐标到它需要重新生成的列（接手区块内坐标）的映射
+    pub fn release(
+        &mut self,
+        coord: IVec2,
+        is_loaded: impl Fn(IVec2) -> bool,
+    ) -> HashMap<IVec2, Vec<UVec2>> {
+        let origin = chunk_origin_cell(coord);
+        let size = IVec2::new(GRID_X as i32, GRID_Y as i32);
+        let mut handovers: HashMap<IVec2, Vec<UVec2>> = HashMap::new();
+
+        for column in border_columns() {
+            let cell = origin + column.as_ivec2();
+            if self.owner(cell) != Some(coord) {
+                continue;
+            }
+            let heir = (-1..=1)
+                .flat_map(|dy| (-1..=1).map(move |dx| coord + IVec2::new(dx, dy)))
+                .filter(|neighbor| *neighbor != coord && is_loaded(*neighbor))
+                .find_map(|neighbor| {
+                    let local = cell - chunk_origin_cell(neighbor);
+                    let inside = local.cmpge(IVec2::ZERO).all() && local.cmplt(size).all();
+                    inside.then_some((neighbor, local.as_uvec2()))
+                });
+            match heir {
+                Some((neighbor, local)) => {
+                    self.owners.insert(cell, neighbor);
+                    handovers.entry(neighbor).or_default().push(local);
+                }
+                None => {
+                    self.owners.remove(&cell);
+                }
+            }
+        }
+        handovers
+    }
+
+    /// 清除所有归属
+    pub fn clear(&mut self) {
+        self.owners.clear();
+    }
+}
+
+/// 已加载区块资源
+///
+/// 追踪当前加载的区块实体及其边缘数据
+#[derive(Resource, Default)]
+pub struct LoadedChunks {
+    /// 区块坐标到区块实体的映射
+    entities: HashMap<IVec2, Entity>,
+    /// 区块坐标到区块边缘的映射
+    edges: HashMap<IVec2, ChunkEdges>,
+    /// 区块坐标到每个节点模型实例的映射（保存地图和转交边缘时使用）
+    nodes: HashMap<IVec2, Vec<ModelInstance>>,
+    /// 边缘网格的归属
+    seams: SeamOwners,
+}
+
+impl LoadedChunks {
+    /// 检查区块是否已加载
+    pub fn contains(&self, coord: IVec2) -> bool {
+        self.entities.contains_key(&coord)
+    }
+
+    /// 遍历所有已加载区块的坐标
+    pub fn coords(&self) -> impl Iterator<Item = IVec2> + '_ {
+        self.entities.keys().copied()
+    }
+
+    /// 获取区块每个节点选中的模型实例（按区块网格的节点索引排列）
+    pub fn nodes(&self, coord: IVec2) -> Option<&[ModelInstance]> {
+        self.nodes.get(&coord).map(Vec::as_slice)
+    }
+
+    /// 获取边缘网格的归属
+    pub fn seams(&self) -> &SeamOwners {
+        &self.seams
+    }
+
+    /// 收集与相邻区块共享边缘的预设节点
+    ///
+    /// # 返回
+    /// 预设节点列表（包括与对角相邻区块共享的角落节点）
+    fn seam_constraints(&self, coord: IVec2) -> Vec<(NodeIndex, ModelInstance)> {
+        let grid = chunk_grid();
+        let mut presets: HashMap<NodeIndex, ModelInstance> = HashMap::new();
+
+        // 左侧区块的右边缘 -> 本区块的左边缘
+        if let Some(edges) = self.edges.get(&(coord - IVec2::X)) {
+            for y in 0..GRID_Y {
+                for z in 0..GRID_Z {
+                    let model = edges.right[(y * GRID_Z + z) as usize];
+                    presets
+                        .entry(grid.index_from_coords(0, y, z))
+                        .or_insert(model);
+                }
+            }
+        }
+        // 右侧区块的左边缘 -> 本区块的右边缘
+        if let Some(edges) = self.edges.get(&(coord + IVec2::X)) {
+            for y in 0..GRID_Y {
+                for z in 0..GRID_Z {
+                    let model = edges.left[(y * GRID_Z + z) as usize];
+                    presets
+                        .entry(grid.index_from_coords(GRID_X - 1, y, z))
+                        .or_insert(model);
+                }
+            }
+        }
+        // 下方区块的上边缘 -> 本区块的下边缘
+        if let Some(edges) = self.edges.get(&(coord - IVec2::Y)) {
+            for x in 0..GRID_X {
+                for z in 0..GRID_Z {
+                    let model = edges.top[(x * GRID_Z + z) as usize];
+                    presets
+                        .entry(grid.index_from_coords(x, 0, z))
+                        .or_insert(model);
+                }
+            }
+        }
+        // 上方区块的下边缘 -> 本区块的上边缘
+        if let Some(edges) = self.edges.get(&(coord + IVec2::Y)) {
+            for x in 0..GRID_X {
+                for z in 0..GRID_Z {
+                    let model = edges.bottom[(x * GRID_Z + z) as usize];
+                    presets
+                        .entry(grid.index_from_coords(x, GRID_Y - 1, z))
+                        .or_insert(model);
+                }
+            }
+        }
+
+        // 对角相邻区块的角落 -> 本区块的四个角（下方两角取自对角区块的上边缘，上方两角取自下边缘）
+        let corners = [
+            (IVec2::new(-1, -1), (GRID_X - 1, true), (0, 0)),
+            (IVec2::new(1, -1), (0, true), (GRID_X - 1, 0)),
+            (IVec2::new(-1, 1), (GRID_X - 1, false), (0, GRID_Y - 1)),
+            (IVec2::new(1, 1), (0, false), (GRID_X - 1, GRID_Y - 1)),
+        ];
+        for (offset, (edge_x, top), (x, y)) in corners {
+            let Some(edges) = self.edges.get(&(coord + offset)) else {
+                continue;
+            };
+            let row = if top { &edges.top } else { &edges.bottom };
+            for z in 0..GRID_Z {
+                let model = row[(edge_x * GRID_Z + z) as usize];
+                presets
+                    .entry(grid.index_from_coords(x, y, z))
+                    .or_insert(model);
+            }
+        }
+
+        presets.into_iter().collect()
+    }
+
+    /// 卸载区块并销毁其所有瓦片实体
+    ///
+    /// 区块负责的边缘网格由仍然加载的相邻区块重新生成，已拾取的拾取物不重新生成。
+    /// 区块开辟的瓦片随区块一起移除
+    fn unload(
+        &mut self,
+        commands: &mut Commands,
+        generator: &WorldGenerator,
+        overrides: &mut TileOverrides,
+        collected: &CollectedPickups,
+        coord: IVec2,
+    ) {
+        if let Some(entity) = self.entities.remove(&coord) {
+            commands.entity(entity).despawn();
+        }
+        self.edges.remove(&coord);
+        self.nodes.remove(&coord);
+        overrides.remove_chunk(coord);
+
+        let handovers = self
+            .seams
+            .release(coord, |neighbor| self.entities.contains_key(&neighbor));
+        for (heir, columns) in handovers {
+            let (Some(&entity), Some(nodes)) = (self.entities.get(&heir), self.nodes.get(&heir))
+            else {
+                continue;
+            };
+            let data = ChunkData::new(chunk_grid(), nodes.clone());
+            generator.spawn_nodes(
+                commands,
+                entity,
+                heir,
+                &data,
+                |x, y| !columns.contains(&UVec2::new(x, y)),
+                |cell| !collected.contains(cell),
+            );
+            debug!(
+                "Chunk {} took over {} seam tiles from {}",
+                heir,
+                columns.len(),
+                coord
+            );
+        }
+    }
+
+    /// 记录生成失败的占位区块
+    ///
+    /// 占位区块只有一个空的区块实体，没有瓦片、边缘和节点数据，
+    /// 这样流式加载不会每帧重试它，卸载后重新进入范围时会再次尝试生成
+    fn insert_placeholder(&mut self, commands: &mut Commands, coord: IVec2) {
+        let entity = commands
+            .spawn((
+                MapChunk { coord },
+                Transform::from_translation(chunk_origin(coord).extend(0.0)),
+                Visibility::default(),
+            ))
+            .id();
+        self.entities.insert(coord, entity);
+    }
+
+    /// 卸载所有区块并销毁其瓦片实体
+    pub fn clear(&mut self, commands: &mut Commands) {
+        for (_, entity) in self.entities.drain() {
+            commands.entity(entity).despawn();
+        }
+        self.edges.clear();
+        self.nodes.clear();
+        self.seams.clear();
+    }
+}
+
+/// 整个世界网格的原点（出生区块的左下角）
+pub fn world_origin() -> Vec2 {
+    Vec2::new(
+        -TILE_SIZE * GRID_X as f32 / 2.0,
+        -TILE_SIZE * GRID_Y as f32 / 2.0,
+    )
+}
+
+/// 获取区块左下角的世界坐标
+pub fn chunk_origin(coord: IVec2) -> Vec2 {
+    world_origin()
+        + Vec2::new(
+            (coord.x * CHUNK_STRIDE_X) as f32,
+            (coord.y * CHUNK_STRIDE_Y) as f32,
+        ) * TILE_SIZE
+}
+
+/// 获取区块左下角的世界网格坐标（出生区块左下角为原点）
+pub fn chunk_origin_cell(coord: IVec2) -> IVec2 {
+    coord * IVec2::new(CHUNK_STRIDE_X, CHUNK_STRIDE_Y)
+}
+
+/// 遍历区块覆盖的所有世界网格坐标（出生区块左下角为原点）
+pub fn chunk_cells(coord: IVec2) -> impl Iterator<Item = IVec2> {
+    let origin = chunk_origin_cell(coord);
+    (0..GRID_Y as i32)
+        .flat_map(|y| (0..GRID_X as i32).map(move |x| IVec2::new(x, y)))
+        .map(move |cell| origin + cell)
+}
+
+/// 将世界坐标转换为世界网格坐标（出生区块左下角为原点）
+pub fn world_to_cell(world_pos: Vec2) -> IVec2 {
+    ((world_pos - world_origin()) / TILE_SIZE)
+        .floor()
+        .as_ivec2()
+}
+
+/// 将世界坐标转换为区块坐标
+pub fn world_to_chunk(world_pos: Vec2) -> IVec2 {
+    let cell = (world_pos - world_origin()) / TILE_SIZE;
+    IVec2::new(
+        (cell.x / CHUNK_STRIDE_X as f32).floor() as i32,
+        (cell.y / CHUNK_STRIDE_Y as f32).floor() as i32,
+    )
+}
+
+/// 根据世界种子和区块坐标派生区块种子（SplitMix64）
+pub fn chunk_seed(world_seed: u64, coord: IVec2) -> u64 {
+    let packed = ((coord.x as u32 as u64) << 32) | coord.y as u32 as u64;
+    let mut z = world_seed ^ packed.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// 获取区块第 `attempt` 次尝试使用的种子
+///
+/// 第一次尝试使用区块种子，之后每次重试都派生一个新的种子
+pub fn attempt_seed(world_seed: u64, coord: IVec2, attempt: u32) -> u64 {
+    chunk_seed(world_seed.wrapping_add(attempt as u64), coord)
+}
+
+/// 在重试预算内生成区块数据
+///
+/// 每次失败都发送一条 [`GenerationFailed`] 消息并换一个种子重试。
+/// 带边缘约束的尝试全部失败时，最后再尝试一次无约束生成（接缝可能不连续，
+/// 共享的边缘仍然由相邻区块生成），但结构节点始终保留。
+fn generate_chunk(
+    generator: &WorldGenerator,
+    world_seed: u64,
+    coord: IVec2,
+    seams: Vec<(NodeIndex, ModelInstance)>,
+    budget: u32,
+    failures: &mut MessageWriter<GenerationFailed>,
+) -> Result<ChunkData, GenerationError> {
+    let constrained = !seams.is_empty();
+    let structures = generator
+        .structures()
+        .presets(chunk_origin_cell(coord), GRID_X, GRID_Y);
+    // 结构节点优先于接缝节点
+    let mut presets: HashMap<NodeIndex, ModelInstance> = seams.into_iter().collect();
+    presets.extend(structures.iter().copied());
+    let mut presets: Vec<_> = presets.into_iter().collect();
+    presets.sort_by_key(|(index, _)| *index);
+    let mut last_error = None;
+
+    for attempt in 0..budget.max(1) {
+        let seed = attempt_seed(world_seed, coord, attempt);
+        match generator.generate(coord, seed, presets.clone()) {
+            Ok(data) => return Ok(data),
+            Err(error) => {
+                failures.write(GenerationFailed {
+                    coord,
+                    seed,
+                    attempt,
+                    error: error.clone(),
+                });
+                last_error = Some(error);
+            }
+        }
+    }
+
+    if constrained {
+        warn!(
+            "Chunk {} failed with seam constraints, retrying unconstrained",
+            coord
+        );
+        let seed = attempt_seed(world_seed, coord, 0);
+        match generator.generate(coord, seed, structures) {
+            Ok(data) => return Ok(data),
+            Err(error) => {
+                failures.write(GenerationFailed {
+                    coord,
+                    seed,
+                    attempt: budget.max(1),
+                    error: error.clone(),
+                });
+                last_error = Some(error);
+            }
+        }
+    }
+
+    Err(last_error.unwrap_or(GenerationError::Setup("no attempts made".to_string())))
+}
+
+/// 加载单个区块
+///
+/// 以相邻区块的边缘作为约束运行 WFC，并生成区块实体和瓦片实体。
+/// 已由相邻区块负责的边缘网格不重复生成实体。
+/// 区块存在于加载的地图存档中时直接使用保存的模型，不运行 WFC，
+/// 并且只生成保存时还在的拾取物。已拾取的拾取物（[`CollectedPickups`]）不再生成。
+/// 区块的连通性只根据区块自己的瓦片检查，开辟的瓦片记录在 [`TileOverrides`] 中
+///
+/// # 返回
+/// 重试预算耗尽时返回最后一次的生成错误，此时不会生成区块实体
+#[allow(clippy::too_many_arguments)]
+pub fn load_chunk(
+    commands: &mut Commands,
+    generator: &WorldGenerator,
+    chunks: &mut LoadedChunks,
+    overrides: &mut TileOverrides,
+    saved: Option<&SavedChunks>,
+    collected: &CollectedPickups,
+    world_seed: u64,
+    coord: IVec2,
+    budget: u32,
+    failures: &mut MessageWriter<GenerationFailed>,
+) -> Result<(), GenerationError> {
+    let presets = chunks.seam_constraints(coord);
+    let saved_data = saved.and_then(|saved| saved.chunk_data(world_seed, coord));
+    let from_save = saved_data.is_some();
+    let data = match saved_data {
+        Some(data) => data,
+        None => generate_chunk(generator, world_seed, coord, presets, budget, failures)?,
+    };
+    let owned_elsewhere = chunks.seams.claim(coord);
+
+    let origin = chunk_origin(coord);
+    let chunk_entity = commands
+        .spawn((
+            MapChunk { coord },
+            Transform::from_translation(origin.extend(0.0)),
+            Visibility::default(),
+        ))
+        .id();
+
+    generator.spawn_nodes(
+        commands,
+        chunk_entity,
+        coord,
+        &data,
+        |x, y| owned_elsewhere.contains(&UVec2::new(x, y)),
+        |cell| {
+            !collected.contains(cell)
+                && (!from_save || saved.is_some_and(|saved| saved.has_pickup(cell)))
+        },
+    );
+    // 连通区块内的孤立区域，接触区块边界的区域可能经由相邻区块连通，不需要开辟
+    let stats = overrides.carve_chunk(coord, &mut generator.chunk_tiles(coord, &data));
+    if !stats.isolated.is_empty() {
+        debug!(
+            "Chunk {} connectivity: {} isolated regions, carved {} tiles, {} still unreachable",
+            coord,
+            stats.isolated.len(),
+            stats.carved_tiles,
+            stats.unreachable.len()
+        );
+    }
+    for region in &stats.unreachable {
+        warn!(
+            "Isolated region of {} tiles at {} in chunk {} could not be connected",
+            region.size, region.sample, coord
+        );
+    }
+    chunks.edges.insert(coord, ChunkEdges::from_data(&data));
+    chunks.nodes.insert(coord, data.iter().copied().collect());
+    chunks.entities.insert(coord, chunk_entity);
+
+    if from_save {
+        debug!("Loaded chunk {} from saved map", coord);
+    } else {
+        debug!("Loaded chunk {}", coord);
+    }
+    Ok(())
+}
+
+/// 区块流式加载系统
+///
+/// 根据玩家位置加载附近的区块并卸载远处的区块。
+/// 每帧最多加载一个区块，避免 WFC 计算造成卡顿。
+/// 重试预算耗尽的区块留空并记录错误，不中断游戏（错误界面只用于初始加载阶段）
+#[allow(clippy::too_many_arguments)]
+pub fn stream_chunks(
+    mut commands: Commands,
+    generator: Option<Res<WorldGenerator>>,
+    saved: Option<Res<SavedChunks>>,
+    collected: Res<CollectedPickups>,
+    seed: Res<WorldSeed>,
+    budget: Res<GenerationRetryBudget>,
+    mut chunks: ResMut<LoadedChunks>,
+    mut overrides: ResMut<TileOverrides>,
+    mut built: ResMut<CollisionMapBuilt>,
+    mut failures: MessageWriter<GenerationFailed>,
+    player_query: Query<&Transform, With<Player>>,
+) {
+    let Some(generator) = generator else {
+        return;
+    };
+    let Ok(player_transform) = player_query.single() else {
+        return;
+    };
+
+    let center = world_to_chunk(player_transform.translation.truncate());
+    let mut changed = false;
+
+    // 卸载远处的区块
+    let far: Vec<IVec2> = chunks
+        .coords()
+        .filter(|coord| (*coord - center).abs().max_element() > CHUNK_UNLOAD_RADIUS)
+        .collect();
+    for coord in far {
+        chunks.unload(&mut commands, &generator, &mut overrides, &collected, coord);
+        changed = true;
+    }
+
+    // 加载距离玩家最近的缺失区块
+    let nearest_missing = (-CHUNK_LOAD_RADIUS..=CHUNK_LOAD_RADIUS)
+        .flat_map(|dy| (-CHUNK_LOAD_RADIUS..=CHUNK_LOAD_RADIUS).map(move |dx| IVec2::new(dx, dy)))
+        .map(|offset| center + offset)
+        .filter(|coord| !chunks.contains(*coord))
+        .min_by_key(|coord| (*coord - center).length_squared());
+    if let Some(coord) = nearest_missing {
+        if let Err(err) = load_chunk(
+            &mut commands,
+            &generator,
+            &mut chunks,
+            &mut overrides,
+            saved.as_deref(),
+            &collected,
+            seed.value,
+            coord,
+            budget.0,
+            &mut failures,
+        ) {
+            error!(
+                "Chunk {} failed after {} attempts, leaving it empty: {}",
+                coord,
+                budget.0.max(1),
+                err
+            );
+            chunks.insert_placeholder(&mut commands, coord);
+        }
+        changed = true;
+    }
+
+    // 区块发生变化时重建碰撞地图
+    if changed {
+        built.0 = false;
+    }
+}
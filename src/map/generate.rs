@@ -1,77 +1,582 @@
-use crate::config::map::{GRID_X, GRID_Y, NODE_SIZE_Z, TILE_SIZE};
-use crate::map::assets::{load_assets, prepare_tilemap_handles};
-use crate::map::rules::build_world;
+use crate::collision::{CollisionMap, TileOverrides, TileType, convert_water_edges_to_shore};
+use crate::config::map::{GENERATION_RETRY_BUDGET, GRID_X, GRID_Y, NODE_SIZE_Z, TILE_SIZE};
+use crate::inventory::CollectedPickups;
+use crate::map::animation::{AnimatedTile, TileAnimations};
+use crate::map::assets::{ModelSprites, load_assets, prepare_tilemap_handles};
+use crate::map::biome::{BiomeBlend, BiomeMap};
+use crate::map::chunk::{LoadedChunks, chunk_origin, chunk_origin_cell, load_chunk};
+use crate::map::rules::{TerrainRules, build_world, build_world_weighted};
+use crate::map::save::SavedChunks;
+use crate::map::seed::{self, WorldSeed};
+use crate::map::structure::{StructureLayout, resolve_structures};
+use crate::map::tilemap::TilesetDefinition;
+use crate::rendering::y_sort_local_offset;
+use crate::state::GameState;
 use bevy::prelude::*;
 use bevy_procedural_tilemaps::prelude::*;
+use bevy_procedural_tilemaps::proc_gen::generator::model::ModelInstance;
+use bevy_procedural_tilemaps::proc_gen::generator::rules::Rules;
+use bevy_procedural_tilemaps::proc_gen::grid::GridData;
+use bevy_procedural_tilemaps::proc_gen::{GeneratorError, NodeIndex};
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::fmt;
+use std::sync::{Arc, Mutex};
 
-/// 资源路径
-const ASSETS_PATH: &str = "tile_layers";
-/// 瓦片图文件名
-const TILEMAP_FILE: &str = "tilemap.png";
+/// 瓦片集定义文件路径
+pub const TILESET_FILE: &str = "tile_layers/tileset.ron";
+/// 地形规则文件路径
+pub const RULES_FILE: &str = "map/terrain.rules.ron";
 /// 单个网格节点在世界单位中的大小
-const NODE_SIZE: Vec3 = Vec3::new(TILE_SIZE, TILE_SIZE, NODE_SIZE_Z);
+pub const NODE_SIZE: Vec3 = Vec3::new(TILE_SIZE, TILE_SIZE, NODE_SIZE_Z);
 /// 资源缩放比例
-const ASSETS_SCALE: Vec3 = Vec3::new(2.0, 2.0, 1.0);
+pub const ASSETS_SCALE: Vec3 = Vec3::new(2.0, 2.0, 1.0);
 /// 地图中的 Z 层数量，从默认地形层派生
-const GRID_Z: u32 = 5;
+pub const GRID_Z: u32 = 5;
+/// 道具层（最上层）索引，角色等实体与该层的道具一起排序
+pub const PROPS_LAYER: u32 = GRID_Z - 1;
 
-/// 设置地图生成器
+/// 区块生成结果：每个网格节点最终选中的模型实例
+pub type ChunkData = GridData<Cartesian3D, ModelInstance, CartesianGrid<Cartesian3D>>;
+
+/// 地图生成错误
+#[derive(Debug, Clone)]
+pub enum GenerationError {
+    /// 生成器无法构建（例如预设节点与规则冲突）
+    Setup(String),
+    /// WFC 在某个节点遇到矛盾
+    Contradiction { node_index: NodeIndex },
+}
+
+impl fmt::Display for GenerationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GenerationError::Setup(reason) => write!(f, "generator setup failed: {}", reason),
+            GenerationError::Contradiction { node_index } => {
+                write!(f, "contradiction at node {}", node_index)
+            }
+        }
+    }
+}
+
+/// 地图生成失败消息
 ///
-/// 初始化地图生成系统，包括规则、网格和资源
-pub fn setup_generator(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    mut atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+/// 每次 WFC 运行失败时发送，随后会用新的种子重试
+#[derive(Message, Debug, Clone)]
+pub struct GenerationFailed {
+    /// 失败的区块坐标
+    pub coord: IVec2,
+    /// 本次尝试使用的种子
+    pub seed: u64,
+    /// 尝试次数（从 0 开始）
+    pub attempt: u32,
+    /// 失败原因
+    pub error: GenerationError,
+}
+
+/// 生成重试预算资源
+///
+/// 每个区块最多尝试的种子数量。初始加载阶段全部失败后显示错误界面，
+/// 游戏中流式加载的区块全部失败后留空
+#[derive(Resource, Debug, Copy, Clone)]
+pub struct GenerationRetryBudget(pub u32);
+
+impl Default for GenerationRetryBudget {
+    fn default() -> Self {
+        Self(GENERATION_RETRY_BUDGET)
+    }
+}
+
+impl GenerationRetryBudget {
+    /// 按优先级解析重试预算
+    ///
+    /// 优先级：命令行参数（`--retry-budget`）> 环境变量 > 设置文件 > 默认值
+    pub fn resolve() -> Self {
+        seed::retry_budget_setting().map_or_else(Self::default, Self)
+    }
+}
+
+/// 地图生成中止资源
+///
+/// 重试预算耗尽或规则无效时插入，错误界面显示其中的原因
+#[derive(Resource, Debug, Clone)]
+pub struct GenerationAborted {
+    /// 中止原因
+    pub reason: String,
+}
+
+/// 中止地图生成并切换到错误界面
+pub fn abort_generation(
+    commands: &mut Commands,
+    next_state: &mut NextState<GameState>,
+    reason: String,
 ) {
-    // 构建世界模型、资源和连接器集合
-    let (assets_definitions, models, socket_collection) = build_world();
+    error!("❌ Map generation aborted: {}", reason);
+    commands.insert_resource(GenerationAborted { reason });
+    next_state.set(GameState::GenerationFailed);
+}
 
-    // 创建 3D 笛卡尔坐标系的规则
-    // 使用 Z 轴向前作为旋转轴
-    let rules = RulesBuilder::new_cartesian_3d(models, socket_collection)
+/// 记录生成失败的种子，便于之后复现调试
+pub fn log_generation_failures(mut failures: MessageReader<GenerationFailed>) {
+    for failure in failures.read() {
+        warn!(
+            "⚠️ WFC failed for chunk {} with seed {} (attempt {}): {}",
+            failure.coord,
+            failure.seed,
+            failure.attempt + 1,
+            failure.error
+        );
+    }
+}
+
+/// 创建单个区块使用的 3D 笛卡尔网格
+pub fn chunk_grid() -> CartesianGrid<Cartesian3D> {
+    map_grid(GRID_X, GRID_Y)
+}
+
+/// 创建指定尺寸的 3D 笛卡尔网格（Z 层数固定为 `GRID_Z`）
+pub fn map_grid(width: u32, height: u32) -> CartesianGrid<Cartesian3D> {
+    CartesianGrid::new_cartesian_3d(width, height, GRID_Z, false, false, false)
+}
+
+/// 编译 WFC 规则
+///
+/// 创建 3D 笛卡尔坐标系的规则，使用 Z 轴向前作为旋转轴
+///
+/// # 参数
+/// - `models`: 模型集合
+/// - `socket_collection`: 连接器集合
+///
+/// # 返回
+/// 编译好的规则，失败时返回错误
+pub fn compile_rules(
+    models: ModelCollection<Cartesian3D>,
+    socket_collection: SocketCollection,
+) -> Result<Rules<Cartesian3D>, GenerationError> {
+    RulesBuilder::new_cartesian_3d(models, socket_collection)
         .with_rotation_axis(Direction::ZForward)
         .build()
-        .unwrap();
+        .map_err(|err| GenerationError::Setup(format!("{err:?}")))
+}
 
-    // 创建 3D 笛卡尔网格
-    let grid = CartesianGrid::new_cartesian_3d(GRID_X, GRID_Y, GRID_Z, false, false, false);
+/// 按生物群系混合比例编译 WFC 规则
+///
+/// 模型和连接器与基础规则相同，只有生成权重按混合后的倍率调整
+///
+/// # 参数
+/// - `terrain_rules`: 地形规则
+/// - `blend`: 生物群系混合比例
+///
+/// # 返回
+/// 编译好的规则，失败时返回错误
+pub fn compile_biome_rules(
+    terrain_rules: &TerrainRules,
+    blend: &BiomeBlend,
+) -> Result<Rules<Cartesian3D>, GenerationError> {
+    let world = build_world_weighted(terrain_rules, &|model| {
+        blend.multiplier(terrain_rules, model)
+    })
+    .map_err(|err| GenerationError::Setup(err.to_string()))?;
+    compile_rules(world.models, world.socket_collection)
+}
 
-    // 构建地图生成器
-    let gen_builder = GeneratorBuilder::new()
+/// 在指定网格上运行一次 WFC 生成
+///
+/// # 参数
+/// - `rules`: 共享的 WFC 规则
+/// - `grid`: 生成使用的网格
+/// - `seed`: 随机数种子
+/// - `initial_nodes`: 预先确定的节点
+///
+/// # 返回
+/// 生成结果，失败时返回错误
+pub fn generate_grid(
+    rules: Arc<Rules<Cartesian3D>>,
+    grid: CartesianGrid<Cartesian3D>,
+    seed: u64,
+    initial_nodes: Vec<(NodeIndex, ModelInstance)>,
+) -> Result<ChunkData, GenerationError> {
+    let mut gen_builder = GeneratorBuilder::new()
         // 添加规则
-        .with_rules(rules)
+        .with_shared_rules(rules)
         // 添加网格
-        .with_grid(grid.clone())
-        // 使用随机种子
-        .with_rng(RngMode::RandomSeed)
+        .with_grid(grid)
+        // 使用指定种子，保证地图可复现
+        .with_rng(RngMode::Seeded(seed))
         // 使用最小剩余值启发式算法（提高生成效率）
         .with_node_heuristic(NodeSelectionHeuristic::MinimumRemainingValue)
         // 使用加权概率启发式算法（控制模型选择）
         .with_model_heuristic(ModelSelectionHeuristic::WeightedProbability);
 
+    // 设置预先确定的节点
+    if !initial_nodes.is_empty() {
+        gen_builder = gen_builder
+            .with_initial_nodes(initial_nodes)
+            .map_err(|err| GenerationError::Setup(format!("{err:?}")))?;
+    }
+
     // 创建生成器实例
-    let generator = gen_builder.build().unwrap();
+    let mut generator = gen_builder
+        .build()
+        .map_err(|err| GenerationError::Setup(format!("{err:?}")))?;
+
+    match generator.generate_grid() {
+        Ok((_, grid_data)) => Ok(grid_data),
+        Err(GeneratorError { node_index }) => Err(GenerationError::Contradiction { node_index }),
+    }
+}
+
+/// 计算节点中一个精灵相对于网格原点的位置
+///
+/// # 参数
+/// - `x`, `y`, `z`: 节点的网格坐标
+/// - `grid_offset`: 精灵的网格偏移量
+/// - `world_offset`: 精灵的世界坐标偏移量
+///
+/// # 返回
+/// 精灵的平移量
+pub fn node_translation(
+    (x, y, z): (u32, u32, u32),
+    grid_offset: &GridDelta,
+    world_offset: Vec3,
+) -> Vec3 {
+    let mut translation = Vec3::new(
+        NODE_SIZE.x * (x as f32 + grid_offset.dx as f32 + 0.5),
+        NODE_SIZE.y * (y as f32 + grid_offset.dy as f32 + 0.5),
+        NODE_SIZE.z * (z as f32 + grid_offset.dz as f32),
+    );
+    // 根据节点所在行相对于网格底部的 y 坐标偏移 z 值，靠下的瓦片绘制在上层，
+    // 区块实体的 z 值补上区块底部的深度。
+    // 多格道具的所有部分都使用节点本身（底部）的行，与 YSort 的脚下锚点一致
+    translation.z += y_sort_local_offset(NODE_SIZE.y * y as f32);
+    translation + world_offset
+}
+
+/// 世界生成器资源
+///
+/// 缓存编译好的规则和模型精灵表，每个区块都用它来运行 WFC 并生成瓦片实体
+#[derive(Resource, Clone)]
+pub struct WorldGenerator {
+    /// 基础 WFC 规则（未定义生物群系时使用）
+    rules: Arc<Rules<Cartesian3D>>,
+    /// 地形规则，用于按生物群系重新编译规则
+    terrain_rules: Arc<TerrainRules>,
+    /// 生物群系地图
+    biomes: BiomeMap,
+    /// 按生物群系混合比例缓存的 WFC 规则
+    biome_rules: Arc<Mutex<HashMap<BiomeBlend, Arc<Rules<Cartesian3D>>>>>,
+    /// 预先放置的结构
+    structures: Arc<StructureLayout>,
+    /// 每个模型的名称（按模型索引）
+    model_names: Arc<Vec<String>>,
+    /// 每个模型对应的精灵
+    model_sprites: ModelSprites,
+}
+
+impl WorldGenerator {
+    /// 获取生物群系地图
+    pub fn biomes(&self) -> &BiomeMap {
+        &self.biomes
+    }
+
+    /// 获取预先放置的结构
+    pub fn structures(&self) -> &StructureLayout {
+        &self.structures
+    }
+
+    /// 获取每个模型的名称（按模型索引）
+    pub fn model_names(&self) -> &[String] {
+        &self.model_names
+    }
+
+    /// 获取区块使用的 WFC 规则
+    ///
+    /// 根据区块内各生物群系所占的比例混合模型权重，跨越生物群系边界的区块会得到混合后的权重
+    fn chunk_rules(&self, coord: IVec2) -> Arc<Rules<Cartesian3D>> {
+        let Some(blend) = self.biomes.chunk_blend(coord) else {
+            return self.rules.clone();
+        };
+
+        let mut cache = self
+            .biome_rules
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(rules) = cache.get(&blend) {
+            return rules.clone();
+        }
+
+        match compile_biome_rules(&self.terrain_rules, &blend) {
+            Ok(rules) => {
+                let rules = Arc::new(rules);
+                cache.insert(blend, rules.clone());
+                rules
+            }
+            Err(err) => {
+                warn!("Failed to compile biome rules for chunk {}: {}", coord, err);
+                self.rules.clone()
+            }
+        }
+    }
+
+    /// 运行一次区块生成
+    ///
+    /// # 参数
+    /// - `coord`: 区块坐标（用于选择生物群系规则）
+    /// - `seed`: 区块种子
+    /// - `initial_nodes`: 预先确定的节点（例如与相邻区块共享的边缘）
+    ///
+    /// # 返回
+    /// 生成结果，失败时返回错误
+    pub fn generate(
+        &self,
+        coord: IVec2,
+        seed: u64,
+        initial_nodes: Vec<(NodeIndex, ModelInstance)>,
+    ) -> Result<ChunkData, GenerationError> {
+        generate_grid(self.chunk_rules(coord), chunk_grid(), seed, initial_nodes)
+    }
+
+    /// 为区块生成瓦片实体
+    ///
+    /// # 参数
+    /// - `commands`: 命令队列
+    /// - `chunk_entity`: 区块实体，瓦片会作为它的子实体
+    /// - `coord`: 区块坐标
+    /// - `data`: 区块生成结果
+    /// - `skip`: 返回 true 的 (x, y) 列不生成实体（由相邻区块负责）
+    /// - `keep_pickup`: 对拾取物所在的世界网格坐标返回 false 时不生成该拾取物
+    pub fn spawn_nodes(
+        &self,
+        commands: &mut Commands,
+        chunk_entity: Entity,
+        coord: IVec2,
+        data: &ChunkData,
+        skip: impl Fn(u32, u32) -> bool,
+        keep_pickup: impl Fn(IVec2) -> bool,
+    ) {
+        let grid = chunk_grid();
+        let origin = chunk_origin_cell(coord);
+
+        for (node_index, instance) in data.iter().enumerate() {
+            let pos = grid.pos_from_index(node_index);
+            if skip(pos.x, pos.y) {
+                continue;
+            }
+            let cell = origin + IVec2::new(pos.x as i32, pos.y as i32);
+
+            for model_sprite in self.model_sprites.get(instance.model_index) {
+                if model_sprite.pickable.is_some() && !keep_pickup(cell) {
+                    continue;
+                }
+                let translation = node_translation(
+                    (pos.x, pos.y, pos.z),
+                    &model_sprite.grid_offset,
+                    model_sprite.world_offset,
+                );
+
+                let mut entity = commands.spawn((
+                    model_sprite.sprite.clone(),
+                    Transform::from_translation(translation).with_scale(ASSETS_SCALE),
+                    ChildOf(chunk_entity),
+                ));
+                (model_sprite.spawn_commands)(&mut entity);
+                if let Some((animation, sync)) = model_sprite.animation {
+                    entity.insert(AnimatedTile::new(animation, sync, cell));
+                }
+            }
+        }
+    }
+
+    /// 构建只包含一个区块的碰撞地图
+    ///
+    /// 只使用区块自己的生成结果：每个网格位置保留 Z 轴最高的瓦片类型（与 build_collision_map 相同），
+    /// 然后检测海岸。用于按区块检查连通性
+    ///
+    /// # 参数
+    /// - `coord`: 区块坐标
+    /// - `data`: 区块生成结果
+    ///
+    /// # 返回
+    /// 原点为区块左下角、大小为一个区块的碰撞地图
+    pub fn chunk_tiles(&self, coord: IVec2, data: &ChunkData) -> CollisionMap {
+        let grid = chunk_grid();
+        let origin = chunk_origin(coord);
+        let mut map =
+            CollisionMap::new(GRID_X as i32, GRID_Y as i32, TILE_SIZE, origin.x, origin.y);
+        // 每个网格位置的最高层瓦片：(瓦片类型, Z轴高度)
+        let mut layer_tracker: HashMap<IVec2, (TileType, f32)> = HashMap::new();
+
+        for (node_index, instance) in data.iter().enumerate() {
+            let pos = grid.pos_from_index(node_index);
+            for model_sprite in self.model_sprites.get(instance.model_index) {
+                let Some(tile_type) = model_sprite.tile_type else {
+                    continue;
+                };
+                let translation = node_translation(
+                    (pos.x, pos.y, pos.z),
+                    &model_sprite.grid_offset,
+                    model_sprite.world_offset,
+                );
+                let cell = map.world_to_grid(origin + translation.truncate());
+                match layer_tracker.entry(cell) {
+                    Entry::Occupied(mut entry) => {
+                        if translation.z > entry.get().1 {
+                            *entry.get_mut() = (tile_type, translation.z);
+                        }
+                    }
+                    Entry::Vacant(entry) => {
+                        entry.insert((tile_type, translation.z));
+                    }
+                }
+            }
+        }
+
+        for (cell, (tile_type, _z)) in layer_tracker.iter() {
+            map.set_tile(cell.x, cell.y, *tile_type);
+        }
+        convert_water_edges_to_shore(&mut map);
+        map
+    }
+}
+
+/// 地图数据句柄资源
+///
+/// 存储地形规则和瓦片集定义资源的句柄
+#[derive(Resource)]
+pub struct MapDataHandles {
+    /// 地形规则句柄
+    pub rules: Handle<TerrainRules>,
+    /// 瓦片集定义句柄
+    pub tileset: Handle<TilesetDefinition>,
+}
+
+/// 加载地图数据
+///
+/// 在启动时开始加载地形规则和瓦片集定义文件
+pub fn load_map_data(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(MapDataHandles {
+        rules: asset_server.load(RULES_FILE),
+        tileset: asset_server.load(TILESET_FILE),
+    });
+}
+
+/// 设置地图生成器
+///
+/// 等待地形规则和瓦片集加载完成后初始化地图生成系统，包括规则、网格和资源，并生成出生点所在的区块。
+/// 规则无效或出生区块在重试预算内始终生成失败时切换到错误界面。
+#[allow(clippy::too_many_arguments)]
+pub fn setup_generator(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    seed: Res<WorldSeed>,
+    budget: Res<GenerationRetryBudget>,
+    mut chunks: ResMut<LoadedChunks>,
+    mut overrides: ResMut<TileOverrides>,
+    saved: Option<Res<SavedChunks>>,
+    collected: Res<CollectedPickups>,
+    handles: Res<MapDataHandles>,
+    terrain_rules: Res<Assets<TerrainRules>>,
+    tilesets: Res<Assets<TilesetDefinition>>,
+    mut failures: MessageWriter<GenerationFailed>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    // 等待地形规则和瓦片集加载完成
+    let (Some(terrain_rules), Some(tileset)) = (
+        terrain_rules.get(&handles.rules),
+        tilesets.get(&handles.tileset),
+    ) else {
+        return;
+    };
+
+    // 构建世界模型、资源和连接器集合
+    let world = match build_world(terrain_rules) {
+        Ok(world) => world,
+        Err(err) => {
+            let reason = format!("invalid terrain rules '{}': {}", RULES_FILE, err);
+            abort_generation(&mut commands, &mut next_state, reason);
+            return;
+        }
+    };
+    info!(
+        "Terrain rules loaded: {} models in {} layers",
+        world.model_names.len(),
+        terrain_rules.layers.len()
+    );
+
+    // 创建 3D 笛卡尔坐标系的规则
+    let rules = match compile_rules(world.models, world.socket_collection) {
+        Ok(rules) => rules,
+        Err(err) => {
+            let reason = format!("failed to compile terrain rules '{}': {}", RULES_FILE, err);
+            abort_generation(&mut commands, &mut next_state, reason);
+            return;
+        }
+    };
+
+    // 解析结构放置，结构节点会在 WFC 运行前预先确定
+    let structures = match resolve_structures(terrain_rules) {
+        Ok(structures) => structures,
+        Err(err) => {
+            let reason = format!("invalid structures in '{}': {}", RULES_FILE, err);
+            abort_generation(&mut commands, &mut next_state, reason);
+            return;
+        }
+    };
+    if !structures.is_empty() {
+        info!("🏛️ Structures placed: {}", structures.len());
+    }
 
     // 准备瓦片图句柄
-    let tilemap_handles =
-        prepare_tilemap_handles(&asset_server, &mut atlas_layouts, ASSETS_PATH, TILEMAP_FILE);
-
-    // 加载地图资源
-    let models_assets = load_assets(&tilemap_handles, assets_definitions);
-
-    // 生成地图实体
-    commands.spawn((
-        // 设置地图位置（居中）
-        Transform::from_translation(Vec3 {
-            x: -TILE_SIZE * grid.size_x() as f32 / 2.,
-            y: -TILE_SIZE * grid.size_y() as f32 / 2.,
-            z: 0.,
-        }),
-        // 添加网格组件
-        grid,
-        // 添加生成器组件
-        generator,
-        // 添加节点生成器（用于生成地图实体）
-        NodesSpawner::new(models_assets, NODE_SIZE, ASSETS_SCALE).with_z_offset_from_y(true),
-    ));
+    let tilemap_handles = prepare_tilemap_handles(&asset_server, &mut atlas_layouts, tileset);
+
+    // 收集瓦片动画并加载地图资源
+    let animations = TileAnimations::from_tileset(tileset);
+    if !animations.is_empty() {
+        info!("Tile animations: {}", animations.len());
+    }
+    let model_sprites = load_assets(tileset, &tilemap_handles, &animations, world.assets);
+
+    // 生物群系由世界种子决定
+    let biomes = BiomeMap::new(seed.value, terrain_rules);
+    if !biomes.is_empty() {
+        info!("Biomes: {}", terrain_rules.biomes.len());
+    }
+
+    let generator = WorldGenerator {
+        rules: Arc::new(rules),
+        terrain_rules: Arc::new(terrain_rules.clone()),
+        biomes: biomes.clone(),
+        biome_rules: Arc::default(),
+        structures: Arc::new(structures),
+        model_names: Arc::new(world.model_names),
+        model_sprites,
+    };
+    commands.insert_resource(tilemap_handles);
+    commands.insert_resource(animations);
+    commands.insert_resource(biomes);
+
+    // 生成出生点所在的区块，其余区块随玩家移动按需加载
+    if let Err(err) = load_chunk(
+        &mut commands,
+        &generator,
+        &mut chunks,
+        &mut overrides,
+        saved.as_deref(),
+        &collected,
+        seed.value,
+        IVec2::ZERO,
+        budget.0,
+        &mut failures,
+    ) {
+        let reason = format!(
+            "spawn chunk failed after {} attempts: {}",
+            budget.0.max(1),
+            err
+        );
+        abort_generation(&mut commands, &mut next_state, reason);
+        return;
+    }
+
+    commands.insert_resource(generator);
 }
@@ -0,0 +1,133 @@
+use crate::collision::{
+    CollisionMap, RegionStats, TileType, convert_water_edges_to_shore, ensure_connectivity,
+};
+use crate::config::map::TILE_SIZE;
+use crate::map::assets::SpawnableAsset;
+use crate::map::generate::{ChunkData, map_grid, node_translation};
+use bevy::prelude::*;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+
+/// 已放置的精灵
+///
+/// 记录一个精灵在地图中的名称和位置，不依赖任何渲染资源
+#[derive(Debug, Clone)]
+pub struct PlacedSprite {
+    /// 精灵名称
+    pub sprite_name: String,
+    /// 相对于地图左下角的位置（z 值用于绘制排序）
+    pub translation: Vec3,
+    /// 用于碰撞检测的瓦片类型
+    pub tile_type: Option<TileType>,
+}
+
+/// 地图布局
+///
+/// WFC 生成结果的无头表示：所有精灵的位置以及每个网格的瓦片类型，
+/// 供 mapgen 等工具在不启动游戏的情况下检查地图
+pub struct MapLayout {
+    /// 网格宽度
+    pub width: u32,
+    /// 网格高度
+    pub height: u32,
+    /// 所有精灵（按生成顺序）
+    pub sprites: Vec<PlacedSprite>,
+    /// 瓦片类型地图（与游戏中的碰撞地图构建规则一致）
+    pub collision: CollisionMap,
+    /// 连通性检查结果
+    pub regions: RegionStats,
+}
+
+impl MapLayout {
+    /// 从生成结果构建地图布局
+    ///
+    /// # 参数
+    /// - `data`: WFC 生成结果
+    /// - `width`: 网格宽度
+    /// - `height`: 网格高度
+    /// - `assets`: 每个模型对应的可生成资源列表
+    ///
+    /// # 返回
+    /// 地图布局
+    pub fn from_data(
+        data: &ChunkData,
+        width: u32,
+        height: u32,
+        assets: &[Vec<SpawnableAsset>],
+    ) -> Self {
+        let grid = map_grid(width, height);
+        let mut collision = CollisionMap::new(width as i32, height as i32, TILE_SIZE, 0.0, 0.0);
+        let mut sprites = Vec::new();
+        // 每个网格位置只保留 Z 轴最高的瓦片类型（与 build_collision_map 相同）
+        let mut layer_tracker: HashMap<IVec2, (TileType, f32)> = HashMap::new();
+
+        for (node_index, instance) in data.iter().enumerate() {
+            let pos = grid.pos_from_index(node_index);
+            let Some(model_assets) = assets.get(instance.model_index) else {
+                continue;
+            };
+
+            for asset in model_assets {
+                let translation =
+                    node_translation((pos.x, pos.y, pos.z), asset.grid_offset(), asset.offset());
+                sprites.push(PlacedSprite {
+                    sprite_name: asset.sprite_name().to_string(),
+                    translation,
+                    tile_type: asset.tile_type(),
+                });
+
+                let Some(tile_type) = asset.tile_type() else {
+                    continue;
+                };
+                let cell = collision.world_to_grid(translation.truncate());
+                match layer_tracker.entry(cell) {
+                    Entry::Occupied(mut entry) => {
+                        if translation.z > entry.get().1 {
+                            *entry.get_mut() = (tile_type, translation.z);
+                        }
+                    }
+                    Entry::Vacant(entry) => {
+                        entry.insert((tile_type, translation.z));
+                    }
+                }
+            }
+        }
+
+        for (cell, (tile_type, _z)) in layer_tracker.iter() {
+            collision.set_tile(cell.x, cell.y, *tile_type);
+        }
+        convert_water_edges_to_shore(&mut collision);
+        let (regions, carved) = ensure_connectivity(&mut collision);
+
+        // 与游戏中相同，移除被开辟路径上的道具精灵
+        sprites.retain(|sprite| {
+            !matches!(sprite.tile_type, Some(TileType::Tree | TileType::Rock))
+                || !carved
+                    .iter()
+                    .any(|tile| tile.grid == collision.world_to_grid(sprite.translation.truncate()))
+        });
+
+        Self {
+            width,
+            height,
+            sprites,
+            collision,
+            regions,
+        }
+    }
+
+    /// 将瓦片类型地图转换为 ASCII 文本
+    ///
+    /// 每个瓦片一个字符，第一行是地图的最上方
+    pub fn to_ascii(&self) -> String {
+        let mut text = String::with_capacity(((self.width + 1) * self.height) as usize);
+        for y in (0..self.height as i32).rev() {
+            for x in 0..self.width as i32 {
+                let tile = self.collision.get_tile(x, y).unwrap_or_default();
+                text.push(tile.symbol());
+            }
+            text.push('\n');
+        }
+        text
+    }
+}
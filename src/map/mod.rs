@@ -2,9 +2,92 @@
 //
 // 实现了基于 Wave Function Collapse (WFC) 算法的自动地图生成系统
 
+pub mod animation;
 pub mod assets;
+pub mod biome;
+pub mod chunk;
 pub mod generate;
+pub mod layout;
 pub mod model;
+pub mod regenerate;
 pub mod rules;
+pub mod save;
+pub mod seed;
 pub mod socket;
+pub mod stats;
+pub mod structure;
 pub mod tilemap;
+pub mod validate;
+
+use crate::collision::CollisionMap;
+use crate::map::generate::{GenerationFailed, GenerationRetryBudget, WorldGenerator};
+use crate::map::rules::TerrainRules;
+use crate::map::save::SavedChunks;
+use crate::map::tilemap::TilesetDefinition;
+use crate::state::GameState;
+use bevy::prelude::*;
+use bevy_common_assets::ron::RonAssetPlugin;
+
+pub use biome::{BiomeId, BiomeMap};
+pub use regenerate::RegenerateMap;
+pub use save::{LoadMap, SaveMap, SavedMap};
+pub use seed::WorldSeed;
+
+/// 地图插件
+///
+/// 负责加载地形规则和瓦片集、解析世界种子、设置地图生成器，并随玩家移动流式加载区块。
+/// 发送 [`RegenerateMap`] 消息可以在游戏中重新生成地图，
+/// 发送 [`SaveMap`] / [`LoadMap`] 消息可以将地图保存到文件或从文件加载。
+pub struct MapPlugin;
+
+impl Plugin for MapPlugin {
+    fn build(&self, app: &mut App) {
+        // 添加 RON 资源加载插件，用于加载地形规则和瓦片集定义文件
+        app.add_plugins((
+            RonAssetPlugin::<TerrainRules>::new(&["rules.ron"]),
+            RonAssetPlugin::<TilesetDefinition>::new(&["tileset.ron"]),
+        ))
+        .add_message::<GenerationFailed>()
+        .add_message::<RegenerateMap>()
+        .add_message::<SaveMap>()
+        .add_message::<LoadMap>()
+        .insert_resource(WorldSeed::resolve())
+        .insert_resource(GenerationRetryBudget::resolve())
+        .init_resource::<chunk::LoadedChunks>()
+        .add_systems(Startup, (seed::log_world_seed, generate::load_map_data))
+        .add_systems(
+            Update,
+            (
+                // 地形规则和瓦片集加载完成后设置地图生成器
+                generate::setup_generator
+                    .run_if(not(resource_exists::<WorldGenerator>))
+                    .run_if(in_state(GameState::Loading)),
+                chunk::stream_chunks.run_if(in_state(GameState::Playing)),
+                generate::log_generation_failures,
+                animation::animate_tiles.run_if(in_state(GameState::Playing)),
+                (
+                    save::save_map,
+                    // 在处理加载请求之前比对，避免与加载前的旧碰撞地图比对
+                    save::verify_loaded_map
+                        .run_if(resource_exists::<SavedChunks>)
+                        .run_if(resource_exists_and_changed::<CollisionMap>)
+                        .before(save::load_map),
+                    save::load_map,
+                )
+                    .run_if(in_state(GameState::Playing)),
+            ),
+        )
+        // 在 Update 之前处理重新生成请求，使状态切换在本帧生效
+        .add_systems(
+            PreUpdate,
+            regenerate::regenerate_map.run_if(in_state(GameState::Playing)),
+        );
+
+        #[cfg(debug_assertions)]
+        app.add_systems(
+            Update,
+            (regenerate::regenerate_on_key, save::save_load_on_key)
+                .run_if(in_state(GameState::Playing)),
+        );
+    }
+}
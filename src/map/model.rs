@@ -9,6 +9,14 @@ pub struct TerrainModelBuilder {
     pub models: ModelCollection<Cartesian3D>,
     /// 每个模型对应的可生成资源列表
     pub assets: Vec<Vec<SpawnableAsset>>,
+    /// 每个模型的名称
+    pub names: Vec<String>,
+}
+
+impl Default for TerrainModelBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl TerrainModelBuilder {
@@ -17,12 +25,14 @@ impl TerrainModelBuilder {
         Self {
             models: ModelCollection::new(),
             assets: Vec::new(),
+            names: Vec::new(),
         }
     }
 
     /// 创建新模型
     ///
     /// # 参数
+    /// - `name`: 模型名称
     /// - `template`: 模型模板
     /// - `assets`: 该模型对应的可生成资源列表
     ///
@@ -30,6 +40,7 @@ impl TerrainModelBuilder {
     /// 新创建模型的引用
     pub fn create_model<T>(
         &mut self,
+        name: &str,
         template: T,
         assets: Vec<SpawnableAsset>,
     ) -> &mut Model<Cartesian3D>
@@ -38,16 +49,23 @@ impl TerrainModelBuilder {
     {
         // 在模型集合中创建新模型
         let model_ref = self.models.create(template);
-        // 保存对应的资源
+        // 保存对应的资源和名称
         self.assets.push(assets);
+        self.names.push(name.to_string());
         model_ref
     }
 
     /// 将构建器拆分为组件
     ///
     /// # 返回
-    /// 包含资源列表和模型集合的元组
-    pub fn into_parts(self) -> (Vec<Vec<SpawnableAsset>>, ModelCollection<Cartesian3D>) {
-        (self.assets, self.models)
+    /// 包含资源列表、模型名称和模型集合的元组
+    pub fn into_parts(
+        self,
+    ) -> (
+        Vec<Vec<SpawnableAsset>>,
+        Vec<String>,
+        ModelCollection<Cartesian3D>,
+    ) {
+        (self.assets, self.names, self.models)
     }
 }
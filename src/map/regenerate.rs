@@ -0,0 +1,109 @@
+use crate::characters::input::Player;
+use crate::collision::{CollisionMap, CollisionMapBuilt, TileOverrides};
+use crate::inventory::{CollectedPickups, Inventory, Pickable};
+use crate::map::chunk::LoadedChunks;
+use crate::map::generate::{GenerationAborted, WorldGenerator};
+use crate::map::save::SavedChunks;
+use crate::map::seed::{SeedSource, WorldSeed};
+use crate::state::GameState;
+use bevy::prelude::*;
+
+/// 重新生成地图消息
+///
+/// 发送此消息即可在游戏中重新生成地图，无需重启应用：
+/// 销毁当前所有区块和拾取物，重置碰撞地图，回到加载状态重新运行地图生成器，
+/// 生成完成后把玩家放到新地图的可行走位置
+#[derive(Message, Debug, Copy, Clone, Default)]
+pub struct RegenerateMap {
+    /// 新的世界种子，为 None 时随机生成
+    pub seed: Option<u64>,
+    /// 是否保留玩家背包
+    pub keep_inventory: bool,
+}
+
+/// 按 F5 重新生成地图（调试用）
+///
+/// F5 保留背包，Shift+F5 同时清空背包
+pub fn regenerate_on_key(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut requests: MessageWriter<RegenerateMap>,
+) {
+    if keyboard.just_pressed(KeyCode::F5) {
+        let reset_inventory =
+            keyboard.pressed(KeyCode::ShiftLeft) || keyboard.pressed(KeyCode::ShiftRight);
+        requests.write(RegenerateMap {
+            seed: None,
+            keep_inventory: !reset_inventory,
+        });
+    }
+}
+
+/// 处理重新生成地图请求
+///
+/// 在 `PreUpdate` 中运行，使切换到加载状态在本帧的 `Update` 之前生效，
+/// 避免区块流式加载继续使用旧的生成器
+#[allow(clippy::too_many_arguments)]
+pub fn regenerate_map(
+    mut commands: Commands,
+    mut requests: MessageReader<RegenerateMap>,
+    mut seed: ResMut<WorldSeed>,
+    mut chunks: ResMut<LoadedChunks>,
+    mut built: ResMut<CollisionMapBuilt>,
+    mut inventory: ResMut<Inventory>,
+    saved: Option<Res<SavedChunks>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    pickups: Query<Entity, With<Pickable>>,
+    mut player_query: Query<&mut Transform, With<Player>>,
+) {
+    // 同一帧有多个请求时只处理最后一个
+    let Some(request) = requests.read().last().copied() else {
+        return;
+    };
+
+    *seed = match request.seed {
+        Some(value) => WorldSeed::new(value, SeedSource::Regenerate),
+        None => WorldSeed::new(rand::random(), SeedSource::Random),
+    };
+    info!(
+        "🔄 Regenerating map with seed {} (from {:?})",
+        seed.value, seed.source
+    );
+
+    // 先销毁拾取物，再销毁区块及其所有瓦片
+    for entity in pickups.iter() {
+        commands.entity(entity).despawn();
+    }
+    chunks.clear(&mut commands);
+
+    // 重置碰撞地图、运行时改变的瓦片和生成器，加载状态下会用新种子重新运行 setup_generator
+    built.0 = false;
+    commands.remove_resource::<CollisionMap>();
+    // 加载存档时恢复存档中的瓦片覆盖和已拾取的拾取物（种子与存档不同时为空）
+    let overrides = saved
+        .as_deref()
+        .map_or_else(TileOverrides::default, |saved| {
+            saved.tile_overrides(seed.value)
+        });
+    commands.insert_resource(overrides);
+    let collected = saved
+        .as_deref()
+        .map_or_else(CollectedPickups::default, |saved| {
+            saved.collected_pickups(seed.value)
+        });
+    commands.insert_resource(collected);
+    commands.remove_resource::<WorldGenerator>();
+    commands.remove_resource::<GenerationAborted>();
+
+    if !request.keep_inventory {
+        *inventory = Inventory::default();
+        info!("Inventory cleared");
+    }
+
+    // 玩家回到出生区块中心，进入游戏时再移动到最近的可行走位置
+    for mut transform in player_query.iter_mut() {
+        transform.translation.x = 0.0;
+        transform.translation.y = 0.0;
+    }
+
+    next_state.set(GameState::Loading);
+}
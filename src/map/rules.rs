@@ -3,751 +3,435 @@ use crate::inventory::ItemKind;
 use crate::map::assets::SpawnableAsset;
 use crate::map::model::TerrainModelBuilder;
 use crate::map::socket::{TerrainSockets, create_sockets};
+use bevy::prelude::*;
 use bevy_procedural_tilemaps::prelude::{
-    Cartesian3D, Direction, GridDelta, ModelCollection, ModelRotation, SocketCollection,
-    SocketsCartesian3D,
+    Cartesian3D, Direction, GridDelta, ModelCollection, ModelRotation, ModelTemplate, Socket,
+    SocketCollection, SocketsCartesian3D,
 };
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
 
-/// 构建泥土层
+/// 地形规则资源
 ///
-/// 创建泥土层的模型和连接规则
-fn build_dirt_layer(
-    terrain_model_builder: &mut TerrainModelBuilder,
-    terrain_sockets: &TerrainSockets,
-    socket_collection: &mut SocketCollection,
-) {
-    // 创建主要的泥土瓦片模型
-    terrain_model_builder
-        .create_model(
-            SocketsCartesian3D::Simple {
-                // x+ 方向的连接器
-                x_pos: terrain_sockets.dirt.material,
-                // x- 方向的连接器
-                x_neg: terrain_sockets.dirt.material,
-                // z+ 方向的连接器（向上）
-                z_pos: terrain_sockets.dirt.layer_up,
-                // z- 方向的连接器（向下）
-                z_neg: terrain_sockets.dirt.layer_down,
-                // y+ 方向的连接器
-                y_pos: terrain_sockets.dirt.material,
-                // y- 方向的连接器
-                y_neg: terrain_sockets.dirt.material,
-            },
-            vec![SpawnableAsset::new("dirt").with_tile_type(TileType::Dirt)],
-        )
-        // 设置生成权重（越高越常见）
-        .with_weight(20.);
-
-    // 添加连接规则：泥土材质只能与泥土材质连接
-    socket_collection.add_connections(vec![(
-        terrain_sockets.dirt.material,
-        vec![terrain_sockets.dirt.material],
-    )]);
+/// 从 `*.rules.ron` 文件加载，描述所有连接器、连接规则、模型模板和地形层
+#[derive(Asset, TypePath, Debug, Clone, Deserialize)]
+pub struct TerrainRules {
+    /// 连接器名称列表
+    pub sockets: Vec<String>,
+    /// 可复用的模型模板（按名称索引）
+    #[serde(default)]
+    pub templates: HashMap<String, TemplateDefinition>,
+    /// 地形层列表（按从下到上的顺序）
+    pub layers: Vec<LayerDefinition>,
+    /// 生物群系列表（为空表示整个地图使用同一套权重）
+    #[serde(default)]
+    pub biomes: Vec<BiomeDefinition>,
+    /// 手工设计的结构（瓦片印章）
+    #[serde(default)]
+    pub structures: Vec<StructureDefinition>,
+    /// 结构放置位置，在 WFC 运行前预先确定这些节点
+    #[serde(default)]
+    pub placements: Vec<StructurePlacement>,
 }
 
-/// 构建草地层
+/// 结构定义
 ///
-/// 创建绿色草地层的模型和连接规则，包括主瓦片、外角、内角和边缘
-fn build_grass_layer(
-    terrain_model_builder: &mut TerrainModelBuilder,
-    terrain_sockets: &TerrainSockets,
-    socket_collection: &mut SocketCollection,
-) {
-    // 空模型 - 代表泥土上方没有草地的区域
-    terrain_model_builder.create_model(
-        SocketsCartesian3D::Simple {
-            x_pos: terrain_sockets.void,
-            x_neg: terrain_sockets.void,
-            z_pos: terrain_sockets.grass.layer_up,
-            z_neg: terrain_sockets.grass.layer_down,
-            y_pos: terrain_sockets.void,
-            y_neg: terrain_sockets.void,
-        },
-        Vec::new(),
-    );
-
-    // 主草地瓦片
-    terrain_model_builder
-        .create_model(
-            SocketsCartesian3D::Multiple {
-                x_pos: vec![terrain_sockets.grass.material],
-                x_neg: vec![terrain_sockets.grass.material],
-                z_pos: vec![
-                    terrain_sockets.grass.layer_up,
-                    terrain_sockets.grass.grass_fill_up,
-                ],
-                z_neg: vec![terrain_sockets.grass.layer_down],
-                y_pos: vec![terrain_sockets.grass.material],
-                y_neg: vec![terrain_sockets.grass.material],
-            },
-            vec![SpawnableAsset::new("green_grass").with_tile_type(TileType::Grass)],
-        )
-        .with_weight(5.);
+/// 一个由字符网格描述的小型瓦片印章，每个字符通过图例映射到各地形层的模型
+#[derive(Debug, Clone, Deserialize)]
+pub struct StructureDefinition {
+    /// 结构名称
+    pub name: String,
+    /// 图例：字符到模型名称列表的映射（按地形层从下到上，每层一个模型）
+    pub legend: HashMap<char, Vec<String>>,
+    /// 字符网格，第一行是结构的最上方
+    pub rows: Vec<String>,
+}
 
-    // 外角模板
-    let green_grass_corner_out = SocketsCartesian3D::Simple {
-        x_pos: terrain_sockets.grass.void_and_grass,
-        x_neg: terrain_sockets.void,
-        z_pos: terrain_sockets.grass.layer_up,
-        z_neg: terrain_sockets.grass.layer_down,
-        y_pos: terrain_sockets.void,
-        y_neg: terrain_sockets.grass.grass_and_void,
-    }
-    .to_template();
+/// 结构放置
+#[derive(Debug, Clone, Deserialize)]
+pub struct StructurePlacement {
+    /// 结构名称
+    pub structure: String,
+    /// 结构左下角的世界网格坐标（出生区块左下角为原点）
+    pub at: (i32, i32),
+}
 
-    // 内角模板
-    let green_grass_corner_in = SocketsCartesian3D::Simple {
-        x_pos: terrain_sockets.grass.grass_and_void,
-        x_neg: terrain_sockets.grass.material,
-        z_pos: terrain_sockets.grass.layer_up,
-        z_neg: terrain_sockets.grass.layer_down,
-        y_pos: terrain_sockets.grass.material,
-        y_neg: terrain_sockets.grass.void_and_grass,
-    }
-    .to_template();
+/// 生物群系定义
+///
+/// 生物群系不改变模型和连接器，只按模型名称调整生成权重
+#[derive(Debug, Clone, Deserialize)]
+pub struct BiomeDefinition {
+    /// 生物群系名称
+    pub name: String,
+    /// 模型权重倍率（按模型名称索引，以 `*` 结尾的键按前缀匹配）
+    #[serde(default)]
+    pub weights: HashMap<String, f32>,
+}
 
-    // 边缘模板
-    let green_grass_side = SocketsCartesian3D::Simple {
-        x_pos: terrain_sockets.grass.void_and_grass,
-        x_neg: terrain_sockets.grass.grass_and_void,
-        z_pos: terrain_sockets.grass.layer_up,
-        z_neg: terrain_sockets.grass.layer_down,
-        y_pos: terrain_sockets.void,
-        y_neg: terrain_sockets.grass.material,
+impl BiomeDefinition {
+    /// 获取模型的权重倍率
+    ///
+    /// 精确匹配优先，否则使用最长的前缀匹配，都没有时返回 1.0
+    pub fn multiplier(&self, model: &str) -> f32 {
+        if let Some(weight) = self.weights.get(model) {
+            return *weight;
+        }
+        self.weights
+            .iter()
+            .filter_map(|(key, weight)| {
+                let prefix = key.strip_suffix('*')?;
+                model.starts_with(prefix).then_some((prefix.len(), *weight))
+            })
+            .max_by_key(|(len, _)| *len)
+            .map_or(1.0, |(_, weight)| weight)
     }
-    .to_template();
+}
 
-    // 创建草地外角的旋转版本，逆时针旋转
-    terrain_model_builder.create_model(
-        green_grass_corner_out.clone(),
-        vec![SpawnableAsset::new("green_grass_corner_out_tl").with_tile_type(TileType::Grass)],
-    );
-    terrain_model_builder.create_model(
-        green_grass_corner_out.rotated(ModelRotation::Rot90, Direction::ZForward),
-        vec![SpawnableAsset::new("green_grass_corner_out_bl").with_tile_type(TileType::Grass)],
-    );
-    terrain_model_builder.create_model(
-        green_grass_corner_out.rotated(ModelRotation::Rot180, Direction::ZForward),
-        vec![SpawnableAsset::new("green_grass_corner_out_br").with_tile_type(TileType::Grass)],
-    );
-    terrain_model_builder.create_model(
-        green_grass_corner_out.rotated(ModelRotation::Rot270, Direction::ZForward),
-        vec![SpawnableAsset::new("green_grass_corner_out_tr").with_tile_type(TileType::Grass)],
-    );
+/// 地形层定义
+#[derive(Debug, Clone, Deserialize)]
+pub struct LayerDefinition {
+    /// 层名称
+    pub name: String,
+    /// 该层的模型列表
+    pub models: Vec<ModelDefinition>,
+    /// 该层添加的连接规则
+    #[serde(default)]
+    pub connections: Vec<ConnectionDefinition>,
+}
 
-    // 创建草地内角的旋转版本，逆时针旋转
-    terrain_model_builder.create_model(
-        green_grass_corner_in.clone(),
-        vec![SpawnableAsset::new("green_grass_corner_in_tl").with_tile_type(TileType::Grass)],
-    );
-    terrain_model_builder.create_model(
-        green_grass_corner_in.rotated(ModelRotation::Rot90, Direction::ZForward),
-        vec![SpawnableAsset::new("green_grass_corner_in_bl").with_tile_type(TileType::Grass)],
-    );
-    terrain_model_builder.create_model(
-        green_grass_corner_in.rotated(ModelRotation::Rot180, Direction::ZForward),
-        vec![SpawnableAsset::new("green_grass_corner_in_br").with_tile_type(TileType::Grass)],
-    );
-    terrain_model_builder.create_model(
-        green_grass_corner_in.rotated(ModelRotation::Rot270, Direction::ZForward),
-        vec![SpawnableAsset::new("green_grass_corner_in_tr").with_tile_type(TileType::Grass)],
-    );
+/// 连接规则定义
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConnectionDefinition {
+    /// 连接器名称
+    pub socket: String,
+    /// 可以与之相连的连接器名称
+    pub connects_to: Vec<String>,
+    /// 是否为旋转连接（用于层与层之间的 Z 方向连接）
+    #[serde(default)]
+    pub rotated: bool,
+}
 
-    // 创建草地边缘的旋转版本，逆时针旋转
-    terrain_model_builder.create_model(
-        green_grass_side.clone(),
-        vec![SpawnableAsset::new("green_grass_side_t").with_tile_type(TileType::Grass)],
-    );
-    terrain_model_builder.create_model(
-        green_grass_side.rotated(ModelRotation::Rot90, Direction::ZForward),
-        vec![SpawnableAsset::new("green_grass_side_l").with_tile_type(TileType::Grass)],
-    );
-    terrain_model_builder.create_model(
-        green_grass_side.rotated(ModelRotation::Rot180, Direction::ZForward),
-        vec![SpawnableAsset::new("green_grass_side_b").with_tile_type(TileType::Grass)],
-    );
-    terrain_model_builder.create_model(
-        green_grass_side.rotated(ModelRotation::Rot270, Direction::ZForward),
-        vec![SpawnableAsset::new("green_grass_side_r").with_tile_type(TileType::Grass)],
-    );
+/// 六个方向的连接器定义
+#[derive(Debug, Clone, Deserialize)]
+pub struct SocketsDefinition {
+    /// x+ 方向的连接器
+    pub x_pos: Vec<String>,
+    /// x- 方向的连接器
+    pub x_neg: Vec<String>,
+    /// y+ 方向的连接器
+    pub y_pos: Vec<String>,
+    /// y- 方向的连接器
+    pub y_neg: Vec<String>,
+    /// z+ 方向的连接器（向上）
+    pub z_pos: Vec<String>,
+    /// z- 方向的连接器（向下）
+    pub z_neg: Vec<String>,
+}
 
-    // 创建连接规则
-    socket_collection.add_rotated_connection(
-        terrain_sockets.dirt.layer_up,
-        vec![terrain_sockets.grass.layer_down],
-    );
-    socket_collection.add_connections(vec![
-        (terrain_sockets.void, vec![terrain_sockets.void]),
-        (
-            terrain_sockets.grass.material,
-            vec![terrain_sockets.grass.material],
-        ),
-        (
-            terrain_sockets.grass.void_and_grass,
-            vec![terrain_sockets.grass.grass_and_void],
-        ),
-    ]);
+/// 模型模板定义
+#[derive(Debug, Clone, Deserialize)]
+pub struct TemplateDefinition {
+    /// 连接器
+    pub sockets: SocketsDefinition,
+    /// 默认生成权重
+    #[serde(default)]
+    pub weight: Option<f32>,
 }
 
-/// 构建黄色草地层
-///
-/// 创建黄色草地层的模型和连接规则
-fn build_yellow_grass_layer(
-    terrain_model_builder: &mut TerrainModelBuilder,
-    terrain_sockets: &TerrainSockets,
-    socket_collection: &mut SocketCollection,
-) {
-    // 空模型
-    terrain_model_builder.create_model(
-        SocketsCartesian3D::Simple {
-            x_pos: terrain_sockets.void,
-            x_neg: terrain_sockets.void,
-            z_pos: terrain_sockets.yellow_grass.layer_up,
-            z_neg: terrain_sockets.yellow_grass.layer_down,
-            y_pos: terrain_sockets.void,
-            y_neg: terrain_sockets.void,
-        },
-        Vec::new(),
-    );
+/// 模型连接器来源
+#[derive(Debug, Clone, Deserialize)]
+pub enum ModelSockets {
+    /// 引用命名模板
+    Template(String),
+    /// 直接定义连接器
+    Sockets(SocketsDefinition),
+}
 
-    // 主黄色草地瓦片
-    terrain_model_builder
-        .create_model(
-            SocketsCartesian3D::Simple {
-                x_pos: terrain_sockets.grass.material,
-                x_neg: terrain_sockets.grass.material,
-                z_pos: terrain_sockets.yellow_grass.layer_up,
-                z_neg: terrain_sockets.yellow_grass.yellow_grass_fill_down,
-                y_pos: terrain_sockets.grass.material,
-                y_neg: terrain_sockets.grass.material,
-            },
-            vec![SpawnableAsset::new("yellow_grass").with_tile_type(TileType::YellowGrass)],
-        )
-        .with_weight(5.);
+/// 模型旋转（绕 Z 轴逆时针）
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default, Deserialize)]
+pub enum Rotation {
+    #[default]
+    Rot0,
+    Rot90,
+    Rot180,
+    Rot270,
+}
 
-    // 外角模板
-    let yellow_grass_corner_out = SocketsCartesian3D::Simple {
-        x_pos: terrain_sockets.grass.void_and_grass,
-        x_neg: terrain_sockets.void,
-        z_pos: terrain_sockets.yellow_grass.layer_up,
-        z_neg: terrain_sockets.yellow_grass.yellow_grass_fill_down,
-        y_pos: terrain_sockets.void,
-        y_neg: terrain_sockets.grass.grass_and_void,
+impl From<Rotation> for ModelRotation {
+    fn from(rotation: Rotation) -> Self {
+        match rotation {
+            Rotation::Rot0 => ModelRotation::Rot0,
+            Rotation::Rot90 => ModelRotation::Rot90,
+            Rotation::Rot180 => ModelRotation::Rot180,
+            Rotation::Rot270 => ModelRotation::Rot270,
+        }
     }
-    .to_template();
+}
 
-    // 内角模板
-    let yellow_grass_corner_in = SocketsCartesian3D::Simple {
-        x_pos: terrain_sockets.grass.grass_and_void,
-        x_neg: terrain_sockets.grass.material,
-        z_pos: terrain_sockets.yellow_grass.layer_up,
-        z_neg: terrain_sockets.yellow_grass.yellow_grass_fill_down,
-        y_pos: terrain_sockets.grass.material,
-        y_neg: terrain_sockets.grass.void_and_grass,
-    }
-    .to_template();
+/// 模型定义
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModelDefinition {
+    /// 模型名称
+    pub name: String,
+    /// 连接器（模板或直接定义）
+    pub sockets: ModelSockets,
+    /// 相对于模板的旋转
+    #[serde(default)]
+    pub rotation: Rotation,
+    /// 生成权重，未设置时使用模板权重
+    #[serde(default)]
+    pub weight: Option<f32>,
+    /// 该模型生成的资源列表（为空表示不生成实体）
+    #[serde(default)]
+    pub assets: Vec<AssetDefinition>,
+}
 
-    // 边缘模板
-    let yellow_grass_side = SocketsCartesian3D::Simple {
-        x_pos: terrain_sockets.grass.void_and_grass,
-        x_neg: terrain_sockets.grass.grass_and_void,
-        z_pos: terrain_sockets.yellow_grass.layer_up,
-        z_neg: terrain_sockets.yellow_grass.yellow_grass_fill_down,
-        y_pos: terrain_sockets.void,
-        y_neg: terrain_sockets.grass.material,
-    }
-    .to_template();
+/// 可生成资源定义
+#[derive(Debug, Clone, Deserialize)]
+pub struct AssetDefinition {
+    /// 精灵名称
+    pub sprite: String,
+    /// 网格偏移量 (x, y, z)
+    #[serde(default)]
+    pub grid_offset: (i32, i32, i32),
+    /// 世界坐标偏移量 (x, y, z)
+    #[serde(default)]
+    pub offset: (f32, f32, f32),
+    /// 用于碰撞检测的瓦片类型
+    #[serde(default)]
+    pub tile_type: Option<TileType>,
+    /// 可选的拾取物类型
+    #[serde(default)]
+    pub pickable: Option<ItemKind>,
+}
 
-    // 创建旋转版本
-    terrain_model_builder.create_model(
-        yellow_grass_corner_out.clone(),
-        vec![
-            SpawnableAsset::new("yellow_grass_corner_out_tl").with_tile_type(TileType::YellowGrass),
-        ],
-    );
-    terrain_model_builder.create_model(
-        yellow_grass_corner_out.rotated(ModelRotation::Rot90, Direction::ZForward),
-        vec![
-            SpawnableAsset::new("yellow_grass_corner_out_bl").with_tile_type(TileType::YellowGrass),
-        ],
-    );
-    terrain_model_builder.create_model(
-        yellow_grass_corner_out.rotated(ModelRotation::Rot180, Direction::ZForward),
-        vec![
-            SpawnableAsset::new("yellow_grass_corner_out_br").with_tile_type(TileType::YellowGrass),
-        ],
-    );
-    terrain_model_builder.create_model(
-        yellow_grass_corner_out.rotated(ModelRotation::Rot270, Direction::ZForward),
-        vec![
-            SpawnableAsset::new("yellow_grass_corner_out_tr").with_tile_type(TileType::YellowGrass),
-        ],
-    );
+impl AssetDefinition {
+    /// 转换为可生成资源
+    fn to_spawnable(&self) -> SpawnableAsset {
+        let (dx, dy, dz) = self.grid_offset;
+        let (ox, oy, oz) = self.offset;
+        let mut asset = SpawnableAsset::new(&self.sprite)
+            .with_grid_offset(GridDelta::new(dx, dy, dz))
+            .with_offset(Vec3::new(ox, oy, oz));
+        if let Some(tile_type) = self.tile_type {
+            asset = asset.with_tile_type(tile_type);
+        }
+        if let Some(kind) = self.pickable {
+            asset = asset.with_pickable(kind);
+        }
+        asset
+    }
+}
 
-    terrain_model_builder.create_model(
-        yellow_grass_corner_in.clone(),
-        vec![
-            SpawnableAsset::new("yellow_grass_corner_in_tl").with_tile_type(TileType::YellowGrass),
-        ],
-    );
-    terrain_model_builder.create_model(
-        yellow_grass_corner_in.rotated(ModelRotation::Rot90, Direction::ZForward),
-        vec![
-            SpawnableAsset::new("yellow_grass_corner_in_bl").with_tile_type(TileType::YellowGrass),
-        ],
-    );
-    terrain_model_builder.create_model(
-        yellow_grass_corner_in.rotated(ModelRotation::Rot180, Direction::ZForward),
-        vec![
-            SpawnableAsset::new("yellow_grass_corner_in_br").with_tile_type(TileType::YellowGrass),
-        ],
-    );
-    terrain_model_builder.create_model(
-        yellow_grass_corner_in.rotated(ModelRotation::Rot270, Direction::ZForward),
-        vec![
-            SpawnableAsset::new("yellow_grass_corner_in_tr").with_tile_type(TileType::YellowGrass),
-        ],
-    );
+/// 规则构建错误
+#[derive(Debug, Clone)]
+pub enum RulesError {
+    /// 引用了未声明的连接器
+    UnknownSocket { context: String, socket: String },
+    /// 引用了不存在的模板
+    UnknownTemplate { model: String, template: String },
+    /// 放置了不存在的结构
+    UnknownStructure { structure: String },
+    /// 结构定义无效
+    InvalidStructure { structure: String, reason: String },
+}
 
-    terrain_model_builder.create_model(
-        yellow_grass_side.clone(),
-        vec![SpawnableAsset::new("yellow_grass_side_t").with_tile_type(TileType::YellowGrass)],
-    );
-    terrain_model_builder.create_model(
-        yellow_grass_side.rotated(ModelRotation::Rot90, Direction::ZForward),
-        vec![SpawnableAsset::new("yellow_grass_side_l").with_tile_type(TileType::YellowGrass)],
-    );
-    terrain_model_builder.create_model(
-        yellow_grass_side.rotated(ModelRotation::Rot180, Direction::ZForward),
-        vec![SpawnableAsset::new("yellow_grass_side_b").with_tile_type(TileType::YellowGrass)],
-    );
-    terrain_model_builder.create_model(
-        yellow_grass_side.rotated(ModelRotation::Rot270, Direction::ZForward),
-        vec![SpawnableAsset::new("yellow_grass_side_r").with_tile_type(TileType::YellowGrass)],
-    );
+impl fmt::Display for RulesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RulesError::UnknownSocket { context, socket } => {
+                write!(f, "unknown socket '{}' in {}", socket, context)
+            }
+            RulesError::UnknownTemplate { model, template } => {
+                write!(f, "unknown template '{}' in model '{}'", template, model)
+            }
+            RulesError::UnknownStructure { structure } => {
+                write!(f, "unknown structure '{}' in placements", structure)
+            }
+            RulesError::InvalidStructure { structure, reason } => {
+                write!(f, "invalid structure '{}': {}", structure, reason)
+            }
+        }
+    }
+}
 
-    // 添加连接规则
-    socket_collection
-        .add_rotated_connection(
-            terrain_sockets.grass.layer_up,
-            vec![terrain_sockets.yellow_grass.layer_down],
-        )
-        .add_rotated_connection(
-            terrain_sockets.yellow_grass.yellow_grass_fill_down,
-            vec![terrain_sockets.grass.grass_fill_up],
-        );
+/// 构建完成的世界规则
+pub struct BuiltWorld {
+    /// 每个模型对应的可生成资源列表
+    pub assets: Vec<Vec<SpawnableAsset>>,
+    /// 每个模型的名称（与模型索引一一对应）
+    pub model_names: Vec<String>,
+    /// 模型集合
+    pub models: ModelCollection<Cartesian3D>,
+    /// 连接器集合
+    pub socket_collection: SocketCollection,
 }
 
-/// 构建水层
-///
-/// 创建水层的模型和连接规则
-pub fn build_water_layer(
-    terrain_model_builder: &mut TerrainModelBuilder,
+/// 将名称列表解析为连接器列表
+fn resolve_sockets(
     terrain_sockets: &TerrainSockets,
-    socket_collection: &mut SocketCollection,
-) {
-    // 空模型 - 代表没有水的陆地区域
-    terrain_model_builder.create_model(
-        SocketsCartesian3D::Multiple {
-            x_pos: vec![terrain_sockets.void],
-            x_neg: vec![terrain_sockets.void],
-            z_pos: vec![
-                terrain_sockets.water.layer_up,
-                terrain_sockets.water.ground_up,
-            ],
-            z_neg: vec![terrain_sockets.water.layer_down],
-            y_pos: vec![terrain_sockets.void],
-            y_neg: vec![terrain_sockets.void],
-        },
-        Vec::new(),
-    );
-
-    // 主水瓦片
-    const WATER_WEIGHT: f32 = 0.01;
-    terrain_model_builder
-        .create_model(
-            SocketsCartesian3D::Simple {
-                x_pos: terrain_sockets.water.material,
-                x_neg: terrain_sockets.water.material,
-                z_pos: terrain_sockets.water.layer_up,
-                z_neg: terrain_sockets.water.layer_down,
-                y_pos: terrain_sockets.water.material,
-                y_neg: terrain_sockets.water.material,
-            },
-            vec![SpawnableAsset::new("water").with_tile_type(TileType::Water)],
-        )
-        .with_weight(10. * WATER_WEIGHT);
+    names: &[String],
+    context: &str,
+) -> Result<Vec<Socket>, RulesError> {
+    names
+        .iter()
+        .map(|name| {
+            terrain_sockets
+                .get(name)
+                .ok_or_else(|| RulesError::UnknownSocket {
+                    context: context.to_string(),
+                    socket: name.clone(),
+                })
+        })
+        .collect()
+}
 
-    // 外角模板
-    let water_corner_out = SocketsCartesian3D::Simple {
-        x_pos: terrain_sockets.water.void_and_water,
-        x_neg: terrain_sockets.void,
-        z_pos: terrain_sockets.water.layer_up,
-        z_neg: terrain_sockets.water.layer_down,
-        y_pos: terrain_sockets.void,
-        y_neg: terrain_sockets.water.water_and_void,
+impl SocketsDefinition {
+    /// 转换为 3D 笛卡尔连接器
+    fn to_cartesian(
+        &self,
+        terrain_sockets: &TerrainSockets,
+        context: &str,
+    ) -> Result<SocketsCartesian3D, RulesError> {
+        Ok(SocketsCartesian3D::Multiple {
+            x_pos: resolve_sockets(terrain_sockets, &self.x_pos, context)?,
+            x_neg: resolve_sockets(terrain_sockets, &self.x_neg, context)?,
+            z_pos: resolve_sockets(terrain_sockets, &self.z_pos, context)?,
+            z_neg: resolve_sockets(terrain_sockets, &self.z_neg, context)?,
+            y_pos: resolve_sockets(terrain_sockets, &self.y_pos, context)?,
+            y_neg: resolve_sockets(terrain_sockets, &self.y_neg, context)?,
+        })
     }
-    .to_template()
-    .with_weight(WATER_WEIGHT);
+}
 
-    // 内角模板
-    let water_corner_in = SocketsCartesian3D::Simple {
-        x_pos: terrain_sockets.water.water_and_void,
-        x_neg: terrain_sockets.water.material,
-        z_pos: terrain_sockets.water.layer_up,
-        z_neg: terrain_sockets.water.layer_down,
-        y_pos: terrain_sockets.water.material,
-        y_neg: terrain_sockets.water.void_and_water,
+/// 根据模型定义创建模型模板
+///
+/// 依次应用模板连接器、旋转和权重
+fn build_template(
+    rules: &TerrainRules,
+    terrain_sockets: &TerrainSockets,
+    model: &ModelDefinition,
+    weight_multiplier: f32,
+) -> Result<ModelTemplate<Cartesian3D>, RulesError> {
+    let context = format!("model '{}'", model.name);
+    let (sockets, template_weight) = match &model.sockets {
+        ModelSockets::Template(name) => {
+            let template =
+                rules
+                    .templates
+                    .get(name)
+                    .ok_or_else(|| RulesError::UnknownTemplate {
+                        model: model.name.clone(),
+                        template: name.clone(),
+                    })?;
+            (&template.sockets, template.weight)
+        }
+        ModelSockets::Sockets(sockets) => (sockets, None),
+    };
+
+    let mut template = sockets
+        .to_cartesian(terrain_sockets, &context)?
+        .to_template();
+    if model.rotation != Rotation::Rot0 {
+        template = template.rotated(model.rotation.into(), Direction::ZForward);
     }
-    .to_template()
-    .with_weight(WATER_WEIGHT);
-
-    // 边缘模板
-    let water_side = SocketsCartesian3D::Simple {
-        x_pos: terrain_sockets.water.void_and_water,
-        x_neg: terrain_sockets.water.water_and_void,
-        z_pos: terrain_sockets.water.layer_up,
-        z_neg: terrain_sockets.water.layer_down,
-        y_pos: terrain_sockets.void,
-        y_neg: terrain_sockets.water.material,
+    let weight = model.weight.or(template_weight);
+    if let Some(weight) = weight {
+        template = template.with_weight(weight * weight_multiplier);
+    } else if weight_multiplier != 1.0 {
+        // 未设置权重的模型使用默认权重 1.0
+        template = template.with_weight(weight_multiplier);
     }
-    .to_template()
-    .with_weight(WATER_WEIGHT);
-
-    // 创建外角的旋转版本
-    terrain_model_builder.create_model(
-        water_corner_out.clone(),
-        vec![SpawnableAsset::new("water_corner_out_tl").with_tile_type(TileType::Water)],
-    );
-    terrain_model_builder.create_model(
-        water_corner_out.rotated(ModelRotation::Rot90, Direction::ZForward),
-        vec![SpawnableAsset::new("water_corner_out_bl").with_tile_type(TileType::Water)],
-    );
-    terrain_model_builder.create_model(
-        water_corner_out.rotated(ModelRotation::Rot180, Direction::ZForward),
-        vec![SpawnableAsset::new("water_corner_out_br").with_tile_type(TileType::Water)],
-    );
-    terrain_model_builder.create_model(
-        water_corner_out.rotated(ModelRotation::Rot270, Direction::ZForward),
-        vec![SpawnableAsset::new("water_corner_out_tr").with_tile_type(TileType::Water)],
-    );
-
-    // 创建内角的旋转版本
-    terrain_model_builder.create_model(
-        water_corner_in.clone(),
-        vec![SpawnableAsset::new("water_corner_in_tl").with_tile_type(TileType::Water)],
-    );
-    terrain_model_builder.create_model(
-        water_corner_in.rotated(ModelRotation::Rot90, Direction::ZForward),
-        vec![SpawnableAsset::new("water_corner_in_bl").with_tile_type(TileType::Water)],
-    );
-    terrain_model_builder.create_model(
-        water_corner_in.rotated(ModelRotation::Rot180, Direction::ZForward),
-        vec![SpawnableAsset::new("water_corner_in_br").with_tile_type(TileType::Water)],
-    );
-    terrain_model_builder.create_model(
-        water_corner_in.rotated(ModelRotation::Rot270, Direction::ZForward),
-        vec![SpawnableAsset::new("water_corner_in_tr").with_tile_type(TileType::Water)],
-    );
-
-    // 创建边缘的旋转版本
-    terrain_model_builder.create_model(
-        water_side.clone(),
-        vec![SpawnableAsset::new("water_side_t").with_tile_type(TileType::Water)],
-    );
-    terrain_model_builder.create_model(
-        water_side.rotated(ModelRotation::Rot90, Direction::ZForward),
-        vec![SpawnableAsset::new("water_side_l").with_tile_type(TileType::Water)],
-    );
-    terrain_model_builder.create_model(
-        water_side.rotated(ModelRotation::Rot180, Direction::ZForward),
-        vec![SpawnableAsset::new("water_side_b").with_tile_type(TileType::Water)],
-    );
-    terrain_model_builder.create_model(
-        water_side.rotated(ModelRotation::Rot270, Direction::ZForward),
-        vec![SpawnableAsset::new("water_side_r").with_tile_type(TileType::Water)],
-    );
-
-    // 添加连接规则
-    socket_collection.add_connections(vec![
-        (
-            terrain_sockets.water.material,
-            vec![terrain_sockets.water.material],
-        ),
-        (
-            terrain_sockets.water.water_and_void,
-            vec![terrain_sockets.water.void_and_water],
-        ),
-    ]);
-
-    // 将水层连接到黄色草地层
-    socket_collection.add_rotated_connection(
-        terrain_sockets.yellow_grass.layer_up,
-        vec![terrain_sockets.water.layer_down],
-    );
+    Ok(template)
 }
 
-/// 构建道具层
+/// 构建单个地形层
 ///
-/// 创建树木、岩石等装饰物的模型和连接规则
-fn build_props_layer(
+/// 创建该层的模型并添加连接规则
+fn build_layer(
+    rules: &TerrainRules,
+    layer: &LayerDefinition,
     terrain_model_builder: &mut TerrainModelBuilder,
     terrain_sockets: &TerrainSockets,
     socket_collection: &mut SocketCollection,
-) {
-    terrain_model_builder.create_model(
-        SocketsCartesian3D::Multiple {
-            x_pos: vec![terrain_sockets.void],
-            x_neg: vec![terrain_sockets.void],
-            z_pos: vec![terrain_sockets.props.layer_up],
-            z_neg: vec![terrain_sockets.props.layer_down],
-            y_pos: vec![terrain_sockets.void],
-            y_neg: vec![terrain_sockets.void],
-        },
-        Vec::new(),
-    );
-
-    // 不同类型道具的权重常量
-    const PROPS_WEIGHT: f32 = 0.025;
-    const ROCKS_WEIGHT: f32 = 0.008;
-    const PLANTS_WEIGHT: f32 = 0.025;
-    const STUMPS_WEIGHT: f32 = 0.012;
-
-    // 基础道具模板
-    let prop = SocketsCartesian3D::Simple {
-        x_pos: terrain_sockets.void,
-        x_neg: terrain_sockets.void,
-        z_pos: terrain_sockets.props.layer_up,
-        z_neg: terrain_sockets.props.props_down,
-        y_pos: terrain_sockets.void,
-        y_neg: terrain_sockets.void,
+    weight_multiplier: &dyn Fn(&str) -> f32,
+) -> Result<(), RulesError> {
+    // 创建模型
+    for model in &layer.models {
+        let template = build_template(
+            rules,
+            terrain_sockets,
+            model,
+            weight_multiplier(&model.name),
+        )?;
+        let assets = model
+            .assets
+            .iter()
+            .map(AssetDefinition::to_spawnable)
+            .collect();
+        terrain_model_builder.create_model(&model.name, template, assets);
     }
-    .to_template()
-    .with_weight(PROPS_WEIGHT);
-
-    // 创建不同类型的道具，使用不同的权重
-    let plant_prop = prop.clone().with_weight(PLANTS_WEIGHT);
-    let rock_prop = prop.clone().with_weight(ROCKS_WEIGHT);
-    let stump_prop = prop.clone().with_weight(STUMPS_WEIGHT);
-
-    // 小树（2 片图块）
-    terrain_model_builder.create_model(
-        plant_prop.clone(),
-        vec![
-            SpawnableAsset::new("small_tree_bottom").with_tile_type(TileType::Tree),
-            SpawnableAsset::new("small_tree_top").with_grid_offset(GridDelta::new(0, 1, 0)),
-        ],
-    );
-
-    // 大树-1（2x2 图块）
-    terrain_model_builder
-        .create_model(
-            SocketsCartesian3D::Simple {
-                x_pos: terrain_sockets.props.big_tree_1_base,
-                x_neg: terrain_sockets.void,
-                z_pos: terrain_sockets.props.layer_up,
-                z_neg: terrain_sockets.props.props_down,
-                y_pos: terrain_sockets.void,
-                y_neg: terrain_sockets.void,
-            },
-            vec![
-                SpawnableAsset::new("big_tree_1_bl").with_tile_type(TileType::Tree),
-                SpawnableAsset::new("big_tree_1_tl").with_grid_offset(GridDelta::new(0, 1, 0)),
-            ],
-        )
-        .with_weight(PROPS_WEIGHT);
-
-    terrain_model_builder
-        .create_model(
-            SocketsCartesian3D::Simple {
-                x_pos: terrain_sockets.void,
-                x_neg: terrain_sockets.props.big_tree_1_base,
-                z_pos: terrain_sockets.props.layer_up,
-                z_neg: terrain_sockets.props.props_down,
-                y_pos: terrain_sockets.void,
-                y_neg: terrain_sockets.void,
-            },
-            vec![
-                SpawnableAsset::new("big_tree_1_br").with_tile_type(TileType::Tree),
-                SpawnableAsset::new("big_tree_1_tr").with_grid_offset(GridDelta::new(0, 1, 0)),
-            ],
-        )
-        .with_weight(PROPS_WEIGHT);
-
-    // 大树-2（2x2 图块）
-    terrain_model_builder
-        .create_model(
-            SocketsCartesian3D::Simple {
-                x_pos: terrain_sockets.props.big_tree_2_base,
-                x_neg: terrain_sockets.void,
-                z_pos: terrain_sockets.props.layer_up,
-                z_neg: terrain_sockets.props.props_down,
-                y_pos: terrain_sockets.void,
-                y_neg: terrain_sockets.void,
-            },
-            vec![
-                SpawnableAsset::new("big_tree_2_bl").with_tile_type(TileType::Tree),
-                SpawnableAsset::new("big_tree_2_tl").with_grid_offset(GridDelta::new(0, 1, 0)),
-            ],
-        )
-        .with_weight(PROPS_WEIGHT);
-    terrain_model_builder
-        .create_model(
-            SocketsCartesian3D::Simple {
-                x_pos: terrain_sockets.void,
-                x_neg: terrain_sockets.props.big_tree_2_base,
-                z_pos: terrain_sockets.props.layer_up,
-                z_neg: terrain_sockets.props.props_down,
-                y_pos: terrain_sockets.void,
-                y_neg: terrain_sockets.void,
-            },
-            vec![
-                SpawnableAsset::new("big_tree_2_br").with_tile_type(TileType::Tree),
-                SpawnableAsset::new("big_tree_2_tr").with_grid_offset(GridDelta::new(0, 1, 0)),
-            ],
-        )
-        .with_weight(PROPS_WEIGHT);
-
-    // 树桩
-    terrain_model_builder.create_model(
-        stump_prop.clone(),
-        vec![SpawnableAsset::new("tree_stump_1").with_tile_type(TileType::Tree)],
-    );
-    terrain_model_builder.create_model(
-        stump_prop.clone(),
-        vec![SpawnableAsset::new("tree_stump_2").with_tile_type(TileType::Tree)],
-    );
-    terrain_model_builder.create_model(
-        stump_prop.clone(),
-        vec![SpawnableAsset::new("tree_stump_3").with_tile_type(TileType::Tree)],
-    );
-
-    // 岩石
-    terrain_model_builder.create_model(
-        rock_prop.clone(),
-        vec![SpawnableAsset::new("rock_1").with_tile_type(TileType::Rock)],
-    );
-    terrain_model_builder.create_model(
-        rock_prop.clone(),
-        vec![SpawnableAsset::new("rock_2").with_tile_type(TileType::Rock)],
-    );
-    terrain_model_builder.create_model(
-        rock_prop.clone(),
-        vec![SpawnableAsset::new("rock_3").with_tile_type(TileType::Rock)],
-    );
-    terrain_model_builder.create_model(
-        rock_prop.clone(),
-        vec![SpawnableAsset::new("rock_4").with_tile_type(TileType::Rock)],
-    );
-
-    // 植物
-    terrain_model_builder.create_model(
-        plant_prop.clone(),
-        vec![
-            SpawnableAsset::new("plant_1")
-                .with_tile_type(TileType::Grass)
-                .with_pickable(ItemKind::Plant1),
-        ],
-    );
-    terrain_model_builder.create_model(
-        plant_prop.clone(),
-        vec![
-            SpawnableAsset::new("plant_2")
-                .with_tile_type(TileType::Grass)
-                .with_pickable(ItemKind::Plant2),
-        ],
-    );
-    terrain_model_builder.create_model(
-        plant_prop.clone(),
-        vec![
-            SpawnableAsset::new("plant_3")
-                .with_tile_type(TileType::Grass)
-                .with_pickable(ItemKind::Plant3),
-        ],
-    );
-    terrain_model_builder.create_model(
-        plant_prop.clone(),
-        vec![
-            SpawnableAsset::new("plant_4")
-                .with_tile_type(TileType::Grass)
-                .with_pickable(ItemKind::Plant4),
-        ],
-    );
 
     // 添加连接规则
-    socket_collection.add_connections(vec![
-        (
-            terrain_sockets.props.big_tree_1_base,
-            vec![terrain_sockets.props.big_tree_1_base],
-        ),
-        (
-            terrain_sockets.props.big_tree_2_base,
-            vec![terrain_sockets.props.big_tree_2_base],
-        ),
-    ]);
+    for connection in &layer.connections {
+        let context = format!("layer '{}' connections", layer.name);
+        let socket = resolve_sockets(
+            terrain_sockets,
+            std::slice::from_ref(&connection.socket),
+            &context,
+        )?[0];
+        let connects_to = resolve_sockets(terrain_sockets, &connection.connects_to, &context)?;
+        if connection.rotated {
+            socket_collection.add_rotated_connection(socket, connects_to);
+        } else {
+            socket_collection.add_connections(vec![(socket, connects_to)]);
+        }
+    }
 
-    // 将道具层连接到水层
-    socket_collection
-        .add_rotated_connection(
-            terrain_sockets.water.layer_up,
-            vec![terrain_sockets.props.layer_down],
-        )
-        .add_rotated_connection(
-            terrain_sockets.props.props_down,
-            vec![terrain_sockets.water.ground_up],
-        );
+    Ok(())
 }
 
 /// 构建世界
 ///
-/// 创建所有地形层的模型和规则，返回资源、模型和连接器集合
-pub fn build_world() -> (
-    Vec<Vec<SpawnableAsset>>,
-    ModelCollection<Cartesian3D>,
-    SocketCollection,
-) {
+/// 从地形规则资源创建所有地形层的模型和规则，返回资源、模型和连接器集合
+pub fn build_world(rules: &TerrainRules) -> Result<BuiltWorld, RulesError> {
+    build_world_weighted(rules, &|_| 1.0)
+}
+
+/// 使用调整后的模型权重构建世界
+///
+/// # 参数
+/// - `rules`: 地形规则
+/// - `weight_multiplier`: 根据模型名称返回权重倍率（例如生物群系混合后的倍率）
+pub fn build_world_weighted(
+    rules: &TerrainRules,
+    weight_multiplier: &dyn Fn(&str) -> f32,
+) -> Result<BuiltWorld, RulesError> {
     let mut socket_collection = SocketCollection::new();
     // 创建地形连接器
-    let terrain_sockets = create_sockets(&mut socket_collection);
+    let terrain_sockets = create_sockets(&mut socket_collection, &rules.sockets);
     let mut terrain_model_builder = TerrainModelBuilder::new();
 
-    // 构建泥土层
-    build_dirt_layer(
-        &mut terrain_model_builder,
-        &terrain_sockets,
-        &mut socket_collection,
-    );
-
-    // 构建草地层
-    build_grass_layer(
-        &mut terrain_model_builder,
-        &terrain_sockets,
-        &mut socket_collection,
-    );
-
-    // 构建黄色草地层
-    build_yellow_grass_layer(
-        &mut terrain_model_builder,
-        &terrain_sockets,
-        &mut socket_collection,
-    );
-
-    // 构建水层
-    build_water_layer(
-        &mut terrain_model_builder,
-        &terrain_sockets,
-        &mut socket_collection,
-    );
-
-    // 构建道具层
-    build_props_layer(
-        &mut terrain_model_builder,
-        &terrain_sockets,
-        &mut socket_collection,
-    );
+    // 按顺序构建每个地形层
+    for layer in &rules.layers {
+        build_layer(
+            rules,
+            layer,
+            &mut terrain_model_builder,
+            &terrain_sockets,
+            &mut socket_collection,
+            weight_multiplier,
+        )?;
+    }
 
     // 将构建器拆分为组件
-    let (assets, models) = terrain_model_builder.into_parts();
-
-    (assets, models, socket_collection)
+    let (assets, model_names, models) = terrain_model_builder.into_parts();
+
+    Ok(BuiltWorld {
+        assets,
+        model_names,
+        models,
+        socket_collection,
+    })
 }
@@ -0,0 +1,654 @@
+use crate::collision::{CollisionMap, TileOverrides, TileType};
+use crate::config::map::{GRID_X, GRID_Y, TILE_SIZE};
+use crate::inventory::{CollectedPickups, ItemKind, Pickable};
+use crate::map::chunk::{LoadedChunks, world_origin, world_to_cell};
+use crate::map::generate::{ChunkData, GRID_Z, WorldGenerator, chunk_grid};
+use crate::map::regenerate::RegenerateMap;
+use crate::map::seed::WorldSeed;
+use bevy::prelude::*;
+use bevy_procedural_tilemaps::prelude::ModelRotation;
+use bevy_procedural_tilemaps::proc_gen::generator::model::ModelInstance;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 地图文件格式版本，格式发生不兼容的变化时递增
+pub const MAP_FILE_VERSION: u32 = 2;
+/// 调试按键使用的地图文件路径（相对于工作目录）
+pub const SAVE_FILE: &str = "saves/map.ron";
+
+/// 保存的地图
+///
+/// 记录已加载区块中每个节点选中的模型、最终的瓦片类型、运行时改变过的瓦片、剩余的拾取物
+/// 和已拾取的拾取物。
+/// 加载时直接用这些模型生成瓦片实体，不再运行 WFC
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedMap {
+    /// 文件格式版本
+    pub version: u32,
+    /// 世界种子（未保存的区块仍用它生成）
+    pub seed: u64,
+    /// 模型表，区块节点按索引引用
+    pub models: Vec<SavedModel>,
+    /// 已加载的区块
+    pub chunks: Vec<SavedChunk>,
+    /// 碰撞地图的瓦片类型
+    pub tiles: SavedTiles,
+    /// 运行时改变过的瓦片（[`TileOverrides`]）
+    pub overrides: Vec<SavedOverride>,
+    /// 剩余的拾取物
+    pub pickups: Vec<SavedPickup>,
+    /// 已拾取的拾取物所在的世界网格坐标（[`CollectedPickups`]），包括已卸载的区块
+    pub collected: Vec<(i32, i32)>,
+}
+
+/// 保存的模型
+///
+/// 模型表中的一项：规则中的模型名称和 WFC 选择的旋转
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SavedModel {
+    /// 模型名称
+    pub name: String,
+    /// 旋转角度（0、90、180 或 270）
+    pub rotation: u32,
+}
+
+/// 保存的区块
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedChunk {
+    /// 区块坐标
+    pub coord: (i32, i32),
+    /// 每个节点的模型（模型表中的索引），按区块网格的节点索引排列
+    pub nodes: Vec<u32>,
+}
+
+/// 保存的瓦片类型
+///
+/// 每行一个字符串，字符为 [`TileType::symbol`]，第一行是最上面一行
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SavedTiles {
+    /// 左下角的世界网格坐标（出生区块左下角为原点）
+    pub origin: (i32, i32),
+    /// 瓦片行
+    pub rows: Vec<String>,
+}
+
+/// 保存的瓦片覆盖
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct SavedOverride {
+    /// 世界网格坐标（出生区块左下角为原点）
+    pub cell: (i32, i32),
+    /// 覆盖的瓦片类型（[`TileType::symbol`]）
+    pub tile: char,
+}
+
+/// 保存的拾取物
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct SavedPickup {
+    /// 所在的世界网格坐标（出生区块左下角为原点）
+    pub cell: (i32, i32),
+    /// 拾取物类型
+    pub kind: ItemKind,
+}
+
+/// 地图文件错误
+#[derive(Debug)]
+pub enum SaveError {
+    /// 读写文件失败
+    Io {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+    /// 序列化或解析失败
+    Format { path: PathBuf, reason: String },
+    /// 文件版本不受支持
+    Version { found: u32 },
+    /// 文件引用了当前规则中不存在的模型
+    UnknownModel { name: String },
+    /// 模型的旋转角度无效
+    InvalidRotation { name: String, rotation: u32 },
+    /// 区块节点数量或模型索引无效
+    InvalidChunk { coord: (i32, i32), reason: String },
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SaveError::Io { path, error } => write!(f, "{}: {}", path.display(), error),
+            SaveError::Format { path, reason } => write!(f, "{}: {}", path.display(), reason),
+            SaveError::Version { found } => write!(
+                f,
+                "unsupported map file version {} (expected {})",
+                found, MAP_FILE_VERSION
+            ),
+            SaveError::UnknownModel { name } => {
+                write!(f, "model '{}' does not exist in the terrain rules", name)
+            }
+            SaveError::InvalidRotation { name, rotation } => {
+                write!(f, "model '{}' has invalid rotation {}", name, rotation)
+            }
+            SaveError::InvalidChunk { coord, reason } => {
+                write!(f, "chunk ({}, {}): {}", coord.0, coord.1, reason)
+            }
+        }
+    }
+}
+
+/// 地图文件格式，由扩展名决定（`.json` 为 JSON，其余为 RON）
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum MapFormat {
+    Ron,
+    Json,
+}
+
+impl MapFormat {
+    fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => MapFormat::Json,
+            _ => MapFormat::Ron,
+        }
+    }
+}
+
+impl SavedMap {
+    /// 读取地图文件并检查版本
+    pub fn read(path: &Path) -> Result<Self, SaveError> {
+        let contents = fs::read_to_string(path).map_err(|error| SaveError::Io {
+            path: path.to_path_buf(),
+            error,
+        })?;
+        let map: SavedMap = match MapFormat::from_path(path) {
+            MapFormat::Ron => ron::from_str(&contents).map_err(|err| err.to_string()),
+            MapFormat::Json => serde_json::from_str(&contents).map_err(|err| err.to_string()),
+        }
+        .map_err(|reason| SaveError::Format {
+            path: path.to_path_buf(),
+            reason,
+        })?;
+
+        if map.version != MAP_FILE_VERSION {
+            return Err(SaveError::Version { found: map.version });
+        }
+        Ok(map)
+    }
+
+    /// 写入地图文件，必要时创建所在目录
+    pub fn write(&self, path: &Path) -> Result<(), SaveError> {
+        let io_error = |error| SaveError::Io {
+            path: path.to_path_buf(),
+            error,
+        };
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).map_err(io_error)?;
+        }
+
+        let contents = match MapFormat::from_path(path) {
+            MapFormat::Ron => ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+                .map_err(|err| err.to_string()),
+            MapFormat::Json => serde_json::to_string_pretty(self).map_err(|err| err.to_string()),
+        }
+        .map_err(|reason| SaveError::Format {
+            path: path.to_path_buf(),
+            reason,
+        })?;
+        fs::write(path, contents).map_err(io_error)
+    }
+}
+
+/// 模型表
+///
+/// 保存地图时为每个不同的模型实例（模型和旋转）分配模型表中的索引
+#[derive(Debug, Default)]
+pub struct ModelTable {
+    /// 已分配的模型
+    models: Vec<SavedModel>,
+    /// 模型实例到模型表索引的映射
+    indices: HashMap<(usize, ModelRotation), u32>,
+}
+
+impl ModelTable {
+    /// 获取模型实例在模型表中的索引，第一次出现时加入模型表
+    ///
+    /// # 参数
+    /// - `instance`: 模型实例
+    /// - `model_names`: 当前规则中每个模型的名称（按模型索引）
+    pub fn index(&mut self, instance: &ModelInstance, model_names: &[String]) -> u32 {
+        let key = (instance.model_index, instance.rotation);
+        if let Some(&index) = self.indices.get(&key) {
+            return index;
+        }
+        let index = self.models.len() as u32;
+        self.models.push(SavedModel {
+            name: model_names
+                .get(instance.model_index)
+                .cloned()
+                .unwrap_or_default(),
+            rotation: instance.rotation.value(),
+        });
+        self.indices.insert(key, index);
+        index
+    }
+
+    /// 取出模型表
+    pub fn into_models(self) -> Vec<SavedModel> {
+        self.models
+    }
+}
+
+/// 将旋转角度转换为模型旋转
+fn rotation_from_degrees(degrees: u32) -> Option<ModelRotation> {
+    match degrees {
+        0 => Some(ModelRotation::Rot0),
+        90 => Some(ModelRotation::Rot90),
+        180 => Some(ModelRotation::Rot180),
+        270 => Some(ModelRotation::Rot270),
+        _ => None,
+    }
+}
+
+impl SavedTiles {
+    /// 从碰撞地图中提取瓦片类型
+    pub fn from_collision_map(map: &CollisionMap) -> Self {
+        let origin = ((map.origin() - world_origin()) / TILE_SIZE)
+            .round()
+            .as_ivec2();
+        let rows = (0..map.height())
+            .rev()
+            .map(|y| {
+                (0..map.width())
+                    .map(|x| map.get_tile(x, y).unwrap_or_default().symbol())
+                    .collect()
+            })
+            .collect();
+        Self {
+            origin: (origin.x, origin.y),
+            rows,
+        }
+    }
+
+    /// 遍历所有瓦片的世界网格坐标和类型（无法识别的字符会被跳过）
+    pub fn iter(&self) -> impl Iterator<Item = (IVec2, TileType)> + '_ {
+        let origin = IVec2::new(self.origin.0, self.origin.1);
+        let height = self.rows.len() as i32;
+        self.rows.iter().enumerate().flat_map(move |(row, tiles)| {
+            let y = height - 1 - row as i32;
+            tiles.chars().enumerate().filter_map(move |(x, symbol)| {
+                TileType::from_symbol(symbol).map(|tile| (origin + IVec2::new(x as i32, y), tile))
+            })
+        })
+    }
+}
+
+/// 从存档加载的区块资源
+///
+/// 加载地图文件时插入，世界种子与存档相同时，这些区块直接使用保存的模型而不运行 WFC
+#[derive(Resource, Debug, Clone)]
+pub struct SavedChunks {
+    /// 存档的世界种子
+    seed: u64,
+    /// 区块坐标到每个节点模型实例的映射
+    chunks: HashMap<IVec2, Vec<ModelInstance>>,
+    /// 剩余拾取物所在的世界网格坐标
+    pickups: HashSet<IVec2>,
+    /// 运行时改变过的瓦片
+    overrides: HashMap<IVec2, TileType>,
+    /// 已拾取的拾取物所在的世界网格坐标
+    collected: Vec<IVec2>,
+    /// 等待与重建后的碰撞地图比对的瓦片类型
+    expected_tiles: Option<SavedTiles>,
+}
+
+impl SavedChunks {
+    /// 将存档中的模型名称解析为当前规则的模型索引
+    ///
+    /// # 参数
+    /// - `map`: 地图存档
+    /// - `model_names`: 当前规则中每个模型的名称（按模型索引）
+    ///
+    /// # 返回
+    /// 解析好的区块，模型不存在或区块数据无效时返回错误
+    pub fn from_saved(map: SavedMap, model_names: &[String]) -> Result<Self, SaveError> {
+        let indices: HashMap<&str, usize> = model_names
+            .iter()
+            .enumerate()
+            .map(|(index, name)| (name.as_str(), index))
+            .collect();
+        let models = map
+            .models
+            .iter()
+            .map(|model| {
+                let model_index = indices.get(model.name.as_str()).copied().ok_or_else(|| {
+                    SaveError::UnknownModel {
+                        name: model.name.clone(),
+                    }
+                })?;
+                let rotation = rotation_from_degrees(model.rotation).ok_or_else(|| {
+                    SaveError::InvalidRotation {
+                        name: model.name.clone(),
+                        rotation: model.rotation,
+                    }
+                })?;
+                Ok(ModelInstance {
+                    model_index,
+                    rotation,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let node_count = (GRID_X * GRID_Y * GRID_Z) as usize;
+        let mut chunks = HashMap::new();
+        for chunk in &map.chunks {
+            if chunk.nodes.len() != node_count {
+                return Err(SaveError::InvalidChunk {
+                    coord: chunk.coord,
+                    reason: format!("expected {} nodes, found {}", node_count, chunk.nodes.len()),
+                });
+            }
+            let nodes = chunk
+                .nodes
+                .iter()
+                .map(|node| {
+                    models
+                        .get(*node as usize)
+                        .copied()
+                        .ok_or_else(|| SaveError::InvalidChunk {
+                            coord: chunk.coord,
+                            reason: format!("model index {} is out of range", node),
+                        })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            chunks.insert(IVec2::new(chunk.coord.0, chunk.coord.1), nodes);
+        }
+
+        Ok(Self {
+            seed: map.seed,
+            chunks,
+            pickups: map
+                .pickups
+                .iter()
+                .map(|pickup| IVec2::new(pickup.cell.0, pickup.cell.1))
+                .collect(),
+            // 无法识别的字符与瓦片类型相同，直接跳过
+            overrides: map
+                .overrides
+                .iter()
+                .filter_map(|saved| {
+                    TileType::from_symbol(saved.tile)
+                        .map(|tile| (IVec2::new(saved.cell.0, saved.cell.1), tile))
+                })
+                .collect(),
+            collected: map
+                .collected
+                .iter()
+                .map(|cell| IVec2::new(cell.0, cell.1))
+                .collect(),
+            expected_tiles: Some(map.tiles),
+        })
+    }
+
+    /// 获取保存的区块数据
+    ///
+    /// # 参数
+    /// - `world_seed`: 当前世界种子，与存档不同时（例如之后又重新生成了地图）不使用存档
+    /// - `coord`: 区块坐标
+    pub fn chunk_data(&self, world_seed: u64, coord: IVec2) -> Option<ChunkData> {
+        if world_seed != self.seed {
+            return None;
+        }
+        let nodes = self.chunks.get(&coord)?;
+        Some(ChunkData::new(chunk_grid(), nodes.clone()))
+    }
+
+    /// 检查保存时该世界网格坐标上的拾取物是否还在
+    pub fn has_pickup(&self, cell: IVec2) -> bool {
+        self.pickups.contains(&cell)
+    }
+
+    /// 创建包含存档中瓦片覆盖的 [`TileOverrides`]
+    ///
+    /// # 参数
+    /// - `world_seed`: 当前世界种子，与存档不同时不使用存档，返回空的覆盖
+    pub fn tile_overrides(&self, world_seed: u64) -> TileOverrides {
+        let mut overrides = TileOverrides::default();
+        if world_seed == self.seed {
+            for (cell, tile) in &self.overrides {
+                overrides.insert(*cell, *tile);
+            }
+        }
+        overrides
+    }
+
+    /// 创建包含存档中已拾取拾取物的 [`CollectedPickups`]
+    ///
+    /// # 参数
+    /// - `world_seed`: 当前世界种子，与存档不同时不使用存档，返回空的记录
+    pub fn collected_pickups(&self, world_seed: u64) -> CollectedPickups {
+        let mut collected = CollectedPickups::default();
+        if world_seed == self.seed {
+            for cell in &self.collected {
+                collected.insert(*cell);
+            }
+        }
+        collected
+    }
+
+    /// 保存的区块数量
+    pub fn len(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// 没有保存任何区块时返回 true
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+}
+
+/// 保存地图消息
+///
+/// 将当前已加载的区块写入地图文件，扩展名为 `.json` 时使用 JSON，否则使用 RON
+#[derive(Message, Debug, Clone)]
+pub struct SaveMap {
+    /// 文件路径
+    pub path: PathBuf,
+}
+
+/// 加载地图消息
+///
+/// 读取地图文件并用其中的区块重建地图，流程与 [`RegenerateMap`] 相同
+#[derive(Message, Debug, Clone)]
+pub struct LoadMap {
+    /// 文件路径
+    pub path: PathBuf,
+}
+
+/// 按 F6 保存地图、F9 加载地图（调试用）
+pub fn save_load_on_key(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut saves: MessageWriter<SaveMap>,
+    mut loads: MessageWriter<LoadMap>,
+) {
+    if keyboard.just_pressed(KeyCode::F6) {
+        saves.write(SaveMap {
+            path: PathBuf::from(SAVE_FILE),
+        });
+    }
+    if keyboard.just_pressed(KeyCode::F9) {
+        loads.write(LoadMap {
+            path: PathBuf::from(SAVE_FILE),
+        });
+    }
+}
+
+/// 处理保存地图请求
+///
+/// # 参数
+/// - `requests`: 保存地图消息
+/// - `seed`: 世界种子
+/// - `chunks`: 已加载区块
+/// - `generator`: 世界生成器，提供模型名称
+/// - `collision_map`: 碰撞地图（尚未构建时保存空的瓦片类型）
+/// - `overrides`: 运行时改变过的瓦片
+/// - `collected`: 已拾取的拾取物
+/// - `pickups`: 剩余的拾取物
+#[allow(clippy::too_many_arguments)]
+pub fn save_map(
+    mut requests: MessageReader<SaveMap>,
+    seed: Res<WorldSeed>,
+    chunks: Res<LoadedChunks>,
+    generator: Option<Res<WorldGenerator>>,
+    collision_map: Option<Res<CollisionMap>>,
+    overrides: Res<TileOverrides>,
+    collected: Res<CollectedPickups>,
+    pickups: Query<(&GlobalTransform, &Pickable)>,
+) {
+    let Some(generator) = generator else {
+        requests.clear();
+        return;
+    };
+
+    for request in requests.read() {
+        let model_names = generator.model_names();
+        let mut models = ModelTable::default();
+        let mut saved_chunks: Vec<SavedChunk> = chunks
+            .coords()
+            .filter_map(|coord| {
+                let nodes = chunks.nodes(coord)?;
+                let nodes = nodes
+                    .iter()
+                    .map(|instance| models.index(instance, model_names))
+                    .collect();
+                Some(SavedChunk {
+                    coord: (coord.x, coord.y),
+                    nodes,
+                })
+            })
+            .collect();
+        saved_chunks.sort_by_key(|chunk| chunk.coord);
+
+        let mut saved_pickups: Vec<SavedPickup> = pickups
+            .iter()
+            .map(|(transform, pickable)| {
+                let cell = world_to_cell(transform.translation().truncate());
+                SavedPickup {
+                    cell: (cell.x, cell.y),
+                    kind: pickable.kind,
+                }
+            })
+            .collect();
+        saved_pickups.sort_by_key(|pickup| pickup.cell);
+
+        // 只保存游戏玩法改变的瓦片，开辟的瓦片在区块加载时重新计算
+        let mut saved_overrides: Vec<SavedOverride> = overrides
+            .changes()
+            .map(|(cell, tile_type)| SavedOverride {
+                cell: (cell.x, cell.y),
+                tile: tile_type.symbol(),
+            })
+            .collect();
+        saved_overrides.sort_by_key(|saved| saved.cell);
+
+        let mut saved_collected: Vec<(i32, i32)> =
+            collected.iter().map(|cell| (cell.x, cell.y)).collect();
+        saved_collected.sort();
+
+        let map = SavedMap {
+            version: MAP_FILE_VERSION,
+            seed: seed.value,
+            models: models.into_models(),
+            chunks: saved_chunks,
+            tiles: collision_map
+                .as_deref()
+                .map(SavedTiles::from_collision_map)
+                .unwrap_or_default(),
+            overrides: saved_overrides,
+            pickups: saved_pickups,
+            collected: saved_collected,
+        };
+        match map.write(&request.path) {
+            Ok(()) => info!(
+                "💾 Saved map to {} ({} chunks, {} changed tiles, {} pickups)",
+                request.path.display(),
+                map.chunks.len(),
+                map.overrides.len(),
+                map.pickups.len()
+            ),
+            Err(err) => warn!("Failed to save map: {}", err),
+        }
+    }
+}
+
+/// 处理加载地图请求
+///
+/// 解析地图文件后插入 [`SavedChunks`] 资源，再以存档的种子发送 [`RegenerateMap`]，
+/// 重新生成时存档中的区块直接使用保存的模型，运行时改变过的瓦片恢复为覆盖，碰撞地图由瓦片实体重建
+pub fn load_map(
+    mut commands: Commands,
+    mut requests: MessageReader<LoadMap>,
+    mut regenerate: MessageWriter<RegenerateMap>,
+    generator: Option<Res<WorldGenerator>>,
+) {
+    let Some(generator) = generator else {
+        requests.clear();
+        return;
+    };
+    // 同一帧有多个请求时只处理最后一个
+    let Some(request) = requests.read().last() else {
+        return;
+    };
+
+    let saved = SavedMap::read(&request.path)
+        .and_then(|map| SavedChunks::from_saved(map, generator.model_names()));
+    match saved {
+        Ok(saved) => {
+            info!(
+                "📂 Loading map from {} ({} chunks, seed {})",
+                request.path.display(),
+                saved.len(),
+                saved.seed
+            );
+            regenerate.write(RegenerateMap {
+                seed: Some(saved.seed),
+                keep_inventory: true,
+            });
+            commands.insert_resource(saved);
+        }
+        Err(err) => warn!("Failed to load map: {}", err),
+    }
+}
+
+/// 比对重建后的碰撞地图与存档中的瓦片类型
+///
+/// 规则在保存后被修改时，同样的模型可能得到不同的瓦片类型，此时记录警告。
+/// 只比对当前已加载的部分，每次加载存档只检查一次
+pub fn verify_loaded_map(mut saved: ResMut<SavedChunks>, collision_map: Res<CollisionMap>) {
+    let Some(expected) = saved.expected_tiles.take() else {
+        return;
+    };
+
+    let map_origin = ((collision_map.origin() - world_origin()) / TILE_SIZE)
+        .round()
+        .as_ivec2();
+    let mut checked = 0;
+    let mut mismatched = 0;
+    for (cell, tile) in expected.iter() {
+        let grid = cell - map_origin;
+        let Some(actual) = collision_map.get_tile(grid.x, grid.y) else {
+            continue;
+        };
+        checked += 1;
+        if actual != tile {
+            mismatched += 1;
+        }
+    }
+
+    if mismatched > 0 {
+        warn!(
+            "Loaded map differs from the saved tiles: {} of {} tiles changed (terrain rules modified since saving?)",
+            mismatched, checked
+        );
+    } else {
+        info!("Loaded map matches the saved tiles ({} checked)", checked);
+    }
+}
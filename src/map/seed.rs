@@ -0,0 +1,155 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::fs;
+
+/// 命令行种子参数（`--seed 42` 或 `--seed=42`）
+const SEED_ARG: &str = "--seed";
+/// 种子环境变量名
+const SEED_ENV_VAR: &str = "BEVY_GAME_SEED";
+/// 命令行重试预算参数（`--retry-budget 8` 或 `--retry-budget=8`）
+const RETRY_BUDGET_ARG: &str = "--retry-budget";
+/// 重试预算环境变量名
+const RETRY_BUDGET_ENV_VAR: &str = "BEVY_GAME_RETRY_BUDGET";
+/// 设置文件路径（相对于工作目录）
+const SETTINGS_FILE: &str = "settings.ron";
+
+/// 种子来源
+///
+/// 记录世界种子是从哪里得到的，便于在日志中复现问题
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SeedSource {
+    /// 命令行参数
+    CommandLine,
+    /// 环境变量
+    Environment,
+    /// 设置文件
+    SettingsFile,
+    /// 游戏中重新生成地图时指定
+    Regenerate,
+    /// 随机生成
+    Random,
+}
+
+/// 世界种子资源
+///
+/// 驱动地图生成器的随机数种子，相同的种子总能生成相同的地图
+#[derive(Resource, Debug, Copy, Clone, Eq, PartialEq)]
+pub struct WorldSeed {
+    /// 种子值
+    pub value: u64,
+    /// 种子来源
+    pub source: SeedSource,
+}
+
+/// 设置文件内容
+///
+/// 包含可选的世界种子和区块生成重试预算
+#[derive(Debug, Default, Deserialize)]
+struct GameSettings {
+    #[serde(default)]
+    seed: Option<u64>,
+    #[serde(default)]
+    retry_budget: Option<u32>,
+}
+
+impl WorldSeed {
+    /// 使用指定种子创建世界种子
+    pub fn new(value: u64, source: SeedSource) -> Self {
+        Self { value, source }
+    }
+
+    /// 按优先级解析世界种子
+    ///
+    /// 优先级：命令行参数 > 环境变量 > 设置文件 > 随机种子
+    pub fn resolve() -> Self {
+        if let Some(value) = seed_from_args() {
+            return Self::new(value, SeedSource::CommandLine);
+        }
+        if let Some(value) = seed_from_env() {
+            return Self::new(value, SeedSource::Environment);
+        }
+        if let Some(value) = seed_from_settings() {
+            return Self::new(value, SeedSource::SettingsFile);
+        }
+        Self::new(rand::random(), SeedSource::Random)
+    }
+}
+
+/// 按优先级读取区块生成重试预算
+///
+/// 优先级：命令行参数 > 环境变量 > 设置文件，都未指定时返回 `None`
+pub fn retry_budget_setting() -> Option<u32> {
+    arg_value(RETRY_BUDGET_ARG)
+        .and_then(|value| parse_number(&value, "retry budget", "command line"))
+        .or_else(|| {
+            let value = std::env::var(RETRY_BUDGET_ENV_VAR).ok()?;
+            parse_number(&value, "retry budget", RETRY_BUDGET_ENV_VAR)
+        })
+        .or_else(|| read_settings()?.retry_budget)
+}
+
+/// 从命令行参数中读取种子
+fn seed_from_args() -> Option<u64> {
+    parse_number(&arg_value(SEED_ARG)?, "seed", "command line")
+}
+
+/// 从环境变量中读取种子
+fn seed_from_env() -> Option<u64> {
+    let value = std::env::var(SEED_ENV_VAR).ok()?;
+    parse_number(&value, "seed", SEED_ENV_VAR)
+}
+
+/// 从设置文件中读取种子
+fn seed_from_settings() -> Option<u64> {
+    read_settings()?.seed
+}
+
+/// 读取命令行参数的值（`--name value` 或 `--name=value`）
+fn arg_value(name: &str) -> Option<String> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == name {
+            return args.next();
+        }
+        if let Some(value) = arg
+            .strip_prefix(name)
+            .and_then(|rest| rest.strip_prefix('='))
+        {
+            return Some(value.to_string());
+        }
+    }
+    None
+}
+
+/// 读取设置文件，文件不存在或解析失败时返回 `None`
+fn read_settings() -> Option<GameSettings> {
+    let contents = fs::read_to_string(SETTINGS_FILE).ok()?;
+    match ron::from_str::<GameSettings>(&contents) {
+        Ok(settings) => Some(settings),
+        Err(err) => {
+            warn!("Failed to parse {}: {}", SETTINGS_FILE, err);
+            None
+        }
+    }
+}
+
+/// 解析数字字符串，解析失败时记录警告
+///
+/// # 参数
+/// - `value`: 待解析的字符串
+/// - `what`: 值的名称，用于警告信息
+/// - `origin`: 值的来源，用于警告信息
+fn parse_number<T: std::str::FromStr>(value: &str, what: &str, origin: &str) -> Option<T> {
+    match value.trim().parse::<T>() {
+        Ok(number) => Some(number),
+        Err(_) => {
+            warn!("Ignoring invalid {} '{}' from {}", what, value, origin);
+            None
+        }
+    }
+}
+
+/// 在启动时记录世界种子
+pub fn log_world_seed(seed: Res<WorldSeed>) {
+    info!("🌱 World seed: {} (from {:?})", seed.value, seed.source);
+}
@@ -0,0 +1,287 @@
+use crate::collision::TileType;
+use crate::inventory::ItemKind;
+use crate::map::assets::SpawnableAsset;
+use crate::map::generate::ChunkData;
+use crate::map::layout::MapLayout;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// 单张地图的生成统计
+#[derive(Debug, Clone, Serialize)]
+pub struct MapStats {
+    /// 世界种子
+    pub seed: u64,
+    /// 瓦片数量（网格宽 × 高）
+    pub tiles: u32,
+    /// 每个模型出现的节点数量（按模型名称）
+    pub models: BTreeMap<String, u32>,
+    /// 每种瓦片类型的瓦片数量
+    pub tile_types: BTreeMap<String, u32>,
+    /// 可行走瓦片占比
+    pub walkable_ratio: f32,
+    /// 水面（深水和浅滩）瓦片占比
+    pub water_coverage: f32,
+    /// 每种可拾取植物的数量
+    pub plants: BTreeMap<String, u32>,
+    /// 孤立的可行走区域数量
+    pub isolated_regions: usize,
+    /// 连通性检查开辟的瓦片数量
+    pub carved_tiles: usize,
+}
+
+impl MapStats {
+    /// 统计一张地图
+    ///
+    /// # 参数
+    /// - `seed`: 世界种子
+    /// - `data`: WFC 生成结果
+    /// - `layout`: 由生成结果构建的地图布局
+    /// - `model_names`: 每个模型的名称（按模型索引）
+    /// - `assets`: 每个模型对应的可生成资源列表
+    pub fn collect(
+        seed: u64,
+        data: &ChunkData,
+        layout: &MapLayout,
+        model_names: &[String],
+        assets: &[Vec<SpawnableAsset>],
+    ) -> Self {
+        let mut models = BTreeMap::new();
+        let mut plants: BTreeMap<String, u32> = ItemKind::ALL
+            .iter()
+            .map(|kind| (format!("{:?}", kind), 0))
+            .collect();
+        for instance in data.iter() {
+            let Some(name) = model_names.get(instance.model_index) else {
+                continue;
+            };
+            *models.entry(name.clone()).or_insert(0) += 1;
+            for asset in assets.get(instance.model_index).into_iter().flatten() {
+                if let Some(kind) = asset.pickable() {
+                    *plants.entry(format!("{:?}", kind)).or_insert(0) += 1;
+                }
+            }
+        }
+
+        let mut tile_types: BTreeMap<String, u32> = TileType::ALL
+            .iter()
+            .map(|tile_type| (format!("{:?}", tile_type), 0))
+            .collect();
+        let mut walkable = 0;
+        let mut water = 0;
+        for y in 0..layout.height as i32 {
+            for x in 0..layout.width as i32 {
+                let tile = layout.collision.get_tile(x, y).unwrap_or_default();
+                *tile_types.entry(format!("{:?}", tile)).or_insert(0) += 1;
+                if tile.is_walkable() {
+                    walkable += 1;
+                }
+                if matches!(tile, TileType::Water | TileType::Shore) {
+                    water += 1;
+                }
+            }
+        }
+
+        let tiles = layout.width * layout.height;
+        Self {
+            seed,
+            tiles,
+            models,
+            tile_types,
+            walkable_ratio: walkable as f32 / tiles as f32,
+            water_coverage: water as f32 / tiles as f32,
+            plants,
+            isolated_regions: layout.regions.isolated.len(),
+            carved_tiles: layout.regions.carved_tiles,
+        }
+    }
+
+    /// 所有可拾取植物的数量
+    pub fn total_plants(&self) -> u32 {
+        self.plants.values().sum()
+    }
+}
+
+/// 数值分布摘要
+#[derive(Debug, Copy, Clone, Default, Serialize)]
+pub struct Summary {
+    pub min: f32,
+    pub mean: f32,
+    pub max: f32,
+}
+
+impl Summary {
+    /// 计算一组数值的最小值、平均值和最大值
+    fn from_values(values: impl IntoIterator<Item = f32>) -> Self {
+        let mut count = 0;
+        let mut summary = Summary {
+            min: f32::INFINITY,
+            mean: 0.0,
+            max: f32::NEG_INFINITY,
+        };
+        for value in values {
+            summary.min = summary.min.min(value);
+            summary.max = summary.max.max(value);
+            summary.mean += value;
+            count += 1;
+        }
+        if count == 0 {
+            return Summary::default();
+        }
+        summary.mean /= count as f32;
+        summary
+    }
+}
+
+/// 单个模型在整批地图中的统计
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelFrequency {
+    /// 所有地图中出现的节点总数
+    pub total: u32,
+    /// 平均每张地图的节点数量
+    pub per_map: f32,
+    /// 占所有网格位置的比例（每层每个位置恰好有一个模型）
+    pub frequency: f32,
+}
+
+/// 批量生成统计
+///
+/// 用同一套规则生成多张地图后的汇总，用于量化权重调整的效果
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchStats {
+    /// 成功生成的地图数量
+    pub generated: usize,
+    /// 生成失败的种子
+    pub failed_seeds: Vec<u64>,
+    /// 每个模型的出现频率
+    pub models: BTreeMap<String, ModelFrequency>,
+    /// 每种瓦片类型占所有瓦片的比例
+    pub tile_types: BTreeMap<String, f32>,
+    /// 可行走瓦片占比的分布
+    pub walkable_ratio: Summary,
+    /// 水面覆盖率的分布
+    pub water_coverage: Summary,
+    /// 每张地图中每种可拾取植物数量的分布
+    pub plants_per_map: BTreeMap<String, Summary>,
+    /// 每张地图中可拾取植物总数的分布
+    pub total_plants_per_map: Summary,
+    /// 每张地图的统计
+    pub maps: Vec<MapStats>,
+}
+
+impl BatchStats {
+    /// 汇总多张地图的统计
+    ///
+    /// # 参数
+    /// - `maps`: 每张成功生成的地图的统计
+    /// - `failed_seeds`: 生成失败的种子
+    /// - `model_names`: 所有模型名称（未出现的模型频率为 0）
+    pub fn from_maps(maps: Vec<MapStats>, failed_seeds: Vec<u64>, model_names: &[String]) -> Self {
+        let map_count = maps.len().max(1) as f32;
+        let total_tiles: u32 = maps.iter().map(|map| map.tiles).sum();
+        let total_tiles = total_tiles.max(1) as f32;
+
+        let models = model_names
+            .iter()
+            .map(|name| {
+                let total: u32 = maps.iter().filter_map(|map| map.models.get(name)).sum();
+                let frequency = ModelFrequency {
+                    total,
+                    per_map: total as f32 / map_count,
+                    frequency: total as f32 / total_tiles,
+                };
+                (name.clone(), frequency)
+            })
+            .collect();
+
+        let tile_types = TileType::ALL
+            .iter()
+            .map(|tile_type| {
+                let name = format!("{:?}", tile_type);
+                let total: u32 = maps
+                    .iter()
+                    .filter_map(|map| map.tile_types.get(&name))
+                    .sum();
+                (name, total as f32 / total_tiles)
+            })
+            .collect();
+
+        let plants_per_map = ItemKind::ALL
+            .iter()
+            .map(|kind| {
+                let name = format!("{:?}", kind);
+                let summary = Summary::from_values(
+                    maps.iter()
+                        .map(|map| map.plants.get(&name).copied().unwrap_or(0) as f32),
+                );
+                (name, summary)
+            })
+            .collect();
+
+        Self {
+            generated: maps.len(),
+            failed_seeds,
+            models,
+            tile_types,
+            walkable_ratio: Summary::from_values(maps.iter().map(|map| map.walkable_ratio)),
+            water_coverage: Summary::from_values(maps.iter().map(|map| map.water_coverage)),
+            plants_per_map,
+            total_plants_per_map: Summary::from_values(
+                maps.iter().map(|map| map.total_plants() as f32),
+            ),
+            maps,
+        }
+    }
+
+    /// 每张地图一行的 CSV
+    ///
+    /// 列：种子、可行走占比、水面覆盖率、孤立区域数、开辟瓦片数、各瓦片类型数量、各植物数量
+    pub fn maps_csv(&self) -> String {
+        let mut csv =
+            String::from("seed,walkable_ratio,water_coverage,isolated_regions,carved_tiles");
+        for tile_type in TileType::ALL {
+            let _ = write!(csv, ",tiles_{:?}", tile_type);
+        }
+        for kind in ItemKind::ALL {
+            let _ = write!(csv, ",plants_{:?}", kind);
+        }
+        csv.push('\n');
+
+        for map in &self.maps {
+            let _ = write!(
+                csv,
+                "{},{:.4},{:.4},{},{}",
+                map.seed,
+                map.walkable_ratio,
+                map.water_coverage,
+                map.isolated_regions,
+                map.carved_tiles
+            );
+            for tile_type in TileType::ALL {
+                let count = map.tile_types.get(&format!("{:?}", tile_type));
+                let _ = write!(csv, ",{}", count.copied().unwrap_or(0));
+            }
+            for kind in ItemKind::ALL {
+                let count = map.plants.get(&format!("{:?}", kind));
+                let _ = write!(csv, ",{}", count.copied().unwrap_or(0));
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// 每个模型一行的 CSV
+    ///
+    /// 列：模型名称、总数、平均每张地图数量、频率
+    pub fn models_csv(&self) -> String {
+        let mut csv = String::from("model,total,per_map,frequency\n");
+        for (name, frequency) in &self.models {
+            let _ = writeln!(
+                csv,
+                "{},{},{:.3},{:.6}",
+                name, frequency.total, frequency.per_map, frequency.frequency
+            );
+        }
+        csv
+    }
+}
@@ -0,0 +1,192 @@
+use crate::map::generate::map_grid;
+use crate::map::rules::{RulesError, StructureDefinition, TerrainRules};
+use bevy::prelude::*;
+use bevy_procedural_tilemaps::prelude::ModelRotation;
+use bevy_procedural_tilemaps::proc_gen::NodeIndex;
+use bevy_procedural_tilemaps::proc_gen::generator::model::{ModelIndex, ModelInstance};
+use std::collections::HashMap;
+
+/// 结构节点
+///
+/// 结构在某个网格位置、某个地形层上预先确定的模型
+#[derive(Debug, Copy, Clone)]
+struct StructureNode {
+    /// 模型索引
+    model: ModelIndex,
+    /// 所属结构的索引
+    structure: usize,
+}
+
+/// 结构布局
+///
+/// 所有已放置结构解析后的节点，按世界网格坐标和地形层索引。
+/// 生成区块前把落在区块内的节点作为预设节点交给 WFC，其余节点由 WFC 围绕它们填充。
+#[derive(Debug, Clone, Default)]
+pub struct StructureLayout {
+    /// (世界网格坐标, 地形层) 到结构节点的映射
+    nodes: HashMap<(IVec2, u32), StructureNode>,
+    /// 已放置结构的名称（按放置顺序）
+    names: Vec<String>,
+}
+
+impl StructureLayout {
+    /// 收集落在指定网格范围内的预设节点
+    ///
+    /// # 参数
+    /// - `origin`: 网格左下角的世界网格坐标
+    /// - `width`: 网格宽度
+    /// - `height`: 网格高度
+    ///
+    /// # 返回
+    /// 预设节点列表
+    pub fn presets(
+        &self,
+        origin: IVec2,
+        width: u32,
+        height: u32,
+    ) -> Vec<(NodeIndex, ModelInstance)> {
+        let grid = map_grid(width, height);
+        let mut presets: Vec<_> = self
+            .nodes
+            .iter()
+            .filter_map(|(&(cell, z), node)| {
+                let local = cell - origin;
+                if local.x < 0 || local.y < 0 || local.x >= width as i32 || local.y >= height as i32
+                {
+                    return None;
+                }
+                let index = grid.index_from_coords(local.x as u32, local.y as u32, z);
+                let instance = ModelInstance {
+                    model_index: node.model,
+                    rotation: ModelRotation::Rot0,
+                };
+                Some((index, instance))
+            })
+            .collect();
+        // 按节点索引排序，保证相同种子的生成结果一致
+        presets.sort_by_key(|(index, _)| *index);
+        presets
+    }
+
+    /// 获取覆盖指定世界网格坐标的结构名称
+    pub fn structure_at(&self, cell: IVec2) -> Option<&str> {
+        self.nodes
+            .get(&(cell, 0))
+            .map(|node| self.names[node.structure].as_str())
+    }
+
+    /// 已放置的结构数量
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// 是否没有放置任何结构
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+/// 解析单个结构定义
+///
+/// # 返回
+/// 结构中每个格子（相对于左下角）在每个地形层上的模型
+fn resolve_structure(
+    definition: &StructureDefinition,
+    model_layers: &HashMap<&str, (u32, ModelIndex)>,
+    layer_count: usize,
+) -> Result<Vec<(IVec2, u32, ModelIndex)>, RulesError> {
+    let invalid = |reason: String| RulesError::InvalidStructure {
+        structure: definition.name.clone(),
+        reason,
+    };
+
+    // 解析图例，检查每个模型都位于正确的地形层
+    let mut legend: HashMap<char, Vec<ModelIndex>> = HashMap::new();
+    for (symbol, models) in &definition.legend {
+        if models.len() != layer_count {
+            return Err(invalid(format!(
+                "legend '{}' lists {} models, expected one per layer ({})",
+                symbol,
+                models.len(),
+                layer_count
+            )));
+        }
+        let mut indices = Vec::with_capacity(models.len());
+        for (z, name) in models.iter().enumerate() {
+            let Some(&(layer, index)) = model_layers.get(name.as_str()) else {
+                return Err(invalid(format!("unknown model '{}'", name)));
+            };
+            if layer as usize != z {
+                return Err(invalid(format!(
+                    "model '{}' belongs to layer {}, not layer {}",
+                    name, layer, z
+                )));
+            }
+            indices.push(index);
+        }
+        legend.insert(*symbol, indices);
+    }
+
+    // 第一行是最上方，y 从下往上递增
+    let height = definition.rows.len() as i32;
+    let mut cells = Vec::new();
+    for (row, line) in definition.rows.iter().enumerate() {
+        let y = height - 1 - row as i32;
+        for (x, symbol) in line.chars().enumerate() {
+            let Some(models) = legend.get(&symbol) else {
+                return Err(invalid(format!("symbol '{}' is not in the legend", symbol)));
+            };
+            for (z, model) in models.iter().enumerate() {
+                cells.push((IVec2::new(x as i32, y), z as u32, *model));
+            }
+        }
+    }
+    Ok(cells)
+}
+
+/// 解析所有结构放置
+///
+/// 把结构印章中的模型名称解析为模型索引，并放置到世界网格中。
+/// 后放置的结构会覆盖先放置的结构的重叠部分。
+///
+/// # 参数
+/// - `rules`: 地形规则
+///
+/// # 返回
+/// 结构布局，如果结构定义或放置无效则返回错误
+pub fn resolve_structures(rules: &TerrainRules) -> Result<StructureLayout, RulesError> {
+    // 模型索引与构建顺序一致：按地形层顺序依次编号
+    let mut model_layers: HashMap<&str, (u32, ModelIndex)> = HashMap::new();
+    let mut next_index: ModelIndex = 0;
+    for (layer, definition) in rules.layers.iter().enumerate() {
+        for model in &definition.models {
+            model_layers.insert(model.name.as_str(), (layer as u32, next_index));
+            next_index += 1;
+        }
+    }
+
+    let mut resolved: HashMap<&str, Vec<(IVec2, u32, ModelIndex)>> = HashMap::new();
+    for definition in &rules.structures {
+        let cells = resolve_structure(definition, &model_layers, rules.layers.len())?;
+        resolved.insert(definition.name.as_str(), cells);
+    }
+
+    let mut layout = StructureLayout::default();
+    for placement in &rules.placements {
+        let Some(cells) = resolved.get(placement.structure.as_str()) else {
+            return Err(RulesError::UnknownStructure {
+                structure: placement.structure.clone(),
+            });
+        };
+        let origin = IVec2::new(placement.at.0, placement.at.1);
+        let structure = layout.names.len();
+        layout.names.push(placement.structure.clone());
+        for &(offset, z, model) in cells {
+            layout
+                .nodes
+                .insert((origin + offset, z), StructureNode { model, structure });
+        }
+    }
+
+    Ok(layout)
+}
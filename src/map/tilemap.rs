@@ -1,448 +1,161 @@
 use bevy::math::{URect, UVec2};
-
-/// 瓦片图定义常量
-///
-/// 定义了地图中所有可用的瓦片类型及其在纹理图集中的位置
-pub const TILEMAP: TilemapDefinition = TilemapDefinition {
-    // 单个瓦片的宽度（像素）
-    tile_width: 32,
-    // 单个瓦片的高度（像素）
-    tile_height: 32,
-    // 纹理图集的总宽度（像素）
-    atlas_width: 256,
-    // 纹理图集的总高度（像素）
-    atlas_height: 320,
-    // 瓦片精灵列表
-    sprites: &[
-        // 泥土瓦片
-        TilemapSprite {
-            name: "dirt",
-            pixel_x: 128,
-            pixel_y: 0,
-        },
-        // 绿色草地瓦片
-        TilemapSprite {
-            name: "green_grass",
-            pixel_x: 160,
-            pixel_y: 0,
-        },
-        // 绿色草地内角（左上）
-        TilemapSprite {
-            name: "green_grass_corner_in_tl",
-            pixel_x: 192,
-            pixel_y: 0,
-        },
-        // 绿色草地内角（右上）
-        TilemapSprite {
-            name: "green_grass_corner_in_tr",
-            pixel_x: 224,
-            pixel_y: 0,
-        },
-        // 绿色草地内角（左下）
-        TilemapSprite {
-            name: "green_grass_corner_in_bl",
-            pixel_x: 192,
-            pixel_y: 32,
-        },
-        // 绿色草地内角（右下）
-        TilemapSprite {
-            name: "green_grass_corner_in_br",
-            pixel_x: 224,
-            pixel_y: 32,
-        },
-        // 绿色草地外角（左上）
-        TilemapSprite {
-            name: "green_grass_corner_out_tl",
-            pixel_x: 0,
-            pixel_y: 64,
-        },
-        // 绿色草地外角（右上）
-        TilemapSprite {
-            name: "green_grass_corner_out_tr",
-            pixel_x: 32,
-            pixel_y: 64,
-        },
-        // 绿色草地外角（左下）
-        TilemapSprite {
-            name: "green_grass_corner_out_bl",
-            pixel_x: 0,
-            pixel_y: 96,
-        },
-        // 绿色草地外角（右下）
-        TilemapSprite {
-            name: "green_grass_corner_out_br",
-            pixel_x: 32,
-            pixel_y: 96,
-        },
-        // 绿色草地边缘（上）
-        TilemapSprite {
-            name: "green_grass_side_t",
-            pixel_x: 64,
-            pixel_y: 64,
-        },
-        // 绿色草地边缘（右）
-        TilemapSprite {
-            name: "green_grass_side_r",
-            pixel_x: 96,
-            pixel_y: 64,
-        },
-        // 绿色草地边缘（左）
-        TilemapSprite {
-            name: "green_grass_side_l",
-            pixel_x: 64,
-            pixel_y: 96,
-        },
-        // 绿色草地边缘（下）
-        TilemapSprite {
-            name: "green_grass_side_b",
-            pixel_x: 96,
-            pixel_y: 96,
-        },
-        // 黄色草地瓦片
-        TilemapSprite {
-            name: "yellow_grass",
-            pixel_x: 0,
-            pixel_y: 256,
-        },
-        // 黄色草地内角（左上）
-        TilemapSprite {
-            name: "yellow_grass_corner_in_tl",
-            pixel_x: 32,
-            pixel_y: 256,
-        },
-        // 黄色草地内角（右上）
-        TilemapSprite {
-            name: "yellow_grass_corner_in_tr",
-            pixel_x: 64,
-            pixel_y: 256,
-        },
-        // 黄色草地内角（左下）
-        TilemapSprite {
-            name: "yellow_grass_corner_in_bl",
-            pixel_x: 32,
-            pixel_y: 288,
-        },
-        // 黄色草地内角（右下）
-        TilemapSprite {
-            name: "yellow_grass_corner_in_br",
-            pixel_x: 64,
-            pixel_y: 288,
-        },
-        // 黄色草地外角（左上）
-        TilemapSprite {
-            name: "yellow_grass_corner_out_tl",
-            pixel_x: 96,
-            pixel_y: 256,
-        },
-        // 黄色草地外角（右上）
-        TilemapSprite {
-            name: "yellow_grass_corner_out_tr",
-            pixel_x: 128,
-            pixel_y: 256,
-        },
-        // 黄色草地外角（左下）
-        TilemapSprite {
-            name: "yellow_grass_corner_out_bl",
-            pixel_x: 96,
-            pixel_y: 288,
-        },
-        // 黄色草地外角（右下）
-        TilemapSprite {
-            name: "yellow_grass_corner_out_br",
-            pixel_x: 128,
-            pixel_y: 288,
-        },
-        // 黄色草地边缘（上）
-        TilemapSprite {
-            name: "yellow_grass_side_t",
-            pixel_x: 160,
-            pixel_y: 256,
-        },
-        // 黄色草地边缘（右）
-        TilemapSprite {
-            name: "yellow_grass_side_r",
-            pixel_x: 192,
-            pixel_y: 256,
-        },
-        // 黄色草地边缘（左）
-        TilemapSprite {
-            name: "yellow_grass_side_l",
-            pixel_x: 160,
-            pixel_y: 288,
-        },
-        // 黄色草地边缘（下）
-        TilemapSprite {
-            name: "yellow_grass_side_b",
-            pixel_x: 192,
-            pixel_y: 288,
-        },
-        // 水瓦片
-        TilemapSprite {
-            name: "water",
-            pixel_x: 32,
-            pixel_y: 192,
-        },
-        // 水内角（左上）
-        TilemapSprite {
-            name: "water_corner_in_tl",
-            pixel_x: 64,
-            pixel_y: 192,
-        },
-        // 水内角（右上）
-        TilemapSprite {
-            name: "water_corner_in_tr",
-            pixel_x: 96,
-            pixel_y: 192,
-        },
-        // 水内角（左下）
-        TilemapSprite {
-            name: "water_corner_in_bl",
-            pixel_x: 64,
-            pixel_y: 224,
-        },
-        // 水内角（右下）
-        TilemapSprite {
-            name: "water_corner_in_br",
-            pixel_x: 96,
-            pixel_y: 224,
-        },
-        // 水外角（左上）
-        TilemapSprite {
-            name: "water_corner_out_tl",
-            pixel_x: 128,
-            pixel_y: 192,
-        },
-        // 水外角（右上）
-        TilemapSprite {
-            name: "water_corner_out_tr",
-            pixel_x: 160,
-            pixel_y: 192,
-        },
-        // 水外角（左下）
-        TilemapSprite {
-            name: "water_corner_out_bl",
-            pixel_x: 128,
-            pixel_y: 224,
-        },
-        // 水外角（右下）
-        TilemapSprite {
-            name: "water_corner_out_br",
-            pixel_x: 160,
-            pixel_y: 224,
-        },
-        // 水边缘（上）
-        TilemapSprite {
-            name: "water_side_t",
-            pixel_x: 192,
-            pixel_y: 192,
-        },
-        // 水边缘（右）
-        TilemapSprite {
-            name: "water_side_r",
-            pixel_x: 224,
-            pixel_y: 192,
-        },
-        // 水边缘（左）
-        TilemapSprite {
-            name: "water_side_l",
-            pixel_x: 192,
-            pixel_y: 224,
-        },
-        // 水边缘（下）
-        TilemapSprite {
-            name: "water_side_b",
-            pixel_x: 224,
-            pixel_y: 224,
-        },
-        // 大树-1（左上）
-        TilemapSprite {
-            name: "big_tree_1_tl",
-            pixel_x: 0,
-            pixel_y: 0,
-        },
-        // 大树-1（右上）
-        TilemapSprite {
-            name: "big_tree_1_tr",
-            pixel_x: 32,
-            pixel_y: 0,
-        },
-        // 大树-1（左下）
-        TilemapSprite {
-            name: "big_tree_1_bl",
-            pixel_x: 0,
-            pixel_y: 32,
-        },
-        // 大树-1（右下）
-        TilemapSprite {
-            name: "big_tree_1_br",
-            pixel_x: 32,
-            pixel_y: 32,
-        },
-        // 大树-2（左上）
-        TilemapSprite {
-            name: "big_tree_2_tl",
-            pixel_x: 64,
-            pixel_y: 0,
-        },
-        // 大树-2（右上）
-        TilemapSprite {
-            name: "big_tree_2_tr",
-            pixel_x: 96,
-            pixel_y: 0,
-        },
-        // 大树-2（左下）
-        TilemapSprite {
-            name: "big_tree_2_bl",
-            pixel_x: 64,
-            pixel_y: 32,
-        },
-        // 大树-2（右下）
-        TilemapSprite {
-            name: "big_tree_2_br",
-            pixel_x: 96,
-            pixel_y: 32,
-        },
-        // 植物-1
-        TilemapSprite {
-            name: "plant_1",
-            pixel_x: 128,
-            pixel_y: 64,
-        },
-        // 植物-2
-        TilemapSprite {
-            name: "plant_2",
-            pixel_x: 160,
-            pixel_y: 64,
-        },
-        // 植物-3
-        TilemapSprite {
-            name: "plant_3",
-            pixel_x: 192,
-            pixel_y: 64,
-        },
-        // 植物-4
-        TilemapSprite {
-            name: "plant_4",
-            pixel_x: 224,
-            pixel_y: 64,
-        },
-        // 岩石-1
-        TilemapSprite {
-            name: "rock_1",
-            pixel_x: 0,
-            pixel_y: 128,
-        },
-        // 岩石-2
-        TilemapSprite {
-            name: "rock_2",
-            pixel_x: 32,
-            pixel_y: 128,
-        },
-        // 岩石-3
-        TilemapSprite {
-            name: "rock_3",
-            pixel_x: 64,
-            pixel_y: 128,
-        },
-        // 岩石-4
-        TilemapSprite {
-            name: "rock_4",
-            pixel_x: 96,
-            pixel_y: 128,
-        },
-        // 小树（顶部）
-        TilemapSprite {
-            name: "small_tree_top",
-            pixel_x: 128,
-            pixel_y: 128,
-        },
-        // 小树（底部）
-        TilemapSprite {
-            name: "small_tree_bottom",
-            pixel_x: 128,
-            pixel_y: 160,
-        },
-        // 树桩-1
-        TilemapSprite {
-            name: "tree_stump_1",
-            pixel_x: 192,
-            pixel_y: 128,
-        },
-        // 树桩-2
-        TilemapSprite {
-            name: "tree_stump_2",
-            pixel_x: 224,
-            pixel_y: 128,
-        },
-        // 树桩-3
-        TilemapSprite {
-            name: "tree_stump_3",
-            pixel_x: 0,
-            pixel_y: 192,
-        },
-    ],
-};
+use bevy::prelude::*;
+use serde::Deserialize;
 
 /// 瓦片精灵结构体
 ///
 /// 定义单个瓦片精灵在纹理图集中的位置
+#[derive(Debug, Clone, Deserialize)]
 pub struct TilemapSprite {
     /// 瓦片名称
-    pub name: &'static str,
+    pub name: String,
     /// 纹理图集中的 X 坐标（像素）
     pub pixel_x: u32,
     /// 纹理图集中的 Y 坐标（像素）
     pub pixel_y: u32,
+    /// 区域宽度（像素），用于跨多个瓦片的精灵，未设置时使用瓦片宽度
+    #[serde(default)]
+    pub width: Option<u32>,
+    /// 区域高度（像素），用于跨多个瓦片的精灵，未设置时使用瓦片高度
+    #[serde(default)]
+    pub height: Option<u32>,
+    /// 可选的帧动画，使用该精灵的瓦片会循环播放这些帧
+    #[serde(default)]
+    pub animation: Option<TileAnimationDefinition>,
+}
+
+/// 瓦片动画定义
+#[derive(Debug, Clone, Deserialize)]
+pub struct TileAnimationDefinition {
+    /// 动画帧（按播放顺序），帧精灵必须与该精灵位于同一张瓦片图
+    pub frames: Vec<TileFrame>,
+    /// 每帧持续时间（秒）
+    pub frame_duration: f32,
+    /// 帧同步方式
+    #[serde(default)]
+    pub sync: FrameSync,
 }
 
-/// 瓦片图定义结构体
+/// 动画帧
+#[derive(Debug, Clone, Deserialize)]
+pub struct TileFrame {
+    /// 帧使用的精灵名称
+    pub sprite: String,
+    /// 是否水平翻转
+    #[serde(default)]
+    pub flip_x: bool,
+    /// 是否垂直翻转
+    #[serde(default)]
+    pub flip_y: bool,
+}
+
+/// 帧同步方式
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Deserialize)]
+pub enum FrameSync {
+    /// 同一动画的所有瓦片同时切换到相同的帧
+    #[default]
+    Synchronized,
+    /// 按瓦片位置错开帧，避免整片瓦片同步闪烁
+    ByPosition,
+}
+
+/// 瓦片集图像
 ///
-/// 定义整个瓦片图的配置参数
-pub struct TilemapDefinition {
-    /// 单个瓦片的宽度（像素）
-    pub tile_width: u32,
-    /// 单个瓦片的高度（像素）
-    pub tile_height: u32,
+/// 单张瓦片图及其包含的精灵
+#[derive(Debug, Clone, Deserialize)]
+pub struct TilesetImage {
+    /// 图像路径（相对于资源目录）
+    pub path: String,
     /// 纹理图集的总宽度（像素）
     pub atlas_width: u32,
     /// 纹理图集的总高度（像素）
     pub atlas_height: u32,
+    /// 覆盖瓦片集默认的瓦片宽度（像素）
+    #[serde(default)]
+    pub tile_width: Option<u32>,
+    /// 覆盖瓦片集默认的瓦片高度（像素）
+    #[serde(default)]
+    pub tile_height: Option<u32>,
     /// 瓦片精灵列表
-    pub sprites: &'static [TilemapSprite],
+    pub sprites: Vec<TilemapSprite>,
 }
 
-impl TilemapDefinition {
-    /// 获取单个瓦片的大小
-    pub const fn tile_size(&self) -> UVec2 {
-        UVec2::new(self.tile_width, self.tile_height)
-    }
+/// 瓦片集定义资源
+///
+/// 从 `*.tileset.ron` 文件加载，可以包含多张瓦片图
+#[derive(Asset, TypePath, Debug, Clone, Deserialize)]
+pub struct TilesetDefinition {
+    /// 默认的单个瓦片宽度（像素）
+    pub tile_width: u32,
+    /// 默认的单个瓦片高度（像素）
+    pub tile_height: u32,
+    /// 瓦片图列表
+    pub images: Vec<TilesetImage>,
+}
 
-    /// 获取纹理图集的总大小
-    pub const fn atlas_size(&self) -> UVec2 {
-        UVec2::new(self.atlas_width, self.atlas_height)
+/// 精灵引用
+///
+/// 指向瓦片集中某张瓦片图的某个精灵
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct SpriteRef {
+    /// 瓦片图索引
+    pub image: usize,
+    /// 精灵在该瓦片图图集中的索引
+    pub index: usize,
+}
+
+impl TilesetDefinition {
+    /// 获取指定瓦片图的单个瓦片大小
+    pub fn tile_size(&self, image: &TilesetImage) -> UVec2 {
+        UVec2::new(
+            image.tile_width.unwrap_or(self.tile_width),
+            image.tile_height.unwrap_or(self.tile_height),
+        )
     }
 
-    /// 根据名称查找瓦片索引
+    /// 根据名称查找精灵
     ///
     /// # 参数
     /// - `name`: 瓦片名称
     ///
     /// # 返回
-    /// 瓦片的索引，如果未找到则返回 None
-    pub fn sprite_index(&self, name: &str) -> Option<usize> {
-        self.sprites.iter().position(|sprite| sprite.name == name)
+    /// 精灵引用，如果未找到则返回 None
+    pub fn sprite_index(&self, name: &str) -> Option<SpriteRef> {
+        self.images
+            .iter()
+            .enumerate()
+            .find_map(|(image, tileset_image)| {
+                tileset_image
+                    .sprites
+                    .iter()
+                    .position(|sprite| sprite.name == name)
+                    .map(|index| SpriteRef { image, index })
+            })
     }
 
-    /// 获取指定索引瓦片的矩形区域
+    /// 获取指定精灵的矩形区域
     ///
     /// # 参数
-    /// - `index`: 瓦片索引
+    /// - `image`: 瓦片图
+    /// - `index`: 精灵在该瓦片图中的索引
     ///
     /// # 返回
-    /// 瓦片在纹理图集中的矩形区域
-    pub fn sprite_rect(&self, index: usize) -> URect {
-        let sprite = &self.sprites[index];
+    /// 精灵在纹理图集中的矩形区域
+    pub fn sprite_rect(&self, image: &TilesetImage, index: usize) -> URect {
+        let sprite = &image.sprites[index];
+        let tile_size = self.tile_size(image);
+        let size = UVec2::new(
+            sprite.width.unwrap_or(tile_size.x),
+            sprite.height.unwrap_or(tile_size.y),
+        );
         let min = UVec2::new(sprite.pixel_x, sprite.pixel_y);
-        URect::from_corners(min, min + self.tile_size())
+        URect::from_corners(min, min + size)
+    }
+}
+
+impl TilesetImage {
+    /// 获取纹理图集的总大小
+    pub fn atlas_size(&self) -> UVec2 {
+        UVec2::new(self.atlas_width, self.atlas_height)
     }
 }
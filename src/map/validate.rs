@@ -0,0 +1,402 @@
+use crate::map::rules::{AssetDefinition, ModelSockets, Rotation, TerrainRules, build_world};
+use crate::map::structure::resolve_structures;
+use crate::map::tilemap::TilesetDefinition;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// 问题严重程度
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Severity {
+    /// 会导致运行时崩溃或某些模型永远无法生成
+    Error,
+    /// 可能是遗漏，但不影响生成
+    Warning,
+}
+
+/// 单个检查问题
+#[derive(Debug, Clone)]
+pub struct ValidationIssue {
+    /// 严重程度
+    pub severity: Severity,
+    /// 问题描述
+    pub message: String,
+}
+
+/// 规则检查报告
+#[derive(Debug, Clone, Default)]
+pub struct ValidationReport {
+    /// 检查的模型数量
+    pub models: usize,
+    /// 检查的连接器数量
+    pub sockets: usize,
+    /// 发现的问题
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    /// 没有错误时返回 true（允许存在警告）
+    pub fn is_ok(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    /// 所有错误
+    pub fn errors(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Error)
+    }
+
+    /// 所有警告
+    pub fn warnings(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Warning)
+    }
+
+    fn error(&mut self, message: String) {
+        self.issues.push(ValidationIssue {
+            severity: Severity::Error,
+            message,
+        });
+    }
+
+    fn warning(&mut self, message: String) {
+        self.issues.push(ValidationIssue {
+            severity: Severity::Warning,
+            message,
+        });
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "Checked {} models and {} sockets",
+            self.models, self.sockets
+        )?;
+        for issue in &self.issues {
+            let label = match issue.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+            };
+            writeln!(f, "  {}: {}", label, issue.message)?;
+        }
+        let errors = self.errors().count();
+        let warnings = self.warnings().count();
+        if errors == 0 && warnings == 0 {
+            write!(f, "OK: no problems found")
+        } else {
+            write!(f, "{} error(s), {} warning(s)", errors, warnings)
+        }
+    }
+}
+
+/// 模型的一个面
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Side {
+    XPos,
+    XNeg,
+    YPos,
+    YNeg,
+    ZPos,
+    ZNeg,
+}
+
+impl Side {
+    const ALL: [Side; 6] = [
+        Side::XPos,
+        Side::XNeg,
+        Side::YPos,
+        Side::YNeg,
+        Side::ZPos,
+        Side::ZNeg,
+    ];
+
+    /// 相邻模型上与之相对的面
+    fn opposite(self) -> Self {
+        match self {
+            Side::XPos => Side::XNeg,
+            Side::XNeg => Side::XPos,
+            Side::YPos => Side::YNeg,
+            Side::YNeg => Side::YPos,
+            Side::ZPos => Side::ZNeg,
+            Side::ZNeg => Side::ZPos,
+        }
+    }
+
+    /// 绕 Z 轴逆时针旋转 90 度后的面
+    fn rotated(self) -> Self {
+        match self {
+            Side::XPos => Side::YPos,
+            Side::YPos => Side::XNeg,
+            Side::XNeg => Side::YNeg,
+            Side::YNeg => Side::XPos,
+            Side::ZPos | Side::ZNeg => self,
+        }
+    }
+
+    fn is_vertical(self) -> bool {
+        matches!(self, Side::ZPos | Side::ZNeg)
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Side::XPos => "x_pos",
+            Side::XNeg => "x_neg",
+            Side::YPos => "y_pos",
+            Side::YNeg => "y_neg",
+            Side::ZPos => "z_pos",
+            Side::ZNeg => "z_neg",
+        }
+    }
+}
+
+/// 旋转对应的 90 度步数
+fn rotation_steps(rotation: Rotation) -> usize {
+    match rotation {
+        Rotation::Rot0 => 0,
+        Rotation::Rot90 => 1,
+        Rotation::Rot180 => 2,
+        Rotation::Rot270 => 3,
+    }
+}
+
+/// 应用旋转后的模型
+struct ResolvedModel<'a> {
+    name: &'a str,
+    layer: usize,
+    rotation: Rotation,
+    template: Option<&'a str>,
+    /// 按 `Side::ALL` 顺序排列的各个面的连接器（已应用旋转）
+    sides: [&'a [String]; 6],
+    assets: &'a [AssetDefinition],
+}
+
+impl ResolvedModel<'_> {
+    fn side(&self, side: Side) -> &[String] {
+        self.sides[side as usize]
+    }
+}
+
+/// 解析所有模型，应用模板和旋转
+///
+/// 引用了不存在模板的模型会被跳过（`build_world` 会报告该错误）
+fn resolve_models(rules: &TerrainRules) -> Vec<ResolvedModel<'_>> {
+    let mut models = Vec::new();
+    for (layer, definition) in rules.layers.iter().enumerate() {
+        for model in &definition.models {
+            let (sockets, template) = match &model.sockets {
+                ModelSockets::Template(name) => match rules.templates.get(name) {
+                    Some(template) => (&template.sockets, Some(name.as_str())),
+                    None => continue,
+                },
+                ModelSockets::Sockets(sockets) => (sockets, None),
+            };
+            let original: [&[String]; 6] = [
+                &sockets.x_pos,
+                &sockets.x_neg,
+                &sockets.y_pos,
+                &sockets.y_neg,
+                &sockets.z_pos,
+                &sockets.z_neg,
+            ];
+
+            let mut sides = original;
+            for side in Side::ALL {
+                let mut target = side;
+                for _ in 0..rotation_steps(model.rotation) {
+                    target = target.rotated();
+                }
+                sides[target as usize] = original[side as usize];
+            }
+
+            models.push(ResolvedModel {
+                name: &model.name,
+                layer,
+                rotation: model.rotation,
+                template,
+                sides,
+                assets: &model.assets,
+            });
+        }
+    }
+    models
+}
+
+/// 名称中的方向后缀（例如 `_tl`、`_b`）
+fn direction_suffix(name: &str) -> Option<&str> {
+    let (_, suffix) = name.rsplit_once('_')?;
+    matches!(suffix, "t" | "l" | "b" | "r" | "tl" | "tr" | "bl" | "br").then_some(suffix)
+}
+
+/// 将方向后缀逆时针旋转 90 度
+fn rotate_suffix(suffix: &str) -> &'static str {
+    match suffix {
+        "t" => "l",
+        "l" => "b",
+        "b" => "r",
+        "r" => "t",
+        "tl" => "bl",
+        "bl" => "br",
+        "br" => "tr",
+        _ => "tl",
+    }
+}
+
+/// 检查地形规则与瓦片集是否一致
+///
+/// 检查内容：
+/// - 规则能否构建（连接器、模板、结构引用是否有效）
+/// - 每个资源引用的精灵都存在于瓦片集中
+/// - 每个连接器都至少有一条连接规则
+/// - 每个模型在每个方向上都至少能与一个模型相邻（最底层的下方和最上层的上方除外）
+/// - 旋转模板生成的模型名称和精灵名称与旋转后的方向一致
+///
+/// # 参数
+/// - `rules`: 地形规则
+/// - `tileset`: 瓦片集定义
+///
+/// # 返回
+/// 检查报告
+pub fn validate_rules(rules: &TerrainRules, tileset: &TilesetDefinition) -> ValidationReport {
+    let mut report = ValidationReport {
+        sockets: rules.sockets.len(),
+        ..Default::default()
+    };
+
+    if let Err(err) = build_world(rules) {
+        report.error(err.to_string());
+    }
+    if let Err(err) = resolve_structures(rules) {
+        report.error(err.to_string());
+    }
+
+    let models = resolve_models(rules);
+    report.models = models.len();
+    let top_layer = rules.layers.len().saturating_sub(1);
+    // 最底层的下方和最上层的上方是网格边界，不需要相邻模型
+    let is_boundary = |model: &ResolvedModel, side: Side| {
+        (side == Side::ZNeg && model.layer == 0) || (side == Side::ZPos && model.layer == top_layer)
+    };
+
+    // 精灵必须存在于瓦片集中，否则 load_assets 会崩溃
+    for model in &models {
+        for asset in model.assets {
+            if tileset.sprite_index(&asset.sprite).is_none() {
+                report.error(format!(
+                    "model '{}' uses sprite '{}' which is not in the tileset",
+                    model.name, asset.sprite
+                ));
+            }
+        }
+    }
+
+    // 连接关系是对称的，记录是否为旋转连接（允许不同旋转的模型在 Z 方向相连）
+    let mut connections: HashMap<&str, HashMap<&str, bool>> = HashMap::new();
+    for layer in &rules.layers {
+        for connection in &layer.connections {
+            for target in &connection.connects_to {
+                for (from, to) in [(&connection.socket, target), (target, &connection.socket)] {
+                    *connections
+                        .entry(from.as_str())
+                        .or_default()
+                        .entry(to.as_str())
+                        .or_default() |= connection.rotated;
+                }
+            }
+        }
+    }
+
+    // 每个连接器都应该有连接规则，只用于网格边界的连接器除外
+    let mut used: HashSet<&str> = HashSet::new();
+    let mut used_inside: HashSet<&str> = HashSet::new();
+    for model in &models {
+        for side in Side::ALL {
+            for socket in model.side(side) {
+                used.insert(socket);
+                if !is_boundary(model, side) {
+                    used_inside.insert(socket);
+                }
+            }
+        }
+    }
+    for socket in &rules.sockets {
+        if !used.contains(socket.as_str()) {
+            report.warning(format!("socket '{}' is not used by any model", socket));
+        } else if used_inside.contains(socket.as_str())
+            && !connections.contains_key(socket.as_str())
+        {
+            report.error(format!("socket '{}' is not connected to anything", socket));
+        }
+    }
+
+    // 每个模型在每个方向上都需要至少一个可以相邻的模型
+    let compatible = |model: &ResolvedModel, neighbour: &ResolvedModel, side: Side| {
+        model.side(side).iter().any(|from| {
+            neighbour.side(side.opposite()).iter().any(|to| {
+                connections
+                    .get(from.as_str())
+                    .and_then(|targets| targets.get(to.as_str()))
+                    .is_some_and(|rotated| {
+                        !side.is_vertical() || *rotated || model.rotation == neighbour.rotation
+                    })
+            })
+        })
+    };
+    for model in &models {
+        for side in Side::ALL {
+            if is_boundary(model, side) {
+                continue;
+            }
+            if !models
+                .iter()
+                .any(|neighbour| compatible(model, neighbour, side))
+            {
+                report.error(format!(
+                    "model '{}' has no possible neighbour on {} ({:?}), it can never be generated",
+                    model.name,
+                    side.label(),
+                    model.side(side)
+                ));
+            }
+        }
+    }
+
+    // 旋转模型的方向后缀应与模板未旋转模型的后缀旋转后一致
+    for model in &models {
+        let Some(template) = model.template else {
+            continue;
+        };
+        let Some(base_suffix) = models
+            .iter()
+            .find(|base| base.template == Some(template) && base.rotation == Rotation::Rot0)
+            .and_then(|base| direction_suffix(base.name))
+        else {
+            continue;
+        };
+        let mut expected = base_suffix;
+        for _ in 0..rotation_steps(model.rotation) {
+            expected = rotate_suffix(expected);
+        }
+
+        if direction_suffix(model.name) != Some(expected) {
+            report.error(format!(
+                "model '{}' uses template '{}' rotated {:?}, expected a name ending in '_{}'",
+                model.name, template, model.rotation, expected
+            ));
+        }
+        for asset in model.assets {
+            if let Some(suffix) = direction_suffix(&asset.sprite)
+                && suffix != expected
+            {
+                report.error(format!(
+                    "model '{}' is rotated {:?} but uses sprite '{}', expected art ending in '_{}'",
+                    model.name, model.rotation, asset.sprite, expected
+                ));
+            }
+        }
+    }
+
+    report
+}
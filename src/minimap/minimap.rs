@@ -0,0 +1,278 @@
+use crate::characters::facing::Facing;
+use crate::characters::input::Player;
+use crate::collision::{CollisionMap, TileType};
+use crate::config::minimap::{DEFAULT_ZOOM, DISPLAY_SIZE, IMAGE_SIZE, MARGIN, ZOOM_LEVELS};
+use crate::inventory::Pickable;
+use bevy::asset::RenderAssetUsages;
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+
+/// 地图范围之外的颜色
+const BACKGROUND_COLOR: Color = Color::srgb(0.05, 0.05, 0.08);
+/// 玩家标记的颜色
+const PLAYER_COLOR: Color = Color::WHITE;
+/// 玩家朝向标记的颜色
+const FACING_COLOR: Color = Color::srgb(1.0, 0.85, 0.1);
+/// 拾取物标记的颜色
+const PICKUP_COLOR: Color = Color::srgb(0.95, 0.3, 0.85);
+
+/// 小地图设置资源
+#[derive(Resource, Debug)]
+pub struct MinimapSettings {
+    /// 是否显示小地图
+    pub enabled: bool,
+    /// 缩放级别（`ZOOM_LEVELS` 中的索引）
+    pub zoom: usize,
+}
+
+impl Default for MinimapSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            zoom: DEFAULT_ZOOM,
+        }
+    }
+}
+
+impl MinimapSettings {
+    /// 当前缩放级别下每个瓦片占用的像素数
+    pub fn pixels_per_tile(&self) -> u32 {
+        ZOOM_LEVELS[self.zoom.min(ZOOM_LEVELS.len() - 1)]
+    }
+}
+
+/// 小地图组件
+///
+/// 标记显示小地图图像的 UI 节点
+#[derive(Component)]
+pub struct Minimap {
+    /// 小地图图像
+    image: Handle<Image>,
+    /// 上次绘制时的视图，视图和地图都没有变化时不重新绘制
+    last_view: Option<MinimapView>,
+}
+
+/// 小地图视图
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+struct MinimapView {
+    /// 玩家所在的碰撞地图网格坐标（视图中心）
+    center: IVec2,
+    /// 玩家朝向
+    facing: Facing,
+    /// 每个瓦片占用的像素数
+    pixels_per_tile: u32,
+}
+
+/// 获取瓦片类型在小地图中的颜色
+fn tile_color(tile_type: TileType) -> Color {
+    match tile_type {
+        TileType::Empty => Color::srgb(0.1, 0.1, 0.1),
+        TileType::Dirt => Color::srgb(0.55, 0.4, 0.25),
+        TileType::Grass => Color::srgb(0.3, 0.6, 0.25),
+        TileType::YellowGrass => Color::srgb(0.7, 0.7, 0.3),
+        TileType::Shore => Color::srgb(0.45, 0.7, 0.85),
+        TileType::Water => Color::srgb(0.15, 0.35, 0.7),
+        TileType::Tree => Color::srgb(0.1, 0.35, 0.15),
+        TileType::Rock => Color::srgb(0.5, 0.5, 0.5),
+    }
+}
+
+/// 获取朝向对应的网格方向
+fn facing_direction(facing: Facing) -> IVec2 {
+    match facing {
+        Facing::Up => IVec2::Y,
+        Facing::Left => IVec2::NEG_X,
+        Facing::Down => IVec2::NEG_Y,
+        Facing::Right => IVec2::X,
+    }
+}
+
+/// 填充图像中的矩形区域
+///
+/// 坐标以图像左下角为原点（与世界坐标的 Y 轴方向一致），超出图像的部分会被裁剪
+///
+/// # 参数
+/// - `image`: 小地图图像
+/// - `min`: 矩形左下角（包含）
+/// - `max`: 矩形右上角（不包含）
+/// - `color`: 填充颜色
+fn fill_rect(image: &mut Image, min: IVec2, max: IVec2, color: Color) {
+    let size = IMAGE_SIZE as i32;
+    let min = min.max(IVec2::ZERO);
+    let max = max.min(IVec2::splat(size));
+    for y in min.y..max.y {
+        for x in min.x..max.x {
+            // 图像的行从上往下排列
+            let _ = image.set_color_at(x as u32, (size - 1 - y) as u32, color);
+        }
+    }
+}
+
+/// 生成小地图节点
+///
+/// 小地图固定在屏幕右上角，地图构建完成之前保持隐藏
+pub fn spawn_minimap(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let image = Image::new_fill(
+        Extent3d {
+            width: IMAGE_SIZE,
+            height: IMAGE_SIZE,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 255],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    let image = images.add(image);
+
+    commands.spawn((
+        Minimap {
+            image: image.clone(),
+            last_view: None,
+        },
+        ImageNode::new(image),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(MARGIN),
+            right: Val::Px(MARGIN),
+            width: Val::Px(DISPLAY_SIZE),
+            height: Val::Px(DISPLAY_SIZE),
+            border: UiRect::all(Val::Px(2.0)),
+            ..default()
+        },
+        BorderColor::all(Color::srgba(1.0, 1.0, 1.0, 0.6)),
+        Visibility::Hidden,
+    ));
+}
+
+/// 按 M 显示或隐藏小地图
+pub fn toggle_minimap(keyboard: Res<ButtonInput<KeyCode>>, mut settings: ResMut<MinimapSettings>) {
+    if keyboard.just_pressed(KeyCode::KeyM) {
+        settings.enabled = !settings.enabled;
+        info!(
+            "Minimap {}",
+            if settings.enabled { "shown" } else { "hidden" }
+        );
+    }
+}
+
+/// 按 = 放大、按 - 缩小小地图
+pub fn zoom_minimap(keyboard: Res<ButtonInput<KeyCode>>, mut settings: ResMut<MinimapSettings>) {
+    if keyboard.just_pressed(KeyCode::Equal) && settings.zoom + 1 < ZOOM_LEVELS.len() {
+        settings.zoom += 1;
+    }
+    if keyboard.just_pressed(KeyCode::Minus) && settings.zoom > 0 {
+        settings.zoom -= 1;
+    }
+}
+
+/// 离开游戏状态时隐藏小地图
+pub fn hide_minimap(mut minimap_query: Query<&mut Visibility, With<Minimap>>) {
+    for mut visibility in minimap_query.iter_mut() {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+/// 绘制小地图
+///
+/// 以玩家所在的网格为中心，每个瓦片按类型绘制为一块颜色，
+/// 再叠加剩余的拾取物和玩家位置及朝向。
+/// 只有碰撞地图、拾取物、设置或玩家所在网格和朝向发生变化时才重新绘制。
+///
+/// # 参数
+/// - `settings`: 小地图设置
+/// - `collision_map`: 碰撞地图（尚未构建时隐藏小地图）
+/// - `images`: 图像资源集合
+/// - `minimap_query`: 小地图节点
+/// - `player_query`: 玩家的位置和朝向
+/// - `pickups`: 剩余的拾取物
+/// - `added_pickups`: 本帧新生成的拾取物
+/// - `removed_pickups`: 本帧被拾取或销毁的拾取物
+#[allow(clippy::too_many_arguments)]
+pub fn draw_minimap(
+    settings: Res<MinimapSettings>,
+    collision_map: Option<Res<CollisionMap>>,
+    mut images: ResMut<Assets<Image>>,
+    mut minimap_query: Query<(&mut Minimap, &mut Visibility)>,
+    player_query: Query<(&Transform, &Facing), With<Player>>,
+    pickups: Query<&GlobalTransform, With<Pickable>>,
+    added_pickups: Query<(), Added<Pickable>>,
+    mut removed_pickups: RemovedComponents<Pickable>,
+) {
+    let pickups_changed = !added_pickups.is_empty() || removed_pickups.read().count() > 0;
+    let Ok((mut minimap, mut visibility)) = minimap_query.single_mut() else {
+        return;
+    };
+
+    let target = if settings.enabled && collision_map.is_some() {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    visibility.set_if_neq(target);
+    let Some(map) = collision_map.filter(|_| settings.enabled) else {
+        return;
+    };
+    let Ok((player_transform, facing)) = player_query.single() else {
+        return;
+    };
+
+    let view = MinimapView {
+        center: map.world_to_grid(player_transform.translation.truncate()),
+        facing: *facing,
+        pixels_per_tile: settings.pixels_per_tile(),
+    };
+    if minimap.last_view == Some(view)
+        && !map.is_changed()
+        && !settings.is_changed()
+        && !pickups_changed
+    {
+        return;
+    }
+    let Some(image) = images.get_mut(&minimap.image) else {
+        return;
+    };
+    minimap.last_view = Some(view);
+
+    let pixels_per_tile = view.pixels_per_tile as i32;
+    let view_tiles = IMAGE_SIZE as i32 / pixels_per_tile;
+    // 视图左下角的网格坐标
+    let origin = view.center - IVec2::splat(view_tiles / 2);
+    let tile_rect = |grid: IVec2| {
+        let min = (grid - origin) * pixels_per_tile;
+        (min, min + IVec2::splat(pixels_per_tile))
+    };
+
+    // 瓦片
+    for y in 0..=view_tiles {
+        for x in 0..=view_tiles {
+            let grid = origin + IVec2::new(x, y);
+            let color = map
+                .get_tile(grid.x, grid.y)
+                .map_or(BACKGROUND_COLOR, tile_color);
+            let (min, max) = tile_rect(grid);
+            fill_rect(image, min, max, color);
+        }
+    }
+
+    // 拾取物
+    for transform in pickups.iter() {
+        let grid = map.world_to_grid(transform.translation().truncate());
+        let (min, max) = tile_rect(grid);
+        fill_rect(image, min, max, PICKUP_COLOR);
+    }
+
+    // 玩家位置和朝向
+    let center = (view.center - origin) * pixels_per_tile + IVec2::splat(pixels_per_tile / 2);
+    fill_rect(
+        image,
+        center - IVec2::ONE,
+        center + IVec2::splat(2),
+        PLAYER_COLOR,
+    );
+    let direction = facing_direction(view.facing);
+    for step in 2..=pixels_per_tile + 2 {
+        let pixel = center + direction * step;
+        fill_rect(image, pixel, pixel + IVec2::ONE, FACING_COLOR);
+    }
+}
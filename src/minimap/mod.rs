@@ -0,0 +1,33 @@
+// 小地图模块
+//
+// 在屏幕角落显示碰撞地图的缩略图，以及玩家的位置、朝向和剩余的拾取物
+
+mod minimap;
+
+use crate::state::GameState;
+use bevy::prelude::*;
+
+pub use minimap::{Minimap, MinimapSettings};
+
+/// 小地图插件
+///
+/// 按 M 显示或隐藏小地图，按 = / - 缩放
+pub struct MinimapPlugin;
+
+impl Plugin for MinimapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MinimapSettings>()
+            .add_systems(Startup, minimap::spawn_minimap)
+            .add_systems(
+                Update,
+                (
+                    minimap::toggle_minimap,
+                    minimap::zoom_minimap,
+                    minimap::draw_minimap,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnExit(GameState::Playing), minimap::hide_minimap);
+    }
+}
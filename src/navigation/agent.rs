@@ -0,0 +1,238 @@
+use crate::characters::collider::Collider;
+use crate::characters::facing::Facing;
+use crate::characters::physics::{Velocity, terrain_speed_at};
+use crate::characters::state::CharacterState;
+use crate::collision::CollisionMap;
+use crate::config::navigation::{DEFAULT_SPEED, WAYPOINT_TOLERANCE};
+use crate::navigation::grid::NavGrids;
+use bevy::prelude::*;
+use bevy::tasks::futures_lite::future;
+use bevy::tasks::{AsyncComputeTaskPool, Task, block_on};
+
+/// 导航代理组件
+///
+/// 设置 `target` 后在后台计算到目标的路径，然后沿路径设置 [`Velocity`] 移动，
+/// 到达目标后 `target` 会被清空。碰撞地图变化时重新寻路。
+#[derive(Component, Debug, Clone)]
+#[require(Velocity)]
+pub struct NavAgent {
+    /// 目标的世界坐标，None 表示停在原地
+    pub target: Option<Vec2>,
+    /// 移动速度（世界单位/秒）
+    pub speed: f32,
+}
+
+impl Default for NavAgent {
+    fn default() -> Self {
+        Self {
+            target: None,
+            speed: DEFAULT_SPEED,
+        }
+    }
+}
+
+impl NavAgent {
+    /// 创建指定速度的导航代理
+    pub fn new(speed: f32) -> Self {
+        Self {
+            target: None,
+            speed,
+        }
+    }
+
+    /// 设置目标
+    pub fn with_target(mut self, target: Vec2) -> Self {
+        self.target = Some(target);
+        self
+    }
+}
+
+/// 导航路径组件
+///
+/// 寻路完成后插入到代理上，记录剩余的路径点
+#[derive(Component, Debug, Clone)]
+pub struct NavPath {
+    /// 路径点的世界坐标
+    waypoints: Vec<Vec2>,
+    /// 下一个要前往的路径点索引
+    next: usize,
+}
+
+impl NavPath {
+    /// 尚未到达的路径点
+    pub fn remaining(&self) -> &[Vec2] {
+        &self.waypoints[self.next.min(self.waypoints.len())..]
+    }
+}
+
+/// 寻路任务组件
+///
+/// 代理的路径正在后台计算，新的请求会替换（取消）旧的任务
+#[derive(Component)]
+pub struct PathRequest {
+    task: Task<Option<Vec<Vec2>>>,
+}
+
+/// 发起寻路请求
+///
+/// 代理的目标变化或碰撞地图变化时，为有目标的代理在后台任务中寻路。
+/// 目标变化时旧的路径立即作废；碰撞地图变化时在新路径算好之前继续沿旧路径移动。
+///
+/// # 参数
+/// - `commands`: 命令缓冲
+/// - `map`: 碰撞地图（尚未构建时不寻路）
+/// - `grids`: 导航网格缓存
+/// - `agents`: 导航代理
+pub fn request_paths(
+    mut commands: Commands,
+    map: Option<Res<CollisionMap>>,
+    mut grids: ResMut<NavGrids>,
+    agents: Query<(Entity, Ref<NavAgent>, &Transform, Option<&Collider>)>,
+) {
+    let Some(map) = map else {
+        return;
+    };
+    let map_changed = map.is_changed();
+
+    let pool = AsyncComputeTaskPool::get();
+    for (entity, agent, transform, collider) in agents.iter() {
+        let target_changed = agent.is_changed();
+        if !target_changed && !map_changed {
+            continue;
+        }
+
+        let mut entity_commands = commands.entity(entity);
+        if target_changed {
+            entity_commands.remove::<NavPath>();
+        }
+        let Some(target) = agent.target else {
+            entity_commands.remove::<PathRequest>();
+            continue;
+        };
+
+        let (start, radius) = match collider {
+            Some(collider) => (collider.world_position(transform), collider.radius),
+            None => (transform.translation.truncate(), 0.0),
+        };
+        let grid = grids.get_or_build(&map, radius);
+        let task = pool.spawn(async move { grid.find_path(start, target) });
+        entity_commands.insert(PathRequest { task });
+    }
+}
+
+/// 接收寻路结果
+///
+/// 后台任务完成后移除 [`PathRequest`]，找到路径时插入 [`NavPath`]
+pub fn poll_path_requests(
+    mut commands: Commands,
+    mut requests: Query<(Entity, &mut PathRequest, &NavAgent)>,
+) {
+    for (entity, mut request, agent) in requests.iter_mut() {
+        let Some(result) = block_on(future::poll_once(&mut request.task)) else {
+            continue;
+        };
+
+        let mut entity_commands = commands.entity(entity);
+        entity_commands.remove::<PathRequest>();
+        match result {
+            Some(waypoints) => {
+                entity_commands.insert(NavPath { waypoints, next: 0 });
+            }
+            None => {
+                entity_commands.remove::<NavPath>();
+                warn!("No path found for {:?} to {:?}", entity, agent.target);
+            }
+        }
+    }
+}
+
+/// 沿路径移动代理
+///
+/// 依次朝路径点设置速度，靠近路径点后前往下一个，到达终点后停下并清空目标。
+/// 没有路径的代理保持静止。代理有角色状态和朝向时一并更新，使动画跟随移动。
+/// 速度乘以脚下地形的速度倍率，然后再限制不越过路径点，因此快速地形上也不会冲过路径点。
+#[allow(clippy::type_complexity)]
+pub fn follow_paths(
+    mut commands: Commands,
+    time: Res<Time>,
+    map: Option<Res<CollisionMap>>,
+    mut agents: Query<(
+        Entity,
+        &mut NavAgent,
+        Option<&mut NavPath>,
+        &Transform,
+        Option<&Collider>,
+        &mut Velocity,
+        Option<&mut Facing>,
+        Option<&mut CharacterState>,
+    )>,
+) {
+    let dt = time.delta_secs();
+    for (entity, mut agent, path, transform, collider, mut velocity, facing, state) in
+        agents.iter_mut()
+    {
+        let position = match collider {
+            Some(collider) => collider.world_position(transform),
+            None => transform.translation.truncate(),
+        };
+
+        let mut desired = Vec2::ZERO;
+        if let Some(mut path) = path {
+            // 跳过已经到达的路径点
+            while path
+                .remaining()
+                .first()
+                .is_some_and(|waypoint| waypoint.distance(position) <= WAYPOINT_TOLERANCE)
+            {
+                path.next += 1;
+            }
+
+            match path.remaining().first() {
+                Some(waypoint) => {
+                    let offset = *waypoint - position;
+                    // 先应用地形速度，再限制最后一帧不越过路径点
+                    let speed = agent.speed * terrain_speed_at(map.as_deref(), position);
+                    let speed = if dt > 0.0 {
+                        speed.min(offset.length() / dt)
+                    } else {
+                        speed
+                    };
+                    desired = offset.normalize_or_zero() * speed;
+                }
+                None => {
+                    commands.entity(entity).remove::<NavPath>();
+                    agent.target = None;
+                }
+            }
+        }
+
+        apply_steering(desired, &mut velocity, facing, state);
+    }
+}
+
+/// 设置代理的速度，代理有朝向和角色状态时一并更新
+///
+/// # 参数
+/// - `desired`: 期望的速度
+/// - `velocity`: 代理的速度
+/// - `facing`: 代理的朝向（静止时保持不变）
+/// - `state`: 代理的角色状态（跳跃中保持不变）
+pub(super) fn apply_steering(
+    desired: Vec2,
+    velocity: &mut Velocity,
+    facing: Option<Mut<Facing>>,
+    state: Option<Mut<CharacterState>>,
+) {
+    velocity.0 = desired;
+    if let Some(mut facing) = facing.filter(|_| desired != Vec2::ZERO) {
+        facing.set_if_neq(Facing::from_velocity(desired));
+    }
+    if let Some(mut state) = state.filter(|state| state.is_grounded()) {
+        let new_state = if desired == Vec2::ZERO {
+            CharacterState::Idle
+        } else {
+            CharacterState::Walking
+        };
+        state.set_if_neq(new_state);
+    }
+}
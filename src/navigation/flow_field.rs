@@ -0,0 +1,304 @@
+use crate::characters::collider::Collider;
+use crate::characters::facing::Facing;
+use crate::characters::physics::{Velocity, terrain_speed_at};
+use crate::characters::state::CharacterState;
+use crate::collision::CollisionMap;
+use crate::config::navigation::DEFAULT_SPEED;
+use crate::config::player::COLLIDER_RADIUS;
+use crate::navigation::agent::apply_steering;
+use crate::navigation::grid::{NavGrid, NavGrids};
+use bevy::prelude::*;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::sync::Arc;
+
+/// 流场目标标记组件
+///
+/// 流场从该实体所在的瓦片出发计算，同一时间只应有一个目标（通常是玩家）
+#[derive(Component, Debug, Default)]
+pub struct FlowFieldGoal;
+
+/// 流场代理组件
+///
+/// 每帧从 [`FlowField`] 中读取所在瓦片的方向并设置 [`Velocity`]，
+/// 适合大量朝同一目标移动的敌人
+#[derive(Component, Debug, Clone)]
+#[require(Velocity)]
+pub struct FlowFieldAgent {
+    /// 移动速度（世界单位/秒）
+    pub speed: f32,
+}
+
+impl Default for FlowFieldAgent {
+    fn default() -> Self {
+        Self {
+            speed: DEFAULT_SPEED,
+        }
+    }
+}
+
+/// 流场资源
+///
+/// 积分场记录每个瓦片到目标瓦片的最短路径代价，方向场记录每个瓦片朝代价最低的邻居移动的方向。
+/// 目标移动到新的瓦片时增量修复（见 [`FlowField::retarget`]），碰撞地图变化时重新计算，
+/// 代理查询方向是 O(1) 的。
+/// 可通行规则与 A* 寻路相同（按默认碰撞体半径，八方向移动且不切角）。
+#[derive(Resource, Default)]
+pub struct FlowField {
+    /// 计算时使用的导航网格
+    grid: Option<Arc<NavGrid>>,
+    /// 目标的网格坐标
+    goal: Option<IVec2>,
+    /// 积分场，无法到达的瓦片为 u32::MAX
+    costs: Vec<u32>,
+    /// 方向场（单位向量），目标瓦片和无法到达的瓦片为零向量
+    directions: Vec<Vec2>,
+}
+
+impl FlowField {
+    /// 从目标瓦片计算流场
+    ///
+    /// 目标瓦片不要求可以通行，目标贴着障碍物时流场仍然有效
+    ///
+    /// # 参数
+    /// - `grid`: 导航网格
+    /// - `goal`: 目标的网格坐标
+    pub fn compute(grid: Arc<NavGrid>, goal: IVec2) -> Self {
+        let mut field = Self {
+            costs: vec![u32::MAX; grid.len()],
+            directions: vec![Vec2::ZERO; grid.len()],
+            grid: Some(grid.clone()),
+            goal: Some(goal),
+        };
+        if !grid.in_bounds(goal) {
+            return field;
+        }
+
+        // 从目标出发的 Dijkstra，移动代价对称，因此等于各瓦片到目标的代价
+        let goal_index = grid.index(goal);
+        field.costs[goal_index] = 0;
+        field.propagate(&grid, goal_index);
+
+        field.directions = (0..grid.len())
+            .map(|index| field.direction_for(&grid, index))
+            .collect();
+        field
+    }
+
+    /// 把目标移动到新的瓦片并增量修复流场
+    ///
+    /// 旧目标可以通行且新目标在旧流场中可以到达时，任意瓦片先到旧目标再到新目标的代价
+    /// （旧代价加上新目标的旧代价）是新代价的上界。以这些上界为初值，只从新目标出发传播
+    /// 严格更低的代价，最短路径仍然经过旧目标的瓦片不会进入优先队列，方向也保持不变；
+    /// 只有代价降低的瓦片及其邻居重新计算方向。结果与 [`FlowField::compute`] 相同。
+    /// 旧目标或新目标不可通行、新目标在旧流场中无法到达时重新计算整个流场，
+    /// 尚未计算过流场（没有导航网格）时不做任何事。
+    ///
+    /// # 参数
+    /// - `goal`: 新目标的网格坐标
+    pub fn retarget(&mut self, goal: IVec2) {
+        let Some(grid) = self.grid.clone() else {
+            return;
+        };
+        let Some(old_goal) = self.goal else {
+            *self = Self::compute(grid, goal);
+            return;
+        };
+        if goal == old_goal {
+            return;
+        }
+        let offset = if grid.is_passable(old_goal) && grid.is_passable(goal) {
+            self.costs[grid.index(goal)]
+        } else {
+            u32::MAX
+        };
+        if offset == u32::MAX {
+            *self = Self::compute(grid, goal);
+            return;
+        }
+
+        // 经过旧目标到达新目标的代价作为上界
+        for cost in self.costs.iter_mut().filter(|cost| **cost != u32::MAX) {
+            *cost += offset;
+        }
+        let goal_index = grid.index(goal);
+        self.costs[goal_index] = 0;
+        self.goal = Some(goal);
+        let improved = self.propagate(&grid, goal_index);
+
+        // 代价降低的瓦片、它们的邻居和旧目标需要重新选择方向
+        let mut dirty = vec![false; self.directions.len()];
+        for index in improved
+            .into_iter()
+            .chain([goal_index, grid.index(old_goal)])
+        {
+            let cell = grid.grid_at(index);
+            for y in -1..=1 {
+                for x in -1..=1 {
+                    let next = cell + IVec2::new(x, y);
+                    if grid.in_bounds(next) {
+                        dirty[grid.index(next)] = true;
+                    }
+                }
+            }
+        }
+        for (index, dirty) in dirty.into_iter().enumerate() {
+            if dirty {
+                self.directions[index] = self.direction_for(&grid, index);
+            }
+        }
+    }
+
+    /// 从指定瓦片出发传播更低的代价（Dijkstra）
+    ///
+    /// # 返回
+    /// 代价被降低的瓦片索引（不包含起点）
+    fn propagate(&mut self, grid: &NavGrid, start: usize) -> Vec<usize> {
+        let mut improved = Vec::new();
+        let mut open = BinaryHeap::new();
+        open.push(Reverse((self.costs[start], start)));
+        while let Some(Reverse((cost, index))) = open.pop() {
+            if cost > self.costs[index] {
+                continue;
+            }
+            for (next, step_cost) in grid.neighbors(grid.grid_at(index)) {
+                let next_cost = cost + step_cost;
+                let next_index = grid.index(next);
+                if next_cost < self.costs[next_index] {
+                    self.costs[next_index] = next_cost;
+                    improved.push(next_index);
+                    open.push(Reverse((next_cost, next_index)));
+                }
+            }
+        }
+        improved
+    }
+
+    /// 计算瓦片朝代价最低的邻居移动的方向
+    ///
+    /// 候选邻居与积分场使用相同的移动规则（八方向且不切角）。目标瓦片可能不可通行，
+    /// 不会出现在邻居中，因此相邻且不切角时单独作为候选
+    fn direction_for(&self, grid: &NavGrid, index: usize) -> Vec2 {
+        let Some(goal) = self.goal else {
+            return Vec2::ZERO;
+        };
+        let cell = grid.grid_at(index);
+        if self.costs[index] == u32::MAX || cell == goal {
+            return Vec2::ZERO;
+        }
+
+        let to_goal = goal - cell;
+        let goal_step =
+            (to_goal.abs().max_element() == 1 && grid.corner_clear(cell, to_goal)).then_some(goal);
+        grid.neighbors(cell)
+            .map(|(next, _)| next)
+            .chain(goal_step)
+            .min_by_key(|next| self.costs[grid.index(*next)])
+            .map_or(Vec2::ZERO, |next| (next - cell).as_vec2().normalize())
+    }
+
+    /// 目标的网格坐标，尚未计算时返回 None
+    pub fn goal(&self) -> Option<IVec2> {
+        self.goal
+    }
+
+    /// 获取世界坐标所在瓦片的索引
+    fn index_at(&self, world_pos: Vec2) -> Option<usize> {
+        let grid = self.grid.as_ref()?;
+        let cell = grid.world_to_grid(world_pos);
+        grid.in_bounds(cell).then(|| grid.index(cell))
+    }
+
+    /// 获取世界坐标处的移动方向
+    ///
+    /// # 返回
+    /// 朝目标移动的单位向量，位于目标瓦片、无法到达或超出范围时为零向量
+    pub fn direction_at(&self, world_pos: Vec2) -> Vec2 {
+        self.index_at(world_pos)
+            .map_or(Vec2::ZERO, |index| self.directions[index])
+    }
+
+    /// 获取世界坐标处到目标的路径代价（直线移动一个瓦片为 10）
+    ///
+    /// # 返回
+    /// 路径代价，无法到达或超出范围时返回 None
+    pub fn cost_at(&self, world_pos: Vec2) -> Option<u32> {
+        self.index_at(world_pos)
+            .map(|index| self.costs[index])
+            .filter(|cost| *cost != u32::MAX)
+    }
+}
+
+/// 更新流场
+///
+/// 有流场代理时，目标进入新的瓦片后增量修复流场，碰撞地图变化（导航网格重建）后重新计算流场
+///
+/// # 参数
+/// - `map`: 碰撞地图（尚未构建时不计算）
+/// - `grids`: 导航网格缓存
+/// - `field`: 流场
+/// - `goals`: 流场目标
+/// - `agents`: 流场代理
+pub fn update_flow_field(
+    map: Option<Res<CollisionMap>>,
+    mut grids: ResMut<NavGrids>,
+    mut field: ResMut<FlowField>,
+    goals: Query<(&Transform, Option<&Collider>), With<FlowFieldGoal>>,
+    agents: Query<(), With<FlowFieldAgent>>,
+) {
+    let Some(map) = map else {
+        return;
+    };
+    if agents.is_empty() {
+        return;
+    }
+    let Ok((transform, collider)) = goals.single() else {
+        return;
+    };
+
+    let position = match collider {
+        Some(collider) => collider.world_position(transform),
+        None => transform.translation.truncate(),
+    };
+    let grid = grids.get_or_build(&map, COLLIDER_RADIUS);
+    let goal = grid.world_to_grid(position);
+    let grid_changed = field
+        .grid
+        .as_ref()
+        .is_none_or(|current| !Arc::ptr_eq(current, &grid));
+    if grid_changed {
+        *field = FlowField::compute(grid, goal);
+        debug!("Flow field recomputed towards ({}, {})", goal.x, goal.y);
+    } else if field.goal != Some(goal) {
+        field.retarget(goal);
+        debug!("Flow field repaired towards ({}, {})", goal.x, goal.y);
+    }
+}
+
+/// 沿流场移动代理
+///
+/// 代理到达目标瓦片后停下，由角色之间的碰撞分开聚集在目标周围的代理。
+/// 速度乘以脚下地形的速度倍率
+#[allow(clippy::type_complexity)]
+pub fn follow_flow_field(
+    field: Res<FlowField>,
+    map: Option<Res<CollisionMap>>,
+    mut agents: Query<(
+        &FlowFieldAgent,
+        &Transform,
+        Option<&Collider>,
+        &mut Velocity,
+        Option<&mut Facing>,
+        Option<&mut CharacterState>,
+    )>,
+) {
+    for (agent, transform, collider, mut velocity, facing, state) in agents.iter_mut() {
+        let position = match collider {
+            Some(collider) => collider.world_position(transform),
+            None => transform.translation.truncate(),
+        };
+        let speed = agent.speed * terrain_speed_at(map.as_deref(), position);
+        let desired = field.direction_at(position) * speed;
+        apply_steering(desired, &mut velocity, facing, state);
+    }
+}
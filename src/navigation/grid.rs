@@ -0,0 +1,251 @@
+use crate::collision::CollisionMap;
+use crate::config::navigation::MAX_SEARCH_NODES;
+use bevy::prelude::*;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::sync::Arc;
+
+/// 直线移动一个瓦片的代价
+const STRAIGHT_COST: u32 = 10;
+/// 斜向移动一个瓦片的代价（约为 √2 × 10）
+const DIAGONAL_COST: u32 = 14;
+
+/// 八个方向的邻居偏移
+const NEIGHBORS: [IVec2; 8] = [
+    IVec2::new(1, 0),
+    IVec2::new(-1, 0),
+    IVec2::new(0, 1),
+    IVec2::new(0, -1),
+    IVec2::new(1, 1),
+    IVec2::new(1, -1),
+    IVec2::new(-1, 1),
+    IVec2::new(-1, -1),
+];
+
+/// 导航网格
+///
+/// 碰撞地图在某个碰撞体半径下的可通行快照：碰撞体圆心位于瓦片中心时不与障碍物相交的瓦片可以通行。
+/// 快照不借用碰撞地图，可以发送到后台任务中寻路。
+#[derive(Debug)]
+pub struct NavGrid {
+    /// 每个瓦片是否可以通行，地图瓦片展开成一维数组
+    passable: Vec<bool>,
+    /// 网格宽度（瓦片数量）
+    width: i32,
+    /// 网格高度（瓦片数量）
+    height: i32,
+    /// 每个瓦片在世界单位中的大小
+    tile_size: f32,
+    /// 网格原点的世界坐标（左下角）
+    origin: Vec2,
+}
+
+impl NavGrid {
+    /// 根据碰撞地图创建导航网格
+    ///
+    /// # 参数
+    /// - `map`: 碰撞地图
+    /// - `radius`: 代理的碰撞体半径
+    pub fn new(map: &CollisionMap, radius: f32) -> Self {
+        let passable = (0..map.height())
+            .flat_map(|y| (0..map.width()).map(move |x| (x, y)))
+            .map(|(x, y)| map.is_circle_clear(map.grid_to_world(x, y), radius))
+            .collect();
+
+        Self {
+            passable,
+            width: map.width(),
+            height: map.height(),
+            tile_size: map.tile_size(),
+            origin: map.origin(),
+        }
+    }
+
+    /// 检查网格坐标是否在范围内
+    pub fn in_bounds(&self, grid: IVec2) -> bool {
+        grid.x >= 0 && grid.x < self.width && grid.y >= 0 && grid.y < self.height
+    }
+
+    /// 瓦片总数
+    pub fn len(&self) -> usize {
+        self.passable.len()
+    }
+
+    /// 网格为空时返回 true
+    pub fn is_empty(&self) -> bool {
+        self.passable.is_empty()
+    }
+
+    /// 将网格坐标转换为数组索引（调用前需确认坐标在范围内）
+    pub(super) fn index(&self, grid: IVec2) -> usize {
+        (grid.y * self.width + grid.x) as usize
+    }
+
+    /// 将数组索引转换为网格坐标
+    pub(super) fn grid_at(&self, index: usize) -> IVec2 {
+        IVec2::new(index as i32 % self.width, index as i32 / self.width)
+    }
+
+    /// 检查瓦片是否可以通行，超出范围的瓦片不可通行
+    pub fn is_passable(&self, grid: IVec2) -> bool {
+        self.in_bounds(grid) && self.passable[self.index(grid)]
+    }
+
+    /// 将世界坐标转换为网格坐标
+    pub fn world_to_grid(&self, world_pos: Vec2) -> IVec2 {
+        ((world_pos - self.origin) / self.tile_size)
+            .floor()
+            .as_ivec2()
+    }
+
+    /// 将网格坐标转换为世界坐标（瓦片中心点）
+    pub fn grid_to_world(&self, grid: IVec2) -> Vec2 {
+        self.origin + (grid.as_vec2() + 0.5) * self.tile_size
+    }
+
+    /// 遍历可以从指定瓦片一步到达的邻居及移动代价
+    ///
+    /// 允许八个方向移动，斜向移动时两侧的直线邻居都必须可以通行（不能切角）
+    pub(super) fn neighbors(&self, grid: IVec2) -> impl Iterator<Item = (IVec2, u32)> + '_ {
+        NEIGHBORS.into_iter().filter_map(move |offset| {
+            let next = grid + offset;
+            if !self.is_passable(next) || !self.corner_clear(grid, offset) {
+                return None;
+            }
+            if offset.x == 0 || offset.y == 0 {
+                Some((next, STRAIGHT_COST))
+            } else {
+                Some((next, DIAGONAL_COST))
+            }
+        })
+    }
+
+    /// 检查从指定瓦片朝某个方向移动一格时是否切角
+    ///
+    /// 斜向移动时两侧的直线邻居都必须可以通行，直线移动总是返回 true。
+    /// 不检查目标瓦片本身是否可以通行
+    pub(super) fn corner_clear(&self, grid: IVec2, offset: IVec2) -> bool {
+        offset.x == 0
+            || offset.y == 0
+            || (self.is_passable(grid + IVec2::new(offset.x, 0))
+                && self.is_passable(grid + IVec2::new(0, offset.y)))
+    }
+
+    /// 使用 A* 算法寻找从起点到终点的路径
+    ///
+    /// 允许八个方向移动，斜向移动时两侧的直线邻居都必须可以通行（不能切角）。
+    /// 起点所在的瓦片不要求可以通行，代理贴着障碍物时也能找到路径。
+    ///
+    /// # 参数
+    /// - `start`: 起点的世界坐标
+    /// - `goal`: 终点的世界坐标
+    ///
+    /// # 返回
+    /// 依次经过的瓦片中心点（不包含起点所在瓦片，终点为目标所在瓦片的中心），
+    /// 终点不可通行、超出范围或展开节点数超过上限时返回 None
+    pub fn find_path(&self, start: Vec2, goal: Vec2) -> Option<Vec<Vec2>> {
+        let start = self.world_to_grid(start);
+        let goal = self.world_to_grid(goal);
+        if !self.in_bounds(start) || !self.is_passable(goal) {
+            return None;
+        }
+        if start == goal {
+            return Some(vec![self.grid_to_world(goal)]);
+        }
+
+        // 八方向距离，作为启发函数不会高估实际代价
+        let heuristic = |grid: IVec2| {
+            let delta = (goal - grid).abs();
+            let diagonal = delta.x.min(delta.y) as u32;
+            let straight = delta.x.max(delta.y) as u32 - diagonal;
+            diagonal * DIAGONAL_COST + straight * STRAIGHT_COST
+        };
+
+        let size = self.passable.len();
+        let mut open = BinaryHeap::new();
+        let mut costs = vec![u32::MAX; size];
+        let mut came_from = vec![usize::MAX; size];
+        let mut closed = vec![false; size];
+        let start_index = self.index(start);
+        costs[start_index] = 0;
+        open.push(Reverse((heuristic(start), start_index)));
+
+        let mut expanded = 0;
+        while let Some(Reverse((_, index))) = open.pop() {
+            // 同一个瓦片可能以不同代价多次入队，只展开第一次（代价最小）
+            if closed[index] {
+                continue;
+            }
+            closed[index] = true;
+
+            let grid = self.grid_at(index);
+            if grid == goal {
+                return Some(self.reconstruct_path(&came_from, start_index, index));
+            }
+
+            expanded += 1;
+            if expanded > MAX_SEARCH_NODES {
+                return None;
+            }
+
+            let cost = costs[index];
+            for (next, step_cost) in self.neighbors(grid) {
+                let next_cost = cost + step_cost;
+                let next_index = self.index(next);
+                if closed[next_index] || costs[next_index] <= next_cost {
+                    continue;
+                }
+                costs[next_index] = next_cost;
+                came_from[next_index] = index;
+                open.push(Reverse((next_cost + heuristic(next), next_index)));
+            }
+        }
+
+        None
+    }
+
+    /// 从终点沿来源回溯，得到从起点之后到终点的路径点
+    fn reconstruct_path(&self, came_from: &[usize], start: usize, goal: usize) -> Vec<Vec2> {
+        let mut path = Vec::new();
+        let mut current = goal;
+        while current != start {
+            path.push(self.grid_to_world(self.grid_at(current)));
+            current = came_from[current];
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// 导航网格缓存资源
+///
+/// 按碰撞体半径缓存导航网格，碰撞地图变化时清空
+#[derive(Resource, Default)]
+pub struct NavGrids {
+    /// 碰撞体半径（按位存储）到导航网格的映射
+    grids: HashMap<u32, Arc<NavGrid>>,
+}
+
+impl NavGrids {
+    /// 获取指定半径的导航网格，没有缓存时根据碰撞地图创建
+    ///
+    /// # 参数
+    /// - `map`: 碰撞地图
+    /// - `radius`: 代理的碰撞体半径
+    pub fn get_or_build(&mut self, map: &CollisionMap, radius: f32) -> Arc<NavGrid> {
+        self.grids
+            .entry(radius.to_bits())
+            .or_insert_with(|| Arc::new(NavGrid::new(map, radius)))
+            .clone()
+    }
+
+    /// 清空缓存
+    pub fn clear(&mut self) {
+        self.grids.clear();
+    }
+}
+
+/// 碰撞地图变化时清空导航网格缓存
+pub fn clear_nav_grids(mut grids: ResMut<NavGrids>) {
+    grids.clear();
+}
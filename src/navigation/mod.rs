@@ -0,0 +1,42 @@
+// 导航模块
+//
+// 在碰撞地图上用 A* 算法寻路（考虑碰撞体半径，八方向移动且不切角），
+// 并让带有导航代理组件的实体沿路径移动；大量朝同一目标移动的代理使用共享的流场
+
+mod agent;
+mod flow_field;
+mod grid;
+
+use crate::characters::collider;
+use crate::collision::CollisionMap;
+use crate::state::GameState;
+use bevy::prelude::*;
+
+pub use agent::{NavAgent, NavPath, PathRequest};
+pub use flow_field::{FlowField, FlowFieldAgent, FlowFieldGoal};
+pub use grid::{NavGrid, NavGrids};
+
+pub struct NavigationPlugin;
+
+impl Plugin for NavigationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NavGrids>()
+            .init_resource::<FlowField>()
+            .add_systems(
+                Update,
+                (
+                    grid::clear_nav_grids.run_if(resource_exists_and_changed::<CollisionMap>),
+                    // 先接收旧的寻路结果再发起新的请求，避免旧结果覆盖新目标
+                    agent::poll_path_requests,
+                    agent::request_paths,
+                    agent::follow_paths,
+                    flow_field::update_flow_field,
+                    flow_field::follow_flow_field,
+                )
+                    .chain()
+                    // 在移动验证之前设置速度
+                    .before(collider::validate_movement)
+                    .run_if(in_state(GameState::Playing)),
+            );
+    }
+}
@@ -1,3 +1,4 @@
+use crate::rendering::YSort;
 use bevy::prelude::*;
 
 /// 粒子组件 - 定义单个粒子的属性和行为
@@ -110,6 +111,8 @@ pub struct ParticleEmitter {
     pub one_shot: bool,
     /// 是否已生成过
     pub has_spawned: bool,
+    /// 粒子的深度排序，为 None 时粒子绘制在固定的粒子层
+    pub y_sort: Option<YSort>,
 }
 
 impl ParticleEmitter {
@@ -122,6 +125,7 @@ impl ParticleEmitter {
             active: true,
             one_shot: false,
             has_spawned: false,
+            y_sort: None,
         }
     }
 
@@ -130,6 +134,12 @@ impl ParticleEmitter {
         self.one_shot = true;
         self
     }
+
+    /// 让粒子按脚下的 y 坐标与角色和道具一起排序（例如地面上的脚步尘土）
+    pub fn y_sorted(mut self, y_sort: YSort) -> Self {
+        self.y_sort = Some(y_sort);
+        self
+    }
 }
 
 /// 粒子配置 - 定义粒子的生成参数
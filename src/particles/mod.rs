@@ -17,10 +17,15 @@ impl Plugin for ParticlesPlugin {
         info!("Initializing ParticlesPlugin");
         // 注册粒子材质插件
         app.add_plugins(Material2dPlugin::<ParticleMaterial>::default())
-            // 注册粒子系统：更新发射器、更新粒子、清理完成发射器
+            // 注册粒子系统：生成脚步粒子、更新发射器、更新粒子、清理完成发射器
             .add_systems(
                 Update,
-                (update_emitters, update_particles, cleanup_finished_emitters)
+                (
+                    spawn_footstep_particles,
+                    update_emitters,
+                    update_particles,
+                    cleanup_finished_emitters,
+                )
                     .chain()
                     .run_if(in_state(GameState::Playing)),
             );
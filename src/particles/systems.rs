@@ -1,5 +1,9 @@
+use crate::characters::footsteps::Footstep;
+use crate::collision::FootstepSurface;
+use crate::map::generate::PROPS_LAYER;
 use crate::particles::components::{EmissionShape, Particle, ParticleConfig, ParticleEmitter};
 use crate::particles::material::ParticleMaterial;
+use crate::rendering::{YSort, YSortOrigin};
 use bevy::prelude::*;
 use rand::Rng;
 
@@ -7,6 +11,7 @@ use rand::Rng;
 pub fn update_emitters(
     mut commands: Commands,
     time: Res<Time>,
+    origin: Res<YSortOrigin>,
     mut emitters: Query<(Entity, &mut ParticleEmitter, &GlobalTransform)>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ParticleMaterial>>,
@@ -43,6 +48,8 @@ pub fn update_emitters(
                     &mut materials,
                     Some(entity),
                     i,
+                    emitter.y_sort,
+                    *origin,
                 );
             }
 
@@ -55,6 +62,9 @@ pub fn update_emitters(
 }
 
 /// 生成单个粒子
+///
+/// 发射器设置了深度排序时粒子带有同样的 [`YSort`]（按 `origin` 计算初始深度），否则绘制在固定的粒子层
+#[allow(clippy::too_many_arguments)]
 pub fn spawn_particle(
     commands: &mut Commands,
     config: &ParticleConfig,
@@ -64,6 +74,8 @@ pub fn spawn_particle(
     materials: &mut ResMut<Assets<ParticleMaterial>>,
     _owner: Option<Entity>,
     _particle_index: u32,
+    y_sort: Option<YSort>,
+    origin: YSortOrigin,
 ) {
     // 计算随机属性值
     let lifetime =
@@ -104,7 +116,10 @@ pub fn spawn_particle(
     let mut position = emitter_position + emission_offset;
 
     // 设置渲染层级
-    position.z = 25.0;
+    position.z = match y_sort {
+        Some(y_sort) => y_sort.depth(&Transform::from_translation(position), origin),
+        None => 25.0,
+    };
 
     // 计算颜色渐变
     let start_color = config.color;
@@ -137,12 +152,15 @@ pub fn spawn_particle(
     let material = materials.add(ParticleMaterial::new(start_color));
 
     // 生成粒子实体
-    commands.spawn((
+    let mut particle_entity = commands.spawn((
         particle,
         Mesh2d(mesh),
         MeshMaterial2d(material),
         Transform::from_translation(position),
     ));
+    if let Some(y_sort) = y_sort {
+        particle_entity.insert(y_sort);
+    }
 }
 
 /// 更新粒子系统 - 更新粒子位置、旋转、颜色和缩放
@@ -213,3 +231,58 @@ pub fn cleanup_finished_emitters(
         }
     }
 }
+
+/// 获取地面类型对应的脚步粒子配置
+///
+/// # 返回
+/// 粒子配置和粒子数量，草地和岩石上不产生粒子
+fn footstep_particles(surface: FootstepSurface) -> Option<(ParticleConfig, u32)> {
+    let (color, speed, count) = match surface {
+        // 尘土
+        FootstepSurface::Dirt => (Color::srgba(0.55, 0.42, 0.3, 0.8), 30.0, 3),
+        // 水花
+        FootstepSurface::Water => (Color::srgba(0.6, 0.8, 1.0, 0.9), 60.0, 4),
+        FootstepSurface::Grass | FootstepSurface::Stone => return None,
+    };
+    let config = ParticleConfig {
+        lifetime: 0.4,
+        lifetime_variance: 0.1,
+        speed,
+        speed_variance: speed * 0.3,
+        direction: Vec3::Y,
+        direction_variance: 1.2,
+        scale: 0.3,
+        scale_variance: 0.1,
+        color,
+        angular_velocity: 0.0,
+        angular_velocity_variance: 1.0,
+        acceleration: Vec3::new(0.0, -120.0, 0.0),
+        emission_shape: EmissionShape::Circle { radius: 6.0 },
+    };
+    Some((config, count))
+}
+
+/// 脚步粒子系统 - 根据脚步的地面类型生成尘土或水花
+///
+/// 粒子与角色和道具在同一层按 y 坐标排序，绘制在地面瓦片之上，并被前方的角色和道具遮挡
+pub fn spawn_footstep_particles(
+    mut commands: Commands,
+    origin: Res<YSortOrigin>,
+    mut footsteps: MessageReader<Footstep>,
+) {
+    for footstep in footsteps.read() {
+        let Some((config, count)) = footstep_particles(footstep.surface) else {
+            continue;
+        };
+        let y_sort = YSort::new(PROPS_LAYER);
+        let mut transform = Transform::from_translation(footstep.position.extend(0.0));
+        transform.translation.z = y_sort.depth(&transform, *origin);
+        commands.spawn((
+            ParticleEmitter::new(0.016, count, config)
+                .one_shot()
+                .y_sorted(y_sort),
+            transform,
+            GlobalTransform::from(transform),
+        ));
+    }
+}
@@ -0,0 +1,33 @@
+// 渲染模块
+//
+// 提供世界实体的通用深度排序（Y-sort），让角色、拾取物和道具按脚下位置正确遮挡
+
+mod y_sort;
+
+use crate::state::GameState;
+use bevy::prelude::*;
+use bevy::transform::TransformSystems;
+
+pub use y_sort::{YSort, YSortOrigin, y_sort_local_offset, y_sort_offset};
+
+/// 渲染插件
+///
+/// 每帧根据 [`YSort`] 组件更新实体的 z 值，并在玩家进入另一行区块时移动深度排序原点
+pub struct RenderingPlugin;
+
+impl Plugin for RenderingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<YSortOrigin>().add_systems(
+            PostUpdate,
+            (
+                y_sort::update_y_sort_origin,
+                y_sort::update_chunk_depth,
+                y_sort::update_y_sort,
+            )
+                .chain()
+                // 在变换传播之前更新深度，使本帧的移动立即生效
+                .before(TransformSystems::Propagate)
+                .run_if(in_state(GameState::Playing)),
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use crate::characters::input::Player;
+use crate::config::map::{NODE_SIZE_Z, Y_SORT_RANGE};
+use crate::map::chunk::{MapChunk, chunk_origin, world_to_chunk};
+use bevy::prelude::*;
+
+/// 深度排序原点资源
+///
+/// 深度按相对于该 y 坐标的距离计算，原点固定在玩家所在区块的下边缘，
+/// 只在玩家进入另一行区块时移动。z 值因此始终很小，
+/// 相距一个像素的 y 坐标在整个排序范围内都有不同的深度，不受世界坐标大小影响
+#[derive(Resource, Debug, Default, Copy, Clone, PartialEq)]
+pub struct YSortOrigin(pub f32);
+
+/// 深度排序组件
+///
+/// 按实体“脚下”的 y 坐标计算 z 值：越靠下的实体绘制在越上层。
+/// 与地图瓦片使用相同的公式（见 [`y_sort_offset`]），因此角色、投射物、拾取物
+/// 可以和同一地形层的道具（例如多格大树）正确地相互遮挡。
+///
+/// 只作用于没有父实体的实体，地图瓦片在生成时已经按相同公式设置好 z 值。
+#[derive(Component, Debug, Copy, Clone, PartialEq)]
+pub struct YSort {
+    /// 参与排序的地形层索引（z 基准为 `layer * NODE_SIZE_Z`）
+    pub layer: u32,
+    /// 锚点（脚下）相对于实体原点的 y 偏移，使用未缩放的本地单位
+    pub anchor: f32,
+}
+
+impl YSort {
+    /// 创建深度排序组件，锚点位于实体原点
+    ///
+    /// # 参数
+    /// - `layer`: 参与排序的地形层索引
+    pub fn new(layer: u32) -> Self {
+        Self { layer, anchor: 0.0 }
+    }
+
+    /// 设置锚点偏移
+    ///
+    /// # 参数
+    /// - `anchor`: 脚下相对于实体原点的 y 偏移（例如精灵高度的一半取负值）
+    pub fn with_anchor(mut self, anchor: f32) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    /// 计算实体在指定变换下的 z 值
+    ///
+    /// # 参数
+    /// - `transform`: 实体的变换
+    /// - `origin`: 深度排序原点（[`YSortOrigin`]）
+    pub fn depth(&self, transform: &Transform, origin: YSortOrigin) -> f32 {
+        let anchor_y = transform.translation.y + self.anchor * transform.scale.y;
+        NODE_SIZE_Z * self.layer as f32 + y_sort_offset(anchor_y, origin)
+    }
+}
+
+/// 计算世界 y 坐标对应的深度偏移
+///
+/// 以排序原点为中心，`[-Y_SORT_RANGE / 2, Y_SORT_RANGE / 2]` 线性映射到 `[NODE_SIZE_Z, 0]`，
+/// 深度不依赖区块，跨越区块边界时不会跳变。超出范围的值会被截断，保证偏移不会越过相邻地形层。
+/// 排序范围覆盖所有可能加载的区块，因此截断只影响已经卸载的位置
+///
+/// # 参数
+/// - `y`: 锚点的世界 y 坐标
+/// - `origin`: 深度排序原点
+///
+/// # 返回
+/// 深度偏移，越靠下越大
+pub fn y_sort_offset(y: f32, origin: YSortOrigin) -> f32 {
+    let t = ((y - origin.0) / Y_SORT_RANGE + 0.5).clamp(0.0, 1.0);
+    NODE_SIZE_Z * (1.0 - t)
+}
+
+/// 计算相对于区块原点的深度偏移
+///
+/// 地图瓦片在生成时使用：瓦片的 z 值只包含相对于所在区块下边缘的偏移，
+/// 区块实体的 z 值（[`update_chunk_depth`]）补上区块下边缘的 [`y_sort_offset`]，
+/// 两者相加与直接按世界 y 坐标计算的深度相同。排序原点移动时只需要更新区块实体
+///
+/// # 参数
+/// - `dy`: 相对于区块下边缘的 y 距离
+pub fn y_sort_local_offset(dy: f32) -> f32 {
+    -NODE_SIZE_Z * dy / Y_SORT_RANGE
+}
+
+/// 玩家进入另一行区块时移动深度排序原点
+pub fn update_y_sort_origin(
+    mut origin: ResMut<YSortOrigin>,
+    player_query: Query<&Transform, With<Player>>,
+) {
+    let Ok(player_transform) = player_query.single() else {
+        return;
+    };
+    let chunk = world_to_chunk(player_transform.translation.truncate());
+    origin.set_if_neq(YSortOrigin(chunk_origin(IVec2::new(0, chunk.y)).y));
+}
+
+/// 根据区块下边缘的世界 y 坐标设置区块实体的 z 值
+///
+/// 新生成的区块和排序原点移动后的所有区块都会更新，区块中的瓦片随父实体一起移动
+pub fn update_chunk_depth(
+    origin: Res<YSortOrigin>,
+    mut chunks: Query<(Ref<MapChunk>, &mut Transform)>,
+) {
+    for (chunk, mut transform) in chunks.iter_mut() {
+        if !origin.is_changed() && !chunk.is_added() {
+            continue;
+        }
+        let z = y_sort_offset(chunk_origin(chunk.coord).y, *origin);
+        if transform.translation.z != z {
+            transform.translation.z = z;
+        }
+    }
+}
+
+/// 根据锚点更新实体的 z 值
+///
+/// 排序原点移动后更新所有实体，否则只更新变换或排序设置改变过的实体
+pub fn update_y_sort(
+    origin: Res<YSortOrigin>,
+    mut query: Query<(&mut Transform, Ref<YSort>), Without<ChildOf>>,
+) {
+    for (mut transform, y_sort) in query.iter_mut() {
+        if !origin.is_changed() && !transform.is_changed() && !y_sort.is_changed() {
+            continue;
+        }
+        let z = y_sort.depth(&transform, *origin);
+        // 只在 z 值变化时写入，避免每帧触发变更检测
+        if transform.translation.z != z {
+            transform.translation.z = z;
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use crate::config::map::TILE_SIZE;
+use crate::map::chunk::world_to_cell;
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+/// 空间索引标记组件
+///
+/// 带有该组件的实体会按全局位置加入 [`SpatialIndex`]，移动或销毁时自动更新
+#[derive(Component, Debug, Copy, Clone, Default)]
+pub struct SpatialTracked;
+
+/// 空间索引资源
+///
+/// 以瓦片为单位把实体分桶，网格与碰撞地图的瓦片对齐（出生区块左下角为原点）
+#[derive(Resource, Debug, Default)]
+pub struct SpatialIndex {
+    /// 网格坐标到该网格内实体的映射
+    cells: HashMap<IVec2, Vec<Entity>>,
+    /// 实体到其所在网格和位置的映射
+    entries: HashMap<Entity, (IVec2, Vec2)>,
+}
+
+impl SpatialIndex {
+    /// 插入实体或更新实体的位置
+    ///
+    /// # 参数
+    /// - `entity`: 实体
+    /// - `position`: 实体的世界坐标
+    pub fn insert(&mut self, entity: Entity, position: Vec2) {
+        let cell = world_to_cell(position);
+        match self.entries.insert(entity, (cell, position)) {
+            // 仍在同一网格内，只需要更新位置
+            Some((old_cell, _)) if old_cell == cell => return,
+            Some((old_cell, _)) => self.remove_from_cell(old_cell, entity),
+            None => {}
+        }
+        self.cells.entry(cell).or_default().push(entity);
+    }
+
+    /// 从索引中移除实体
+    pub fn remove(&mut self, entity: Entity) {
+        if let Some((cell, _)) = self.entries.remove(&entity) {
+            self.remove_from_cell(cell, entity);
+        }
+    }
+
+    fn remove_from_cell(&mut self, cell: IVec2, entity: Entity) {
+        if let Some(entities) = self.cells.get_mut(&cell) {
+            entities.retain(|other| *other != entity);
+            if entities.is_empty() {
+                self.cells.remove(&cell);
+            }
+        }
+    }
+
+    /// 获取实体在索引中的位置
+    pub fn position(&self, entity: Entity) -> Option<Vec2> {
+        self.entries.get(&entity).map(|(_, position)| *position)
+    }
+
+    /// 索引中的实体数量
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// 索引为空时返回 true
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 清空索引
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.entries.clear();
+    }
+
+    /// 遍历与世界坐标矩形相交的所有网格中的实体（未按实际位置过滤）
+    fn candidates(&self, min: Vec2, max: Vec2) -> impl Iterator<Item = (Entity, Vec2)> + '_ {
+        let min_cell = world_to_cell(min);
+        let max_cell = world_to_cell(max);
+        (min_cell.y..=max_cell.y)
+            .flat_map(move |y| (min_cell.x..=max_cell.x).map(move |x| IVec2::new(x, y)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .filter_map(|entity| {
+                self.entries
+                    .get(entity)
+                    .map(|(_, position)| (*entity, *position))
+            })
+    }
+
+    /// 查询圆形范围内的实体
+    ///
+    /// # 参数
+    /// - `center`: 圆心的世界坐标
+    /// - `radius`: 半径
+    ///
+    /// # 返回
+    /// 范围内的实体及其位置（无序）
+    pub fn within_radius(
+        &self,
+        center: Vec2,
+        radius: f32,
+    ) -> impl Iterator<Item = (Entity, Vec2)> + '_ {
+        let radius_sq = radius * radius;
+        self.candidates(center - Vec2::splat(radius), center + Vec2::splat(radius))
+            .filter(move |(_, position)| position.distance_squared(center) <= radius_sq)
+    }
+
+    /// 查询矩形范围内的实体
+    ///
+    /// # 参数
+    /// - `rect`: 世界坐标矩形（包含边界）
+    ///
+    /// # 返回
+    /// 范围内的实体及其位置（无序）
+    pub fn within_rect(&self, rect: Rect) -> impl Iterator<Item = (Entity, Vec2)> + '_ {
+        self.candidates(rect.min, rect.max)
+            .filter(move |(_, position)| {
+                position.cmpge(rect.min).all() && position.cmple(rect.max).all()
+            })
+    }
+
+    /// 查询距离最近的 K 个实体
+    ///
+    /// 从圆心所在的网格开始一圈一圈向外搜索，已找到 K 个实体且更外圈不可能更近时停止
+    ///
+    /// # 参数
+    /// - `center`: 查询点的世界坐标
+    /// - `k`: 最多返回的实体数量
+    ///
+    /// # 返回
+    /// 按距离从近到远排序的实体及其位置
+    pub fn nearest(&self, center: Vec2, k: usize) -> Vec<(Entity, Vec2)> {
+        let mut found: Vec<(Entity, Vec2)> = Vec::new();
+        if k == 0 || self.entries.is_empty() {
+            return found;
+        }
+
+        let by_distance = |(_, a): &(Entity, Vec2), (_, b): &(Entity, Vec2)| {
+            a.distance_squared(center)
+                .total_cmp(&b.distance_squared(center))
+        };
+        let center_cell = world_to_cell(center);
+        let mut visited = 0;
+        let mut ring = 0;
+        loop {
+            for cell in ring_cells(center_cell, ring) {
+                let Some(entities) = self.cells.get(&cell) else {
+                    continue;
+                };
+                visited += entities.len();
+                found.extend(
+                    entities
+                        .iter()
+                        .filter_map(|entity| self.position(*entity).map(|pos| (*entity, pos))),
+                );
+            }
+
+            if visited >= self.entries.len() {
+                break;
+            }
+            if found.len() >= k {
+                // 第 ring + 1 圈及更外的网格到查询点的距离至少为 ring 个瓦片
+                found.select_nth_unstable_by(k - 1, by_distance);
+                if found[k - 1].1.distance(center) <= ring as f32 * TILE_SIZE {
+                    break;
+                }
+            }
+            ring += 1;
+        }
+
+        found.sort_by(by_distance);
+        found.truncate(k);
+        found
+    }
+}
+
+/// 遍历与中心网格切比雪夫距离恰好为 `ring` 的所有网格
+fn ring_cells(center: IVec2, ring: i32) -> impl Iterator<Item = IVec2> {
+    (-ring..=ring)
+        .flat_map(move |y| (-ring..=ring).map(move |x| IVec2::new(x, y)))
+        .filter(move |offset| offset.x.abs() == ring || offset.y.abs() == ring)
+        .map(move |offset| center + offset)
+}
+
+/// 更新空间索引
+///
+/// 先移除已销毁或移除了 [`SpatialTracked`] 的实体，再更新新增或移动过的实体
+#[allow(clippy::type_complexity)]
+pub fn update_spatial_index(
+    mut index: ResMut<SpatialIndex>,
+    mut removed: RemovedComponents<SpatialTracked>,
+    moved: Query<(Entity, &GlobalTransform), (With<SpatialTracked>, Changed<GlobalTransform>)>,
+) {
+    for entity in removed.read() {
+        index.remove(entity);
+    }
+    for (entity, transform) in moved.iter() {
+        index.insert(entity, transform.translation().truncate());
+    }
+}
@@ -0,0 +1,25 @@
+// 空间索引模块
+//
+// 按与碰撞地图对齐的网格把实体分桶，提供半径、矩形和最近 K 个实体的邻近查询
+
+mod index;
+
+use bevy::prelude::*;
+use bevy::transform::TransformSystems;
+
+pub use index::{SpatialIndex, SpatialTracked};
+
+/// 空间索引插件
+///
+/// 每帧在变换传播之后，根据带有 [`SpatialTracked`] 组件的实体的全局位置更新 [`SpatialIndex`]
+pub struct SpatialPlugin;
+
+impl Plugin for SpatialPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SpatialIndex>().add_systems(
+            PostUpdate,
+            // 使用传播后的全局位置，区块的子实体（例如拾取物）也能正确索引
+            index::update_spatial_index.after(TransformSystems::Propagate),
+        );
+    }
+}
@@ -0,0 +1,51 @@
+use crate::map::WorldSeed;
+use crate::map::generate::GenerationAborted;
+use bevy::prelude::*;
+
+#[derive(Component)]
+pub struct ErrorScreen;
+
+pub fn spawn_error_screen(
+    mut commands: Commands,
+    seed: Res<WorldSeed>,
+    aborted: Option<Res<GenerationAborted>>,
+) {
+    let reason = aborted
+        .map(|aborted| aborted.reason.clone())
+        .unwrap_or_else(|| "unknown error".to_string());
+
+    commands
+        .spawn((
+            ErrorScreen,
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            BackgroundColor(Color::srgb(0.15, 0.05, 0.05)),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(format!(
+                    "MAP GENERATION FAILED\n\n{}\n\nSeed: {}\n\nPress ESC to quit",
+                    reason, seed.value
+                )),
+                TextFont {
+                    font_size: 36.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                TextLayout::new_with_justify(Justify::Center),
+            ));
+        });
+
+    info!("Error screen spawned");
+}
+
+pub fn quit_on_escape(input: Res<ButtonInput<KeyCode>>, mut exit: MessageWriter<AppExit>) {
+    if input.just_pressed(KeyCode::Escape) {
+        exit.write(AppExit::error());
+    }
+}
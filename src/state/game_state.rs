@@ -6,4 +6,6 @@ pub enum GameState {
     Loading,
     Playing,
     Paused,
+    /// 地图生成失败，显示错误界面
+    GenerationFailed,
 }
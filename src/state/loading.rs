@@ -6,6 +6,37 @@ pub struct LoadingScreen;
 #[derive(Component)]
 pub struct LoadingText;
 
+/// 加载阶段资源
+///
+/// 按顺序记录加载进度，所有阶段完成后才进入游戏
+#[derive(Resource, Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum LoadingStage {
+    /// 加载角色列表、地形规则和瓦片集定义
+    #[default]
+    Assets,
+    /// 运行地图生成器
+    Generating,
+    /// 加载瓦片图图像
+    TilesetImages,
+    /// 构建碰撞地图
+    CollisionMap,
+    /// 全部完成
+    Done,
+}
+
+impl LoadingStage {
+    /// 加载界面上显示的阶段描述
+    pub fn label(&self) -> &'static str {
+        match self {
+            LoadingStage::Assets => "Loading assets",
+            LoadingStage::TilesetImages => "Loading tileset images",
+            LoadingStage::Generating => "Generating map",
+            LoadingStage::CollisionMap => "Building collision map",
+            LoadingStage::Done => "Starting",
+        }
+    }
+}
+
 pub fn spawn_loading_screen(mut commands: Commands) {
     commands
         .spawn((
@@ -34,10 +65,14 @@ pub fn spawn_loading_screen(mut commands: Commands) {
     info!("Loading screen spawned");
 }
 
-pub fn animate_loading(time: Res<Time>, mut query: Query<&mut Text, With<LoadingText>>) {
+pub fn animate_loading(
+    time: Res<Time>,
+    stage: Res<LoadingStage>,
+    mut query: Query<&mut Text, With<LoadingText>>,
+) {
     for mut text in query.iter_mut() {
         let dots = (time.elapsed_secs() * 2.0) as usize % 4;
-        **text = format!("Loading {}", ".".repeat(dots));
+        **text = format!("{} {}", stage.label(), ".".repeat(dots));
     }
 }
 
@@ -1,33 +1,55 @@
 use crate::characters::config::CharactersList;
 use crate::characters::spawn::CharactersListResource;
+use crate::collision::{CollisionMap, CollisionMapBuilt};
+use crate::map::assets::TilemapHandles;
+use crate::map::generate::{MapDataHandles, WorldGenerator};
 use bevy::prelude::*;
 
+mod error;
 mod game_state;
 mod loading;
 mod pause;
 
 pub use game_state::GameState;
+pub use loading::LoadingStage;
 
 pub struct StatePlugin;
 
 impl Plugin for StatePlugin {
     fn build(&self, app: &mut App) {
         app.init_state::<GameState>()
+            .init_resource::<LoadingStage>()
             .add_systems(OnEnter(GameState::Loading), loading::spawn_loading_screen)
             .add_systems(
                 Update,
                 (check_assets_loaded, loading::animate_loading)
+                    .chain()
                     .run_if(in_state(GameState::Loading)),
             )
+            .add_systems(OnExit(GameState::Loading), loading::despawn_loading_screen)
+            // 只有加载成功进入游戏时才初始化玩家
             .add_systems(
-                OnExit(GameState::Loading),
+                OnTransition {
+                    exited: GameState::Loading,
+                    entered: GameState::Playing,
+                },
                 (
-                    loading::despawn_loading_screen,
                     crate::characters::spawn::initialize_player_character,
-                ),
+                    // 碰撞地图已在加载阶段构建完成，确保玩家出生在可行走的位置
+                    crate::characters::spawn::place_player_on_walkable_tile,
+                )
+                    .chain(),
             )
             .add_systems(OnEnter(GameState::Paused), pause::spawn_pause_menu)
             .add_systems(OnExit(GameState::Paused), pause::despawn_pause_menu)
+            .add_systems(
+                OnEnter(GameState::GenerationFailed),
+                error::spawn_error_screen,
+            )
+            .add_systems(
+                Update,
+                error::quit_on_escape.run_if(in_state(GameState::GenerationFailed)),
+            )
             .add_systems(
                 Update,
                 toggle_pause.run_if(in_state(GameState::Playing).or(in_state(GameState::Paused))),
@@ -35,16 +57,49 @@ impl Plugin for StatePlugin {
     }
 }
 
+/// 检查加载进度
+///
+/// 依次检查资源文件、地图生成、瓦片图图像和碰撞地图，
+/// 更新当前加载阶段，全部完成后进入游戏状态
+#[allow(clippy::too_many_arguments)]
 fn check_assets_loaded(
+    asset_server: Res<AssetServer>,
     characters_list_res: Option<Res<CharactersListResource>>,
     characters_lists: Res<Assets<CharactersList>>,
+    map_data: Option<Res<MapDataHandles>>,
+    tilemap_handles: Option<Res<TilemapHandles>>,
+    generator: Option<Res<WorldGenerator>>,
+    collision_map: Option<Res<CollisionMap>>,
+    built: Res<CollisionMapBuilt>,
+    mut stage: ResMut<LoadingStage>,
     mut next_state: ResMut<NextState<GameState>>,
 ) {
-    let Some(res) = characters_list_res else {
-        return;
+    let assets_loaded = characters_list_res
+        .is_some_and(|res| characters_lists.get(&res.handle).is_some())
+        && map_data.is_some_and(|handles| {
+            asset_server.is_loaded_with_dependencies(&handles.rules)
+                && asset_server.is_loaded_with_dependencies(&handles.tileset)
+        });
+    let images_loaded = tilemap_handles.is_some_and(|handles| handles.is_loaded(&asset_server));
+
+    let current = if !assets_loaded {
+        LoadingStage::Assets
+    } else if generator.is_none() {
+        LoadingStage::Generating
+    } else if !images_loaded {
+        LoadingStage::TilesetImages
+    } else if !built.0 || collision_map.is_none() {
+        LoadingStage::CollisionMap
+    } else {
+        LoadingStage::Done
     };
 
-    if characters_lists.get(&res.handle).is_some() {
+    if *stage != current {
+        info!("Loading stage: {}", current.label());
+        *stage = current;
+    }
+
+    if current == LoadingStage::Done {
         info!("Assets loaded, transitioning to Playing!");
         next_state.set(GameState::Playing);
     }
@@ -1,9 +1,10 @@
+use crate::map::WorldSeed;
 use bevy::prelude::*;
 
 #[derive(Component)]
 pub struct PauseMenu;
 
-pub fn spawn_pause_menu(mut commands: Commands) {
+pub fn spawn_pause_menu(mut commands: Commands, seed: Res<WorldSeed>) {
     commands
         .spawn((
             PauseMenu,
@@ -18,7 +19,10 @@ pub fn spawn_pause_menu(mut commands: Commands) {
         ))
         .with_children(|parent| {
             parent.spawn((
-                Text::new("PAUSED\n\nPress ESC to resume"),
+                Text::new(format!(
+                    "PAUSED\n\nPress ESC to resume\n\nSeed: {}",
+                    seed.value
+                )),
                 TextFont {
                     font_size: 36.0,
                     ..default()
@@ -0,0 +1,92 @@
+// 区块接缝检查
+//
+// 确保区块卸载后由相邻区块接手共享的边缘网格，重新加载时不会重复生成，
+// 地图上不会留下空洞。
+
+use bevy::math::{IVec2, UVec2};
+use bevy_game::config::map::{GRID_X, GRID_Y};
+use bevy_game::map::chunk::{SeamOwners, chunk_origin_cell};
+use std::collections::HashSet;
+
+/// 区块右边缘的所有列（区块内坐标）
+fn right_column() -> HashSet<UVec2> {
+    (0..GRID_Y).map(|y| UVec2::new(GRID_X - 1, y)).collect()
+}
+
+/// 区块左边缘的所有列（区块内坐标）
+fn left_column() -> HashSet<UVec2> {
+    (0..GRID_Y).map(|y| UVec2::new(0, y)).collect()
+}
+
+/// 两个区块共享的一列世界网格坐标
+fn shared_cells(left: IVec2) -> Vec<IVec2> {
+    let origin = chunk_origin_cell(left);
+    (0..GRID_Y as i32)
+        .map(|y| origin + IVec2::new(GRID_X as i32 - 1, y))
+        .collect()
+}
+
+#[test]
+fn neighbour_skips_seam_owned_by_loaded_chunk() {
+    let mut seams = SeamOwners::default();
+    let a = IVec2::ZERO;
+    let b = IVec2::X;
+
+    assert!(seams.claim(a).is_empty());
+    assert_eq!(seams.claim(b), left_column());
+    for cell in shared_cells(a) {
+        assert_eq!(seams.owner(cell), Some(a));
+    }
+}
+
+#[test]
+fn seam_survives_unload_and_reload() {
+    let mut seams = SeamOwners::default();
+    let a = IVec2::ZERO;
+    let b = IVec2::X;
+    seams.claim(a);
+    seams.claim(b);
+
+    // A 卸载，B 仍然加载：B 接手并重新生成共享的一列
+    let handovers = seams.release(a, |coord| coord == b);
+    assert_eq!(handovers.len(), 1);
+    let columns: HashSet<UVec2> = handovers[&b].iter().copied().collect();
+    assert_eq!(columns, left_column());
+    for cell in shared_cells(a) {
+        assert_eq!(seams.owner(cell), Some(b));
+    }
+
+    // A 重新加载：共享的一列由 B 负责，A 不重复生成
+    assert_eq!(seams.claim(a), right_column());
+    for cell in shared_cells(a) {
+        assert_eq!(seams.owner(cell), Some(b));
+    }
+}
+
+#[test]
+fn corner_is_handed_to_diagonal_neighbour() {
+    let mut seams = SeamOwners::default();
+    let a = IVec2::ZERO;
+    let diagonal = IVec2::ONE;
+    seams.claim(a);
+    seams.claim(diagonal);
+
+    let corner = chunk_origin_cell(diagonal);
+    assert_eq!(seams.owner(corner), Some(a));
+
+    let handovers = seams.release(a, |coord| coord == diagonal);
+    assert_eq!(handovers[&diagonal], vec![UVec2::ZERO]);
+    assert_eq!(seams.owner(corner), Some(diagonal));
+}
+
+#[test]
+fn seam_without_loaded_neighbour_is_released() {
+    let mut seams = SeamOwners::default();
+    let a = IVec2::ZERO;
+    seams.claim(a);
+
+    assert!(seams.release(a, |_| false).is_empty());
+    for cell in shared_cells(a) {
+        assert_eq!(seams.owner(cell), None);
+    }
+}
@@ -0,0 +1,150 @@
+// 连通性检查
+//
+// 确保空白瓦片（没有地形覆盖的网格）不会被当作可行走区域，
+// 开辟路径时只穿过道具和深水。按区块检查时区块边界不是屏障，
+// 开辟的瓦片与区块的加载顺序无关。
+
+use bevy::math::IVec2;
+use bevy_game::collision::{
+    CollisionMap, TileOverrides, TileType, WalkableRegions, ensure_chunk_connectivity,
+    ensure_connectivity,
+};
+use bevy_game::config::map::{GRID_X, GRID_Y, TILE_SIZE};
+use bevy_game::map::chunk::chunk_origin;
+
+/// 创建一张全部为指定瓦片的地图
+fn filled_map(width: i32, height: i32, tile_type: TileType) -> CollisionMap {
+    let mut map = CollisionMap::new(width, height, 64.0, 0.0, 0.0);
+    for y in 0..height {
+        for x in 0..width {
+            map.set_tile(x, y, tile_type);
+        }
+    }
+    map
+}
+
+#[test]
+fn empty_tiles_are_not_a_region() {
+    let map = CollisionMap::new(5, 5, 64.0, 0.0, 0.0);
+    let regions = WalkableRegions::compute(&map);
+    assert_eq!(regions.count(), 0);
+    assert_eq!(regions.region_at(2, 2), None);
+}
+
+#[test]
+fn empty_tiles_do_not_connect_regions() {
+    // 两块草地之间隔着一列空白瓦片
+    let mut map = filled_map(5, 3, TileType::Grass);
+    for y in 0..3 {
+        map.set_tile(2, y, TileType::Empty);
+    }
+
+    let regions = WalkableRegions::compute(&map);
+    assert_eq!(regions.count(), 2);
+    assert_ne!(regions.region_at(0, 1), regions.region_at(4, 1));
+
+    // 空白瓦片不能被开辟，两块区域保持孤立
+    let (stats, carved) = ensure_connectivity(&mut map);
+    assert!(carved.is_empty());
+    assert_eq!(stats.unreachable.len(), 1);
+    assert_eq!(map.get_tile(2, 1), Some(TileType::Empty));
+}
+
+#[test]
+fn carving_turns_water_into_shore() {
+    // 两块草地之间隔着一列深水
+    let mut map = filled_map(5, 3, TileType::Grass);
+    for y in 0..3 {
+        map.set_tile(2, y, TileType::Water);
+    }
+
+    let (stats, carved) = ensure_connectivity(&mut map);
+    assert_eq!(stats.region_count, 1);
+    assert_eq!(carved.len(), 1);
+    assert_eq!(carved[0].from, TileType::Water);
+    assert_eq!(carved[0].to, TileType::Shore);
+    assert_eq!(
+        map.get_tile(carved[0].grid.x, carved[0].grid.y),
+        Some(TileType::Shore)
+    );
+}
+
+/// 创建一个区块的碰撞地图
+///
+/// 草地中有一块被树木包围的孤立草地（位置随区块变化），
+/// 右侧的一列深水把靠近区块边界的草地隔开
+fn chunk_map(coord: IVec2) -> CollisionMap {
+    let origin = chunk_origin(coord);
+    let (width, height) = (GRID_X as i32, GRID_Y as i32);
+    let mut map = CollisionMap::new(width, height, TILE_SIZE, origin.x, origin.y);
+    for y in 0..height {
+        for x in 0..width {
+            map.set_tile(x, y, TileType::Grass);
+        }
+        map.set_tile(width - 3, y, TileType::Water);
+    }
+    let pocket = IVec2::new(4 + coord.x.rem_euclid(3) * 4, 4 + coord.y.rem_euclid(3) * 3);
+    for dy in -1..=1 {
+        for dx in -1..=1 {
+            if dx != 0 || dy != 0 {
+                map.set_tile(pocket.x + dx, pocket.y + dy, TileType::Tree);
+            }
+        }
+    }
+    map
+}
+
+/// 按顺序加载（正数）或卸载（负数，按绝对值减一的索引）区块，返回排序后的所有覆盖
+fn load_in_order(coords: &[IVec2], order: &[i32]) -> Vec<(IVec2, TileType)> {
+    let mut overrides = TileOverrides::default();
+    for step in order {
+        let coord = coords[(step.abs() - 1) as usize];
+        if *step > 0 {
+            overrides.carve_chunk(coord, &mut chunk_map(coord));
+        } else {
+            overrides.remove_chunk(coord);
+        }
+    }
+    let mut tiles: Vec<(IVec2, TileType)> = overrides.iter().collect();
+    tiles.sort_by_key(|(grid, _)| (grid.x, grid.y));
+    tiles
+}
+
+#[test]
+fn chunk_border_is_not_a_barrier() {
+    let mut map = chunk_map(IVec2::ZERO);
+
+    // 整张地图检查时，深水右侧的草地是孤立区域
+    let (stats, _) = ensure_connectivity(&mut chunk_map(IVec2::ZERO));
+    assert_eq!(stats.isolated.len(), 2);
+
+    // 按区块检查时它接触区块边界，可能经由相邻区块连通，只开辟被树木包围的草地
+    let (stats, carved) = ensure_chunk_connectivity(&mut map);
+    assert_eq!(stats.isolated.len(), 1);
+    assert_eq!(carved.len(), 1);
+    assert_eq!(carved[0].from, TileType::Tree);
+    assert!(carved.iter().all(|tile| tile.grid.x < GRID_X as i32 - 3));
+}
+
+#[test]
+fn carved_tiles_do_not_depend_on_load_order() {
+    let coords = [
+        IVec2::new(0, 0),
+        IVec2::new(1, 0),
+        IVec2::new(0, 1),
+        IVec2::new(-1, 1),
+    ];
+
+    let forward = load_in_order(&coords, &[1, 2, 3, 4]);
+    assert_eq!(forward.len(), coords.len());
+
+    // 相反的顺序，以及中途卸载并重新加载区块，得到相同的覆盖
+    assert_eq!(load_in_order(&coords, &[4, 3, 2, 1]), forward);
+    assert_eq!(load_in_order(&coords, &[3, 1, -3, 4, 2, 3]), forward);
+
+    // 卸载的区块不保留开辟的瓦片
+    assert_eq!(
+        load_in_order(&coords, &[1, 2, 3, 4, -2]).len(),
+        coords.len() - 1
+    );
+}
@@ -0,0 +1,140 @@
+// 流场检查
+//
+// 覆盖障碍物旁边的方向（与积分场相同的不切角规则），
+// 以及目标移动后增量修复的结果与重新计算一致。
+
+use bevy::math::{IVec2, Vec2};
+use bevy_game::collision::{CollisionMap, TileType};
+use bevy_game::navigation::{FlowField, NavGrid};
+use std::sync::Arc;
+
+const TILE: f32 = 64.0;
+
+/// 创建一张草地地图，并在指定位置放置岩石
+fn map_with_rocks(width: i32, height: i32, rocks: &[(i32, i32)]) -> CollisionMap {
+    let mut map = CollisionMap::new(width, height, TILE, 0.0, 0.0);
+    for y in 0..height {
+        for x in 0..width {
+            map.set_tile(x, y, TileType::Grass);
+        }
+    }
+    for (x, y) in rocks {
+        map.set_tile(*x, *y, TileType::Rock);
+    }
+    map
+}
+
+/// 瓦片中心的世界坐标
+fn center(x: i32, y: i32) -> Vec2 {
+    Vec2::new((x as f32 + 0.5) * TILE, (y as f32 + 0.5) * TILE)
+}
+
+/// 比对两个流场中每个瓦片的代价和方向
+fn assert_same_field(actual: &FlowField, expected: &FlowField, width: i32, height: i32) {
+    for y in 0..height {
+        for x in 0..width {
+            let pos = center(x, y);
+            assert_eq!(
+                actual.cost_at(pos),
+                expected.cost_at(pos),
+                "cost at ({x}, {y})"
+            );
+            assert_eq!(
+                actual.direction_at(pos),
+                expected.direction_at(pos),
+                "direction at ({x}, {y})"
+            );
+        }
+    }
+}
+
+#[test]
+fn adjacent_goal_is_not_reached_by_cutting_a_corner() {
+    // 目标在右上方，但右边是岩石，必须先向上走
+    let map = map_with_rocks(3, 3, &[(1, 0)]);
+    let grid = Arc::new(NavGrid::new(&map, 0.0));
+    let field = FlowField::compute(grid, IVec2::new(1, 1));
+
+    assert_eq!(field.direction_at(center(0, 0)), Vec2::Y);
+    assert_eq!(field.cost_at(center(0, 0)), Some(20));
+    // 不受岩石影响的对角线仍然直接指向目标
+    assert_eq!(
+        field.direction_at(center(2, 2)),
+        Vec2::new(-1.0, -1.0).normalize()
+    );
+}
+
+#[test]
+fn blocked_goal_is_reached_from_its_neighbours() {
+    // 目标本身不可通行（例如玩家贴着树），相邻的瓦片仍然指向目标
+    let map = map_with_rocks(3, 3, &[(1, 1), (2, 0)]);
+    let grid = Arc::new(NavGrid::new(&map, 0.0));
+    let field = FlowField::compute(grid, IVec2::new(1, 1));
+
+    assert_eq!(field.direction_at(center(0, 1)), Vec2::X);
+    assert_eq!(field.direction_at(center(1, 2)), Vec2::NEG_Y);
+    assert_eq!(
+        field.direction_at(center(0, 0)),
+        Vec2::new(1.0, 1.0).normalize()
+    );
+    assert_eq!(field.direction_at(center(2, 1)), Vec2::NEG_X);
+    // 目标瓦片和岩石瓦片没有方向
+    assert_eq!(field.direction_at(center(1, 1)), Vec2::ZERO);
+    assert_eq!(field.direction_at(center(2, 0)), Vec2::ZERO);
+    assert_eq!(field.cost_at(center(2, 0)), None);
+}
+
+#[test]
+fn directions_lead_around_a_wall() {
+    // 一堵竖墙把目标和代理隔开，只能从上方绕过
+    let map = map_with_rocks(5, 5, &[(2, 0), (2, 1), (2, 2), (2, 3)]);
+    let grid = Arc::new(NavGrid::new(&map, 0.0));
+    let field = FlowField::compute(grid, IVec2::new(4, 0));
+
+    // 紧贴墙的瓦片不能穿过墙，只能向上
+    assert_eq!(field.direction_at(center(1, 2)), Vec2::Y);
+    // 墙顶上方的瓦片斜向绕过墙角时不能切角
+    assert_eq!(field.direction_at(center(1, 3)).x, 0.0);
+    assert_eq!(field.direction_at(center(2, 4)), Vec2::X);
+    assert!(field.cost_at(center(0, 0)).is_some());
+}
+
+#[test]
+fn retarget_matches_full_recompute() {
+    let rocks = [
+        (3, 0),
+        (3, 1),
+        (3, 2),
+        (3, 3),
+        (6, 5),
+        (6, 6),
+        (7, 6),
+        (8, 6),
+        (1, 6),
+        (2, 7),
+        (5, 2),
+    ];
+    let map = map_with_rocks(10, 9, &rocks);
+    let grid = Arc::new(NavGrid::new(&map, 0.0));
+
+    let mut field = FlowField::compute(grid.clone(), IVec2::new(0, 0));
+    // 目标逐格移动、跳跃，以及移动到不可通行的瓦片再离开
+    let goals = [
+        (1, 0),
+        (2, 1),
+        (2, 2),
+        (4, 4),
+        (9, 8),
+        (8, 7),
+        (6, 6),
+        (5, 5),
+        (0, 8),
+    ];
+    for (x, y) in goals {
+        let goal = IVec2::new(x, y);
+        field.retarget(goal);
+        assert_eq!(field.goal(), Some(goal));
+        let expected = FlowField::compute(grid.clone(), goal);
+        assert_same_field(&field, &expected, 10, 9);
+    }
+}
@@ -0,0 +1,97 @@
+// A* 寻路检查
+//
+// 覆盖不切角规则和展开节点数上限。
+
+use bevy::math::Vec2;
+use bevy_game::collision::{CollisionMap, TileType};
+use bevy_game::config::navigation::MAX_SEARCH_NODES;
+use bevy_game::navigation::NavGrid;
+
+const TILE: f32 = 64.0;
+
+/// 创建一张草地地图，并在指定位置放置岩石
+fn map_with_rocks(width: i32, height: i32, rocks: &[(i32, i32)]) -> CollisionMap {
+    let mut map = CollisionMap::new(width, height, TILE, 0.0, 0.0);
+    for y in 0..height {
+        for x in 0..width {
+            map.set_tile(x, y, TileType::Grass);
+        }
+    }
+    for (x, y) in rocks {
+        map.set_tile(*x, *y, TileType::Rock);
+    }
+    map
+}
+
+/// 瓦片中心的世界坐标
+fn center(x: i32, y: i32) -> Vec2 {
+    Vec2::new((x as f32 + 0.5) * TILE, (y as f32 + 0.5) * TILE)
+}
+
+/// 创建蛇形迷宫：偶数行是通道，奇数行是墙，缺口交替出现在最右和最左一列
+///
+/// # 返回
+/// 地图和迷宫终点（最后一条通道的末端）
+fn serpentine(width: i32, corridors: i32) -> (CollisionMap, (i32, i32)) {
+    let height = corridors * 2 - 1;
+    let mut rocks = Vec::new();
+    for row in 0..corridors - 1 {
+        let y = row * 2 + 1;
+        let gap = if row % 2 == 0 { width - 1 } else { 0 };
+        rocks.extend((0..width).filter(|x| *x != gap).map(|x| (x, y)));
+    }
+    let end_x = if corridors % 2 == 0 { 0 } else { width - 1 };
+    (map_with_rocks(width, height, &rocks), (end_x, height - 1))
+}
+
+#[test]
+fn straight_path_excludes_start() {
+    let map = map_with_rocks(5, 1, &[]);
+    let grid = NavGrid::new(&map, 0.0);
+    let path = grid.find_path(center(0, 0), center(3, 0)).expect("path");
+    assert_eq!(path, vec![center(1, 0), center(2, 0), center(3, 0)]);
+}
+
+#[test]
+fn does_not_cut_corners() {
+    // 对角线一侧被岩石挡住时必须绕过拐角
+    let map = map_with_rocks(2, 2, &[(1, 0)]);
+    let grid = NavGrid::new(&map, 0.0);
+    let path = grid.find_path(center(0, 0), center(1, 1)).expect("path");
+    assert_eq!(path, vec![center(0, 1), center(1, 1)]);
+
+    // 两侧都被挡住时不能斜着穿过缝隙
+    let map = map_with_rocks(2, 2, &[(1, 0), (0, 1)]);
+    let grid = NavGrid::new(&map, 0.0);
+    assert!(grid.find_path(center(0, 0), center(1, 1)).is_none());
+}
+
+#[test]
+fn blocked_or_off_grid_goal_has_no_path() {
+    let map = map_with_rocks(4, 4, &[(3, 3)]);
+    let grid = NavGrid::new(&map, 0.0);
+    assert!(grid.find_path(center(0, 0), center(3, 3)).is_none());
+    assert!(grid.find_path(center(0, 0), center(10, 0)).is_none());
+}
+
+#[test]
+fn small_maze_is_solved() {
+    let (map, end) = serpentine(20, 5);
+    let grid = NavGrid::new(&map, 0.0);
+    let path = grid
+        .find_path(center(0, 0), center(end.0, end.1))
+        .expect("path");
+    assert_eq!(path.last(), Some(&center(end.0, end.1)));
+    // 每条通道都要走完
+    assert!(path.len() >= 5 * 20 - 1);
+}
+
+#[test]
+fn search_gives_up_after_max_nodes() {
+    // 通道总长度超过展开节点数上限，终点可以到达但搜索在找到它之前放弃
+    let width = 400;
+    let corridors = (MAX_SEARCH_NODES / width as usize) as i32 + 2;
+    let (map, end) = serpentine(width, corridors);
+    let grid = NavGrid::new(&map, 0.0);
+    assert!(grid.find_path(center(0, 0), center(end.0, end.1)).is_none());
+}
@@ -0,0 +1,42 @@
+// 已拾取的拾取物检查
+//
+// 确保拾取过的网格写入地图存档，加载同一种子的存档后仍然记录为已拾取。
+
+use bevy::math::IVec2;
+use bevy_game::map::save::{MAP_FILE_VERSION, SavedChunks, SavedMap, SavedTiles};
+
+/// 创建只包含已拾取记录的地图存档
+fn saved_map(seed: u64, collected: Vec<(i32, i32)>) -> SavedMap {
+    SavedMap {
+        version: MAP_FILE_VERSION,
+        seed,
+        models: Vec::new(),
+        chunks: Vec::new(),
+        tiles: SavedTiles::default(),
+        overrides: Vec::new(),
+        pickups: Vec::new(),
+        collected,
+    }
+}
+
+#[test]
+fn collected_pickups_survive_save_round_trip() {
+    let map = saved_map(7, vec![(4, 2), (-30, 17)]);
+    let path = std::env::temp_dir().join(format!("collected_{}.ron", std::process::id()));
+    map.write(&path).expect("write map");
+    let loaded = SavedMap::read(&path).expect("read map");
+    let _ = std::fs::remove_file(&path);
+
+    let saved = SavedChunks::from_saved(loaded, &[]).expect("parse map");
+    let collected = saved.collected_pickups(7);
+    assert_eq!(collected.len(), 2);
+    assert!(collected.contains(IVec2::new(4, 2)));
+    assert!(collected.contains(IVec2::new(-30, 17)));
+    assert!(!collected.contains(IVec2::new(2, 4)));
+}
+
+#[test]
+fn other_seed_starts_with_nothing_collected() {
+    let saved = SavedChunks::from_saved(saved_map(7, vec![(4, 2)]), &[]).expect("parse map");
+    assert!(saved.collected_pickups(8).is_empty());
+}
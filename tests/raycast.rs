@@ -0,0 +1,135 @@
+// 射线检测检查
+//
+// 覆盖 DDA 在瓦片边界和对角线上的行为，以及起点或终点在地图范围之外的情况。
+
+use bevy::math::{IVec2, Vec2};
+use bevy_game::collision::{CollisionMap, TileType};
+
+const TILE: f32 = 64.0;
+
+/// 创建一张 10 x 10 的草地地图，并在指定位置放置岩石
+fn map_with_rocks(rocks: &[(i32, i32)]) -> CollisionMap {
+    let mut map = CollisionMap::new(10, 10, TILE, 0.0, 0.0);
+    for y in 0..10 {
+        for x in 0..10 {
+            map.set_tile(x, y, TileType::Grass);
+        }
+    }
+    for (x, y) in rocks {
+        map.set_tile(*x, *y, TileType::Rock);
+    }
+    map
+}
+
+/// 瓦片中心的世界坐标
+fn center(x: i32, y: i32) -> Vec2 {
+    Vec2::new((x as f32 + 0.5) * TILE, (y as f32 + 0.5) * TILE)
+}
+
+#[test]
+fn hits_the_near_face_of_a_blocking_tile() {
+    let map = map_with_rocks(&[(5, 2)]);
+    let hit = map.raycast(center(2, 2), Vec2::X, 1000.0).expect("hit");
+    assert_eq!(hit.grid, IVec2::new(5, 2));
+    assert_eq!(hit.tile_type, TileType::Rock);
+    assert!((hit.point.x - 5.0 * TILE).abs() < 1e-3);
+    assert_eq!(hit.normal, Vec2::NEG_X);
+}
+
+#[test]
+fn origin_on_a_tile_boundary() {
+    // 起点恰好在第 1、2 列之间，向右先进入第 2 列
+    let map = map_with_rocks(&[(3, 0)]);
+    let right = map
+        .raycast(Vec2::new(2.0 * TILE, 32.0), Vec2::X, 1000.0)
+        .expect("hit");
+    assert_eq!(right.grid, IVec2::new(3, 0));
+    assert!((right.point.x - 3.0 * TILE).abs() < 1e-3);
+
+    // 起点恰好在第 2、3 列之间，向左立即越过边界进入第 2 列
+    let map = map_with_rocks(&[(1, 0)]);
+    let left = map
+        .raycast(Vec2::new(3.0 * TILE, 32.0), Vec2::NEG_X, 1000.0)
+        .expect("hit");
+    assert_eq!(left.grid, IVec2::new(1, 0));
+    assert!((left.point.x - 2.0 * TILE).abs() < 1e-3);
+    assert_eq!(left.normal, Vec2::X);
+}
+
+#[test]
+fn diagonal_ray_hits_tile_on_the_diagonal() {
+    let map = map_with_rocks(&[(3, 3)]);
+    let hit = map
+        .raycast(center(0, 0), Vec2::new(1.0, 1.0), 1000.0)
+        .expect("hit");
+    assert_eq!(hit.grid, IVec2::new(3, 3));
+    assert!(hit.point.distance(Vec2::splat(3.0 * TILE)) < 1e-2);
+}
+
+#[test]
+fn diagonal_ray_misses_tiles_beside_the_diagonal() {
+    // 岩石紧贴对角线两侧，但不在射线经过的瓦片上
+    let map = map_with_rocks(&[(2, 0), (0, 2)]);
+    assert!(
+        map.raycast(center(0, 0), Vec2::new(1.0, 1.0), 300.0)
+            .is_none()
+    );
+}
+
+#[test]
+fn stops_at_max_distance() {
+    let map = map_with_rocks(&[(6, 0)]);
+    assert!(map.raycast(center(0, 0), Vec2::X, 4.0 * TILE).is_none());
+    assert!(map.raycast(center(0, 0), Vec2::X, 6.0 * TILE).is_some());
+}
+
+#[test]
+fn origin_inside_a_blocking_tile() {
+    let map = map_with_rocks(&[(4, 4)]);
+    let hit = map.raycast(center(4, 4), Vec2::X, 100.0).expect("hit");
+    assert_eq!(hit.grid, IVec2::new(4, 4));
+    assert_eq!(hit.point, center(4, 4));
+    assert_eq!(hit.normal, Vec2::ZERO);
+}
+
+#[test]
+fn off_map_origin_enters_the_map() {
+    let map = map_with_rocks(&[(2, 0)]);
+    let hit = map
+        .raycast(Vec2::new(-100.0, 32.0), Vec2::X, 1000.0)
+        .expect("hit");
+    assert_eq!(hit.grid, IVec2::new(2, 0));
+    assert!((hit.point.x - 2.0 * TILE).abs() < 1e-3);
+
+    // 进入地图的第一个瓦片就是岩石
+    let map = map_with_rocks(&[(0, 0)]);
+    let hit = map
+        .raycast(Vec2::new(-100.0, 32.0), Vec2::X, 1000.0)
+        .expect("hit");
+    assert_eq!(hit.grid, IVec2::ZERO);
+    assert!((hit.point.x).abs() < 1e-3);
+    assert_eq!(hit.normal, Vec2::NEG_X);
+}
+
+#[test]
+fn off_map_origin_that_never_enters_the_map() {
+    let map = map_with_rocks(&[(0, 0)]);
+    assert!(
+        map.raycast(Vec2::new(-100.0, 32.0), Vec2::NEG_X, 1000.0)
+            .is_none()
+    );
+    // 在到达地图之前就超过了最大距离
+    assert!(
+        map.raycast(Vec2::new(-100.0, 32.0), Vec2::X, 50.0)
+            .is_none()
+    );
+}
+
+#[test]
+fn line_of_sight_requires_both_ends_on_the_map() {
+    let map = map_with_rocks(&[(5, 5)]);
+    assert!(map.line_of_sight(center(1, 1), center(8, 1)));
+    assert!(!map.line_of_sight(center(1, 5), center(8, 5)));
+    assert!(!map.line_of_sight(Vec2::new(-100.0, 32.0), center(3, 0)));
+    assert!(!map.line_of_sight(center(3, 0), Vec2::new(10_000.0, 32.0)));
+}
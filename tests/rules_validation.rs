@@ -0,0 +1,33 @@
+// 地形规则检查
+//
+// 确保仓库中的地形规则与瓦片集一致，规则或精灵改名出错时在测试中发现，
+// 而不是在游戏运行时崩溃或生成缺少某些瓦片的地图。
+
+use bevy_game::map::generate::{RULES_FILE, TILESET_FILE};
+use bevy_game::map::rules::TerrainRules;
+use bevy_game::map::tilemap::TilesetDefinition;
+use bevy_game::map::validate::validate_rules;
+use serde::de::DeserializeOwned;
+use std::fs;
+use std::path::Path;
+
+/// 读取并解析资源目录中的 RON 文件
+fn read_asset<T: DeserializeOwned>(path: &str) -> T {
+    let full_path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src/assets")
+        .join(path);
+    let contents = fs::read_to_string(&full_path)
+        .unwrap_or_else(|err| panic!("failed to read {}: {err}", full_path.display()));
+    ron::from_str(&contents)
+        .unwrap_or_else(|err| panic!("failed to parse {}: {err}", full_path.display()))
+}
+
+#[test]
+fn terrain_rules_match_tileset() {
+    let rules: TerrainRules = read_asset(RULES_FILE);
+    let tileset: TilesetDefinition = read_asset(TILESET_FILE);
+
+    let report = validate_rules(&rules, &tileset);
+    assert!(report.is_ok(), "{report}");
+    assert!(report.models > 0);
+}
@@ -0,0 +1,86 @@
+// 地图存档模型表检查
+//
+// 确保模型表记录每个节点的模型和旋转，保存后重新加载得到相同的模型实例。
+
+use bevy::math::IVec2;
+use bevy_game::config::map::{GRID_X, GRID_Y};
+use bevy_game::map::generate::GRID_Z;
+use bevy_game::map::save::{
+    MAP_FILE_VERSION, ModelTable, SaveError, SavedChunk, SavedChunks, SavedMap, SavedModel,
+    SavedTiles,
+};
+use bevy_procedural_tilemaps::prelude::ModelRotation;
+use bevy_procedural_tilemaps::proc_gen::generator::model::ModelInstance;
+
+/// 当前规则中的模型名称
+fn model_names() -> Vec<String> {
+    vec!["grass".to_string(), "cliff".to_string()]
+}
+
+/// 创建只包含一个区块的地图存档
+fn saved_map(models: Vec<SavedModel>, nodes: Vec<u32>) -> SavedMap {
+    SavedMap {
+        version: MAP_FILE_VERSION,
+        seed: 3,
+        models,
+        chunks: vec![SavedChunk {
+            coord: (0, 0),
+            nodes,
+        }],
+        tiles: SavedTiles::default(),
+        overrides: Vec::new(),
+        pickups: Vec::new(),
+        collected: Vec::new(),
+    }
+}
+
+#[test]
+fn rotated_models_survive_save_round_trip() {
+    let rotations = [
+        ModelRotation::Rot0,
+        ModelRotation::Rot90,
+        ModelRotation::Rot180,
+        ModelRotation::Rot270,
+    ];
+    let node_count = (GRID_X * GRID_Y * GRID_Z) as usize;
+    let instances: Vec<ModelInstance> = (0..node_count)
+        .map(|node| ModelInstance {
+            model_index: node % 2,
+            rotation: rotations[node % rotations.len()],
+        })
+        .collect();
+
+    let names = model_names();
+    let mut table = ModelTable::default();
+    let nodes = instances
+        .iter()
+        .map(|instance| table.index(instance, &names))
+        .collect();
+    let map = saved_map(table.into_models(), nodes);
+    // 同一模型的不同旋转是模型表中的不同项
+    assert_eq!(map.models.len(), 4);
+
+    let path = std::env::temp_dir().join(format!("saved_models_{}.ron", std::process::id()));
+    map.write(&path).expect("write map");
+    let loaded = SavedMap::read(&path).expect("read map");
+    let _ = std::fs::remove_file(&path);
+
+    let saved = SavedChunks::from_saved(loaded, &names).expect("parse map");
+    let data = saved.chunk_data(3, IVec2::ZERO).expect("saved chunk");
+    let reloaded: Vec<ModelInstance> = data.iter().copied().collect();
+    assert_eq!(reloaded, instances);
+}
+
+#[test]
+fn invalid_rotation_is_rejected() {
+    let node_count = (GRID_X * GRID_Y * GRID_Z) as usize;
+    let models = vec![SavedModel {
+        name: "cliff".to_string(),
+        rotation: 45,
+    }];
+    let result = SavedChunks::from_saved(saved_map(models, vec![0; node_count]), &model_names());
+    assert!(matches!(
+        result,
+        Err(SaveError::InvalidRotation { rotation: 45, .. })
+    ));
+}
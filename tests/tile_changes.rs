@@ -0,0 +1,166 @@
+// 运行时瓦片变化检查
+//
+// 确保局部海岸检测双向更新（深水变为海岸、海岸变回深水），局部更新与完整重建的结果一致，
+// 并且运行时改变过的瓦片会写入地图存档。
+
+use bevy::math::IVec2;
+use bevy_game::collision::{
+    CollisionMap, TileChanged, TileOverrides, TileType, apply_tile_change, apply_tile_overrides,
+    reclassify_shore_in,
+};
+use bevy_game::config::map::TILE_SIZE;
+use bevy_game::map::chunk::world_origin;
+use bevy_game::map::save::{MAP_FILE_VERSION, SavedMap, SavedOverride, SavedTiles};
+
+/// 创建一张全部为深水的地图
+fn water_map() -> CollisionMap {
+    let mut map = CollisionMap::new(5, 5, 64.0, 0.0, 0.0);
+    for y in 0..5 {
+        for x in 0..5 {
+            map.set_tile(x, y, TileType::Water);
+        }
+    }
+    map
+}
+
+#[test]
+fn water_next_to_new_land_becomes_shore() {
+    let mut map = water_map();
+    map.set_tile(2, 2, TileType::Grass);
+    reclassify_shore_in(&mut map, IVec2::ONE, IVec2::splat(3), |cell| {
+        cell == IVec2::splat(2)
+    });
+
+    assert_eq!(map.get_tile(2, 2), Some(TileType::Grass));
+    for (x, y) in [(1, 1), (2, 1), (3, 3), (1, 3)] {
+        assert_eq!(map.get_tile(x, y), Some(TileType::Shore));
+    }
+    // 范围之外的瓦片不变
+    assert_eq!(map.get_tile(0, 0), Some(TileType::Water));
+}
+
+#[test]
+fn shore_without_land_reverts_to_water() {
+    let mut map = water_map();
+    map.set_tile(2, 2, TileType::Grass);
+    reclassify_shore_in(&mut map, IVec2::ONE, IVec2::splat(3), |_| false);
+
+    // 陆地被淹没后，周围的海岸失去陆地邻居
+    map.set_tile(2, 2, TileType::Water);
+    reclassify_shore_in(&mut map, IVec2::ONE, IVec2::splat(3), |cell| {
+        cell == IVec2::splat(2)
+    });
+
+    for y in 1..=3 {
+        for x in 1..=3 {
+            assert_eq!(map.get_tile(x, y), Some(TileType::Water), "({x}, {y})");
+        }
+    }
+}
+
+#[test]
+fn fixed_shore_is_kept() {
+    let mut map = water_map();
+    map.set_tile(2, 2, TileType::Shore);
+    reclassify_shore_in(&mut map, IVec2::ONE, IVec2::splat(3), |cell| {
+        cell == IVec2::splat(2)
+    });
+    assert_eq!(map.get_tile(2, 2), Some(TileType::Shore));
+    // 海岸本身不算陆地
+    assert_eq!(map.get_tile(1, 1), Some(TileType::Water));
+}
+
+/// 创建一张有湖、岛和海岸的地图，网格坐标与世界网格坐标相同
+///
+/// ```text
+/// ........
+/// .~~~~~~.
+/// .~~~~~~.
+/// .~~..~~.
+/// .~~..~~.
+/// .~~~~~~.
+/// .~~~~~S.
+/// ........
+/// ```
+fn lake_map() -> CollisionMap {
+    let origin = world_origin();
+    let mut map = CollisionMap::new(8, 8, TILE_SIZE, origin.x, origin.y);
+    for y in 0..8 {
+        for x in 0..8 {
+            let border = x == 0 || y == 0 || x == 7 || y == 7;
+            let island = (3..=4).contains(&x) && (3..=4).contains(&y);
+            let tile = if border || island {
+                TileType::Grass
+            } else {
+                TileType::Water
+            };
+            map.set_tile(x, y, tile);
+        }
+    }
+    map.set_tile(6, 1, TileType::Shore);
+    map
+}
+
+/// 断言两张地图的所有瓦片相同
+fn assert_same_tiles(actual: &CollisionMap, expected: &CollisionMap) {
+    for y in 0..expected.height() {
+        for x in 0..expected.width() {
+            assert_eq!(actual.get_tile(x, y), expected.get_tile(x, y), "({x}, {y})");
+        }
+    }
+}
+
+#[test]
+fn local_update_matches_full_rebuild() {
+    let changes = [
+        // 淹没小岛的一角，再把它变回陆地
+        (IVec2::new(3, 3), TileType::Water),
+        (IVec2::new(4, 4), TileType::Water),
+        (IVec2::new(3, 3), TileType::Dirt),
+        // 湖中央的新陆地和被明确设为深水的湖岸
+        (IVec2::new(2, 5), TileType::Grass),
+        (IVec2::new(1, 1), TileType::Water),
+        // 移除湖岸的陆地
+        (IVec2::new(7, 1), TileType::Water),
+    ];
+
+    let mut local = lake_map();
+    let mut overrides = TileOverrides::default();
+    apply_tile_overrides(&mut local, &overrides);
+
+    for (grid, new_type) in changes {
+        apply_tile_change(&mut local, &mut overrides, &TileChanged { grid, new_type });
+
+        let mut rebuilt = lake_map();
+        apply_tile_overrides(&mut rebuilt, &overrides);
+        assert_same_tiles(&local, &rebuilt);
+    }
+}
+
+#[test]
+fn overrides_survive_save_round_trip() {
+    let map = SavedMap {
+        version: MAP_FILE_VERSION,
+        seed: 42,
+        models: Vec::new(),
+        chunks: Vec::new(),
+        tiles: SavedTiles::default(),
+        overrides: vec![SavedOverride {
+            cell: (3, -1),
+            tile: TileType::Shore.symbol(),
+        }],
+        pickups: Vec::new(),
+        collected: Vec::new(),
+    };
+    let path = std::env::temp_dir().join(format!("tile_overrides_{}.ron", std::process::id()));
+    map.write(&path).expect("write map");
+    let loaded = SavedMap::read(&path).expect("read map");
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(loaded.overrides.len(), 1);
+    assert_eq!(loaded.overrides[0].cell, (3, -1));
+    assert_eq!(
+        TileType::from_symbol(loaded.overrides[0].tile),
+        Some(TileType::Shore)
+    );
+}
@@ -0,0 +1,87 @@
+// 深度排序检查
+//
+// 确保深度只取决于世界 y 坐标：越靠下越靠前，跨越区块边界时不会跳变，
+// 区块中的瓦片与直接按世界 y 坐标计算的深度一致，排序范围边缘相距一个像素的位置仍有不同的深度。
+
+use bevy::math::{IVec2, Vec3};
+use bevy::prelude::Transform;
+use bevy_game::config::map::{NODE_SIZE_Z, TILE_SIZE, Y_SORT_RANGE};
+use bevy_game::map::chunk::chunk_origin;
+use bevy_game::map::generate::PROPS_LAYER;
+use bevy_game::rendering::{YSort, YSortOrigin, y_sort_local_offset, y_sort_offset};
+
+/// 玩家位于出生区块时的排序原点
+fn spawn_origin() -> YSortOrigin {
+    YSortOrigin(chunk_origin(IVec2::ZERO).y)
+}
+
+#[test]
+fn depth_decreases_across_chunk_borders() {
+    // 从出生区块下方两个区块一直采样到上方两个区块
+    let bottom = chunk_origin(IVec2::new(0, -2)).y;
+    let top = chunk_origin(IVec2::new(0, 3)).y;
+    let mut y = bottom;
+    let mut previous = y_sort_offset(y, spawn_origin());
+    while y < top {
+        y += TILE_SIZE / 4.0;
+        let offset = y_sort_offset(y, spawn_origin());
+        assert!(offset < previous, "depth must decrease at y = {y}");
+        previous = offset;
+    }
+}
+
+#[test]
+fn chunk_border_has_one_depth() {
+    // 区块的上边缘与上方区块的下边缘是同一个世界 y 坐标
+    let border = chunk_origin(IVec2::new(0, 1)).y;
+    let y_sort = YSort::new(1);
+    let origin = spawn_origin();
+    let transform = Transform::from_translation(Vec3::new(0.0, border, 0.0));
+    assert_eq!(
+        y_sort.depth(&transform, origin),
+        NODE_SIZE_Z + y_sort_offset(border, origin)
+    );
+
+    let just_below = Transform::from_translation(Vec3::new(0.0, border - 1.0, 0.0));
+    let just_above = Transform::from_translation(Vec3::new(0.0, border + 1.0, 0.0));
+    assert!(y_sort.depth(&just_below, origin) > y_sort.depth(&transform, origin));
+    assert!(y_sort.depth(&transform, origin) > y_sort.depth(&just_above, origin));
+}
+
+#[test]
+fn tiles_match_world_depth() {
+    // 区块实体的深度加上瓦片相对区块的深度，等于直接按世界 y 坐标计算的深度
+    let origin = spawn_origin();
+    for coord in [IVec2::new(0, -2), IVec2::ZERO, IVec2::new(3, 2)] {
+        let chunk_y = chunk_origin(coord).y;
+        for dy in [0.0, TILE_SIZE * 3.0, TILE_SIZE * 17.0] {
+            let tile = y_sort_offset(chunk_y, origin) + y_sort_local_offset(dy);
+            let world = y_sort_offset(chunk_y + dy, origin);
+            assert!((tile - world).abs() < 1.0e-5, "chunk {coord}, dy = {dy}");
+        }
+    }
+}
+
+#[test]
+fn far_end_pixels_have_distinct_depths() {
+    // 玩家远离世界原点时，排序范围两端相距一个像素的位置仍然可以区分
+    let origin = YSortOrigin(chunk_origin(IVec2::new(0, 5000)).y);
+    let y_sort = YSort::new(PROPS_LAYER);
+    for edge in [-0.5, 0.5] {
+        let y = origin.0 + edge * Y_SORT_RANGE - edge.signum() * 2.0;
+        let lower = Transform::from_translation(Vec3::new(0.0, y - 0.5, 0.0));
+        let upper = Transform::from_translation(Vec3::new(0.0, y + 0.5, 0.0));
+        assert!(
+            y_sort.depth(&lower, origin) > y_sort.depth(&upper, origin),
+            "y = {y}"
+        );
+    }
+}
+
+#[test]
+fn offset_stays_within_one_layer() {
+    for y in [-1.0e9, -1.0e4, 0.0, 1.0e4, 1.0e9] {
+        let offset = y_sort_offset(y, spawn_origin());
+        assert!((0.0..=NODE_SIZE_Z).contains(&offset));
+    }
+}